@@ -0,0 +1,295 @@
+//! Animated GIF, APNG and animated WebP, played in photo cells.
+//!
+//! Every frame is decoded up front, composited and scaled like a still photo, and
+//! shown in turn by a timer while the cell plays. The timer wakes only when the next
+//! frame is due. Playback mirrors a video cell's: pause, loop and seek by time, with
+//! the position kept in seconds.
+
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use iced::widget::image::Handle;
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, Frames, ImageFormat, ImageReader};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::transform::Transform;
+
+/// Frame delays shorter than this are shown for `DEFAULT_DELAY_SECS` instead, as
+/// browsers do; many GIFs store 0 or 10 ms and rely on that.
+const MIN_DELAY_SECS: f64 = 0.02;
+const DEFAULT_DELAY_SECS: f64 = 0.1;
+/// Decoded frames stop being added past this many RGBA bytes; longer animations are
+/// cut short rather than exhausting memory.
+const MAX_FRAME_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Frames of an animation decoded for display.
+pub struct DecodedFrames {
+    pub handles: Vec<Handle>,
+    /// Start of each frame in seconds, followed by the total duration.
+    pub starts: Vec<f64>,
+    pub size: (u32, u32),      // Size of each frame
+    pub full_size: (u32, u32), // Original size (after the transform)
+}
+
+/// Decode every frame of an animated GIF, PNG or WebP, with the manual `transform`
+/// applied and at most `max_dimension` on the longest side (None keeps full size).
+/// Returns None for files that hold a single image, which load as still photos.
+pub fn decode(
+    path: &Path,
+    max_dimension: Option<u32>,
+    transform: &Transform,
+) -> Result<Option<DecodedFrames>, Box<dyn std::error::Error>> {
+    let format = ImageReader::open(path)?.with_guessed_format()?.format();
+    let reader = || File::open(path).map(BufReader::new);
+    let frames: Frames = match format {
+        Some(ImageFormat::Gif) => GifDecoder::new(reader()?)?.into_frames(),
+        Some(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(reader()?)?;
+            if !decoder.is_apng()? {
+                return Ok(None);
+            }
+            decoder.apng()?.into_frames()
+        }
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(reader()?)?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.into_frames()
+        }
+        _ => return Ok(None),
+    };
+
+    let mut handles = Vec::new();
+    let mut starts = vec![0.0];
+    let mut size = (0, 0);
+    let mut full_size = (0, 0);
+    let mut bytes = 0;
+    for frame in frames {
+        let frame = frame?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let delay = numer as f64 / denom.max(1) as f64 / 1000.0;
+        let delay = if delay < MIN_DELAY_SECS {
+            DEFAULT_DELAY_SECS
+        } else {
+            delay
+        };
+
+        let mut img = transform.apply_to_image(DynamicImage::ImageRgba8(frame.into_buffer()));
+        full_size = (img.width(), img.height());
+        if let Some(max) = max_dimension {
+            if full_size.0.max(full_size.1) > max {
+                img = img.resize(max, max, FilterType::Triangle);
+            }
+        }
+        let rgba = img.to_rgba8();
+        size = rgba.dimensions();
+        bytes += crate::photos::rgba_bytes(size);
+        if bytes > MAX_FRAME_BYTES && !handles.is_empty() {
+            log::warn!(
+                "Animation {} cut at {} frames to stay within memory",
+                path.display(),
+                handles.len()
+            );
+            break;
+        }
+        handles.push(Handle::from_rgba(size.0, size.1, rgba.into_raw()));
+        starts.push(starts[starts.len() - 1] + delay);
+    }
+
+    if handles.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(DecodedFrames {
+        handles,
+        starts,
+        size,
+        full_size,
+    }))
+}
+
+/// Frames and playback state of an animated photo.
+pub struct Animation {
+    /// Empty while the photo's pixels are evicted.
+    pub frames: Vec<Handle>,
+    starts: Vec<f64>,
+    pub position: f64,
+    pub paused: bool,
+    pub looping: bool,
+    pub dragging: bool,
+    /// When playback last advanced, None while it is stopped.
+    clock: Option<Instant>,
+}
+
+impl Animation {
+    pub fn new(frames: DecodedFrames) -> Self {
+        Animation {
+            frames: frames.handles,
+            starts: frames.starts,
+            position: 0.0,
+            paused: false,
+            looping: true,
+            dragging: false,
+            clock: None,
+        }
+    }
+
+    /// Take newly decoded frames (after a reload), keeping the playback state.
+    pub fn replace_frames(&mut self, frames: DecodedFrames) {
+        self.frames = frames.handles;
+        self.starts = frames.starts;
+        self.position = self.position.min(self.duration());
+        self.clock = None;
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.starts.last().copied().unwrap_or(0.0)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    /// Index of the frame shown at the current position.
+    pub fn frame_index(&self) -> usize {
+        self.starts
+            .partition_point(|&s| s <= self.position)
+            .saturating_sub(1)
+            .min(self.frame_count().saturating_sub(1))
+    }
+
+    /// Frame shown at the current position, if the frames are resident.
+    pub fn current_frame(&self) -> Option<&Handle> {
+        self.frames.get(self.frame_index())
+    }
+
+    /// Whether the timer has anything to advance.
+    pub fn is_playing(&self) -> bool {
+        !self.paused && !self.dragging && !self.frames.is_empty()
+    }
+
+    /// Pause or resume; resuming at the end of a played-out animation restarts it.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.clock = None;
+        if !self.paused && self.position >= self.duration() {
+            self.position = 0.0;
+        }
+    }
+
+    pub fn seek(&mut self, position: f64) {
+        self.position = position.clamp(0.0, self.duration());
+        self.clock = None;
+    }
+
+    /// When the next frame is due, None while stopped. Without a running clock the
+    /// next tick is due at once, to start it.
+    pub fn next_due(&self) -> Option<Instant> {
+        if !self.is_playing() {
+            return None;
+        }
+        let Some(clock) = self.clock else {
+            return Some(Instant::now());
+        };
+        let next_start = self
+            .starts
+            .get(self.frame_index() + 1)
+            .copied()
+            .unwrap_or(self.duration());
+        Some(clock + Duration::from_secs_f64((next_start - self.position).max(0.0)))
+    }
+
+    /// Move the position on by the time since the last tick. Returns whether another
+    /// frame is now due.
+    pub fn advance(&mut self, now: Instant) -> bool {
+        if !self.is_playing() {
+            self.clock = None;
+            return false;
+        }
+        let Some(last) = self.clock.replace(now) else {
+            return false;
+        };
+        let before = self.frame_index();
+        let duration = self.duration();
+        self.position += now.duration_since(last).as_secs_f64();
+        if self.position >= duration {
+            if self.looping && duration > 0.0 {
+                self.position %= duration;
+            } else {
+                self.position = duration;
+                self.paused = true;
+            }
+        }
+        self.frame_index() != before
+    }
+}
+
+/// Subscription stream yielding the time once `due` is reached.
+pub fn tick_at(due: &Instant) -> BoxStream<'static, Instant> {
+    let due = tokio::time::Instant::from_std(*due);
+    futures::stream::once(async move {
+        tokio::time::sleep_until(due).await;
+        Instant::now()
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three one-pixel frames of 0.1 s each.
+    fn animation() -> Animation {
+        let frame = || Handle::from_rgba(1, 1, vec![0u8; 4]);
+        Animation::new(DecodedFrames {
+            handles: vec![frame(), frame(), frame()],
+            starts: vec![0.0, 0.1, 0.2, 0.3],
+            size: (1, 1),
+            full_size: (1, 1),
+        })
+    }
+
+    #[test]
+    fn frame_follows_position() {
+        let mut anim = animation();
+        assert_eq!(anim.frame_count(), 3);
+        anim.seek(0.15);
+        assert_eq!(anim.frame_index(), 1);
+        anim.seek(1.0);
+        assert_eq!(anim.position, 0.3);
+        assert_eq!(anim.frame_index(), 2);
+    }
+
+    #[test]
+    fn next_tick_is_due_with_the_next_frame() {
+        let mut anim = animation();
+        let start = Instant::now();
+        assert!(anim.next_due().is_some());
+        assert!(!anim.advance(start));
+        assert_eq!(anim.next_due(), Some(start + Duration::from_secs_f64(0.1)));
+        anim.toggle_pause();
+        assert_eq!(anim.next_due(), None);
+    }
+
+    #[test]
+    fn playback_loops_or_stops_at_the_end() {
+        let mut anim = animation();
+        let start = Instant::now();
+        anim.advance(start);
+        anim.advance(start + Duration::from_millis(350));
+        assert!((anim.position - 0.05).abs() < 1e-9);
+        assert_eq!(anim.frame_index(), 0);
+
+        anim.looping = false;
+        anim.advance(start + Duration::from_millis(700));
+        assert!(anim.paused);
+        assert_eq!(anim.position, 0.3);
+        assert_eq!(anim.next_due(), None);
+    }
+}
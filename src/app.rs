@@ -1,13 +1,56 @@
 use iced::event;
 use iced::time;
-use iced::{Element, Subscription};
+use iced::{Element, Subscription, Task};
+use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
 
+use crate::animation::Animation;
+use crate::blend;
+use crate::caption;
+use crate::chapters;
+use crate::color::ColorAdjust;
+use crate::events::WorkerEvent;
+use crate::exposure::{self, ExposureAids};
+use crate::fit::FitMode;
+use crate::letterbox::{self, Letterbox};
 use crate::loader;
+use crate::markers;
 use crate::message::Message;
-use crate::state::{App, MediaItem};
-use crate::sync::{synchronized_seek, synchronized_set_paused};
+use crate::photos;
+use crate::quality;
+use crate::scaling;
+use crate::scopes::{self, Scope, ScopeView};
+use crate::settings::{FramerateMode, HiddenPlayback};
+use crate::state::{
+    App, Compare, CompareMode, CompareSide, CropDraw, HiddenState, MarkerEdit, MediaItem, PanDrag,
+    PendingMedia, ResumeState, TimecodeEntry, WipeAxis,
+};
+use crate::stills;
+use crate::subtitles::{self, SubtitleSource};
+use crate::sync::{synchronized_group_set_paused, synchronized_seek, synchronized_set_paused};
+use crate::thumbnails;
+use crate::tracks::TrackKind;
+use crate::transform::{CropRect, Transform};
 use crate::ui;
+use crate::zoom::{self, ZoomView};
+
+/// Playback drift between compared videos that triggers a resync.
+const COMPARE_MAX_DRIFT_SECS: f64 = 0.1;
+
+/// Handle for a media file's cached thumbnail, if there is one.
+
+/// Seek a paused video in place so its current frame passes the appsink probes again,
+/// picking up a changed frame tap or exposure aids.
+fn refresh_paused_frame(vid: &crate::state::VideoInstance) {
+    if vid.video.paused() {
+        let _ = synchronized_seek(
+            vid.id,
+            &vid.video,
+            Duration::from_secs_f64(vid.position),
+            true,
+        );
+    }
+}
 
 impl App {
     /// Returns the ID of the keyboard shortcut target:
@@ -17,26 +60,1922 @@ impl App {
         if let Some(item) = self.media.iter().find(|m| m.is_fullscreen()) {
             return Some(item.id());
         }
-        self.media.iter().find_map(|m| match m {
-            MediaItem::Video(v) if v.hovered => Some(v.id),
-            MediaItem::Photo(p) if p.hovered => Some(p.id),
-            _ => None,
-        })
+        self.media.iter().find_map(|m| match m {
+            MediaItem::Video(v) if v.hovered => Some(v.id),
+            MediaItem::Photo(p) if p.hovered => Some(p.id),
+            _ => None,
+        })
+    }
+
+    /// Find a video by ID.
+    fn find_video_mut(&mut self, id: usize) -> Option<&mut crate::state::VideoInstance> {
+        self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Video(v) if v.id == id => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Set the grid column count (clamped to 1..=10).
+    fn set_grid_columns(&mut self, columns: usize) {
+        self.grid_columns = columns.clamp(1, 10);
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Toggle fullscreen for a media item.
+    fn toggle_fullscreen(&mut self, id: usize) {
+        if let Some(item) = self.media.iter_mut().find(|m| m.id() == id) {
+            match item {
+                MediaItem::Video(v) => v.fullscreen = !v.fullscreen,
+                MediaItem::Photo(p) => p.fullscreen = !p.fullscreen,
+                MediaItem::Pending(_) => {}
+            }
+        }
+        self.apply_hidden_playback();
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Items that take over the whole window: the compared pair, or the fullscreen item.
+    /// None while the grid is shown.
+    fn focused_ids(&self) -> Option<Vec<usize>> {
+        if let Some(compare) = &self.compare {
+            return Some(vec![compare.a, compare.b]);
+        }
+        self.media
+            .iter()
+            .find(|m| m.is_fullscreen())
+            .map(|m| vec![m.id()])
+    }
+
+    /// Whether a media item is one of the compared pair.
+    fn in_compare(&self, id: usize) -> bool {
+        self.compare
+            .as_ref()
+            .is_some_and(|c| c.a == id || c.b == id)
+    }
+
+    /// Videos of the compared pair, which seek and pause together.
+    fn compare_videos(&self) -> Vec<(usize, &iced_video_player::Video)> {
+        let Some(compare) = &self.compare else {
+            return Vec::new();
+        };
+        self.media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Video(v) if v.id == compare.a || v.id == compare.b => {
+                    Some((v.id, &v.video))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Add a cell to the compare selection, or remove it. Keeps the two most recent.
+    fn toggle_select(&mut self, id: usize) {
+        if let Some(index) = self.selection.iter().position(|&s| s == id) {
+            self.selection.remove(index);
+        } else {
+            self.selection.push(id);
+            if self.selection.len() > 2 {
+                self.selection.remove(0);
+            }
+        }
+    }
+
+    /// Enter compare mode with the two selected cells, or leave it.
+    fn toggle_compare(&mut self) {
+        if self.compare.take().is_some() {
+            // Dropping the analysis cancels it
+            self.quality = None;
+            log::info!("Compare mode off");
+            // Release the pair if it was held loaded from another page
+            self.sync_page();
+        } else {
+            let &[a, b] = self.selection.as_slice() else {
+                self.status = "Right-click two cells to select them for comparison".to_string();
+                return;
+            };
+            // The pair takes over the window, so neither stays fullscreen underneath
+            for item in &mut self.media {
+                match item {
+                    MediaItem::Video(v) => v.fullscreen = false,
+                    MediaItem::Photo(p) => p.fullscreen = false,
+                    MediaItem::Pending(_) => {}
+                }
+            }
+            self.compare = Some(Compare {
+                a,
+                b,
+                wipe: 0.5,
+                axis: WipeAxis::Vertical,
+                flicker: None,
+                dragging: false,
+                mode: CompareMode::Wipe,
+                gain: 8.0,
+                opacity: 0.5,
+                blended: None,
+                blend_pending: false,
+                blend_generation: 0,
+                blend_stale: false,
+            });
+            log::info!("Compare mode: a={}, b={}", a, b);
+            self.sync_page();
+            self.lock_compare_timeline();
+        }
+        self.sync_frame_taps();
+        self.apply_hidden_playback();
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Switch the compare view between wipe, difference and onion skin.
+    fn cycle_compare_mode(&mut self) {
+        let Some(compare) = &mut self.compare else {
+            return;
+        };
+        compare.mode = compare.mode.next();
+        compare.blended = None;
+        // A blend still running was for the old mode; its result is dropped on arrival
+        compare.blend_pending = false;
+        log::info!("Compare view: {}", compare.mode.as_str());
+        let blending = compare.blend_mode().is_some();
+        self.sync_frame_taps();
+        if blending {
+            // Seeking in place pushes a fresh frame through the taps even while paused
+            self.lock_compare_timeline();
+            self.request_blend();
+        }
+    }
+
+    /// Start a PSNR/SSIM analysis of the compared pair, replacing any earlier one.
+    fn start_quality(&mut self) {
+        let Some(compare) = &self.compare else {
+            return;
+        };
+        let path = |id: usize| match self.media.iter().find(|m| m.id() == id)? {
+            MediaItem::Video(vid) => Some(vid.path.clone()),
+            _ => None,
+        };
+        let (Some(a), Some(b)) = (path(compare.a), path(compare.b)) else {
+            self.status = "Quality analysis needs two videos".to_string();
+            return;
+        };
+        let job = quality::Job {
+            a,
+            b,
+            offset: self.sync_offset(compare.b) - self.sync_offset(compare.a),
+        };
+        self.quality = Some(quality::Analysis::start(job, self.events.tx.clone()));
+    }
+
+    /// Write the finished analysis as CSV and JSON.
+    fn export_quality(&mut self) {
+        let Some(analysis) = &self.quality else {
+            return;
+        };
+        if analysis.frames.is_empty() {
+            self.status = "No quality results to export".to_string();
+            return;
+        }
+        let Some(base) = rfd::FileDialog::new().set_file_name("quality").save_file() else {
+            return;
+        };
+        self.status = match quality::export(&base, &analysis.job, &analysis.frames) {
+            Ok(()) => {
+                log::info!("Exported quality results to {}", base.display());
+                format!("Exported quality results to {}.csv/.json", base.display())
+            }
+            Err(e) => {
+                log::error!("Quality export failed: {}", e);
+                format!("Quality export failed: {}", e)
+            }
+        };
+    }
+
+    /// Keep decoded frames of videos only while a blend view or a scope needs them.
+    fn sync_frame_taps(&mut self) {
+        let tapped = self
+            .compare
+            .as_ref()
+            .filter(|c| c.blend_mode().is_some())
+            .map(|c| (c.a, c.b));
+        for item in &self.media {
+            if let MediaItem::Video(vid) = item {
+                let enabled =
+                    tapped.is_some_and(|(a, b)| vid.id == a || vid.id == b) || vid.scope.is_some();
+                vid.pipeline.frame_tap.set_enabled(enabled);
+            }
+        }
+    }
+
+    /// Blend the compared pair's latest frames on the worker. While a blend is running,
+    /// the request is remembered and issued once it finishes.
+    fn request_blend(&mut self) {
+        let Some(compare) = &self.compare else {
+            return;
+        };
+        let Some(mode) = compare.blend_mode() else {
+            return;
+        };
+        if compare.blend_pending {
+            if let Some(compare) = &mut self.compare {
+                compare.blend_stale = true;
+            }
+            return;
+        }
+        let (Some(a), Some(b)) = (self.latest_frame(compare.a), self.latest_frame(compare.b))
+        else {
+            return;
+        };
+        let generation = self.blender.request(blend::Job { a, b, mode });
+        if let Some(compare) = &mut self.compare {
+            compare.blend_pending = true;
+            compare.blend_generation = generation;
+            compare.blend_stale = false;
+        }
+    }
+
+    /// Latest frame of an item: the tapped frame of a video, or a photo's pixels.
+    fn latest_frame(&self, id: usize) -> Option<blend::Frame> {
+        match self.media.iter().find(|m| m.id() == id)? {
+            MediaItem::Video(vid) => vid.pipeline.frame_tap.latest(),
+            MediaItem::Photo(photo) => photo.source_handle().and_then(blend::Frame::from_handle),
+            MediaItem::Pending(_) => None,
+        }
+    }
+
+    /// Show the next scope of a cell, or none after the last.
+    fn cycle_scope(&mut self, id: usize) {
+        let Some(view) = self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Video(v) if v.id == id => Some(&mut v.scope),
+            MediaItem::Photo(p) if p.id == id => Some(&mut p.scope),
+            _ => None,
+        }) else {
+            return;
+        };
+        let next = Scope::cycle(view.as_ref().map(|v| v.scope));
+        *view = next.map(ScopeView::new);
+        self.status = match next {
+            Some(scope) => format!("Scope: {}", scope.as_str()),
+            None => "Scope off".to_string(),
+        };
+        self.sync_frame_taps();
+        // The newly enabled tap only fills once a frame arrives
+        if let Some(vid) = self.find_video_mut(id) {
+            refresh_paused_frame(vid);
+        }
+        self.request_scope(id);
+    }
+
+    /// Change a cell's exposure aids. Videos draw them on the next frame; photos
+    /// are drawn on a background thread.
+    fn update_aids(&mut self, id: usize, change: impl FnOnce(&mut ExposureAids)) {
+        let Some(item) = self.media.iter_mut().find(|m| m.id() == id) else {
+            return;
+        };
+        let aids = match item {
+            MediaItem::Video(vid) => {
+                change(&mut vid.aids);
+                vid.pipeline.exposure.set(vid.aids);
+                refresh_paused_frame(vid);
+                vid.aids
+            }
+            MediaItem::Photo(photo) => {
+                change(&mut photo.aids);
+                photo.aid_handle = None;
+                photo.aids
+            }
+            MediaItem::Pending(_) => return,
+        };
+        self.request_photo_aids(id);
+        self.status = match (aids.zebras, aids.false_color) {
+            (false, false) => "Exposure aids off".to_string(),
+            (true, false) => format!("Zebras above {} IRE", aids.zebra_ire),
+            (false, true) => "False color".to_string(),
+            (true, true) => format!("False color, zebras above {} IRE", aids.zebra_ire),
+        };
+    }
+
+    /// Draw a photo's exposure aids from its current pixels, if any aid is on.
+    fn request_photo_aids(&self, id: usize) {
+        let Some(photo) = self.media.iter().find_map(|m| match m {
+            MediaItem::Photo(p) if p.id == id && p.aids.is_active() => Some(p),
+            _ => None,
+        }) else {
+            return;
+        };
+        if let Some(handle) = photo.source_handle() {
+            exposure::apply_to_photo_async(id, handle.clone(), photo.aids, self.events.tx.clone());
+        }
+    }
+
+    /// Step the zebra threshold for every cell.
+    fn cycle_zebra_level(&mut self) {
+        let ire = ExposureAids::next_zebra_level(self.settings.zebra_ire);
+        self.settings.zebra_ire = ire;
+        self.settings.save();
+        self.status = format!("Zebras above {} IRE", ire);
+        let mut redraw = Vec::new();
+        for item in &mut self.media {
+            match item {
+                MediaItem::Video(vid) => {
+                    vid.aids.zebra_ire = ire;
+                    vid.pipeline.exposure.set(vid.aids);
+                    if vid.aids.zebras {
+                        refresh_paused_frame(vid);
+                    }
+                }
+                MediaItem::Photo(photo) => {
+                    photo.aids.zebra_ire = ire;
+                    if photo.aids.zebras {
+                        redraw.push(photo.id);
+                    }
+                }
+                MediaItem::Pending(_) => {}
+            }
+        }
+        for id in redraw {
+            self.request_photo_aids(id);
+        }
+    }
+
+    /// Switch a video to its next subtitle source: each sidecar, each embedded track,
+    /// then none.
+    fn cycle_subtitles(&mut self, id: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        let embedded = vid.pipeline.track_count(TrackKind::Text);
+        let source = vid.subtitles.next(vid.sidecars.len(), embedded);
+        self.set_subtitles(id, source);
+    }
+
+    /// Show a video's subtitles from `source`. Embedded tracks are re-read from the
+    /// current position, since cues already demuxed from them were not kept.
+    fn set_subtitles(&mut self, id: usize, source: SubtitleSource) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        vid.subtitles = source;
+        vid.embedded_cues.clear();
+        if let SubtitleSource::Embedded(track) = source {
+            vid.pipeline.select_track(TrackKind::Text, track);
+            let position = Duration::from_secs_f64(vid.position);
+            let _ = synchronized_seek(id, &vid.video, position, true);
+        }
+        self.status = format!("Subtitles: {}", vid.subtitle_label());
+    }
+
+    /// Open or close a video's track picker, reading its tracks when it opens.
+    fn toggle_tracks(&mut self, id: usize) {
+        if let Some(vid) = self.find_video_mut(id) {
+            vid.tracks = match vid.tracks {
+                Some(_) => None,
+                None => Some(vid.pipeline.read_tracks()),
+            };
+        }
+    }
+
+    /// Play another video or audio track of a video.
+    fn select_track(&mut self, id: usize, kind: TrackKind, index: i32) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        vid.pipeline.select_track(kind, index);
+        if kind == TrackKind::Video {
+            refresh_paused_frame(vid);
+        }
+        if vid.tracks.is_some() {
+            vid.tracks = Some(vid.pipeline.read_tracks());
+        }
+        self.status = format!("{} track {}", kind.title(), index + 1);
+    }
+
+    /// Show a video's subtitles later (or earlier, for negative `delta`).
+    fn adjust_subtitle_delay(&mut self, id: usize, delta: f64) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        // Rounded so repeated steps don't drift off the tenth
+        vid.subtitle_delay = ((vid.subtitle_delay + delta) * 1000.0).round() / 1000.0;
+        self.status = format!("Subtitle delay: {:+.1}s", vid.subtitle_delay);
+    }
+
+    /// Show or hide a cell's caption.
+    fn toggle_caption(&mut self, id: usize) {
+        match self.media.iter_mut().find(|m| m.id() == id) {
+            Some(MediaItem::Video(vid)) => vid.show_caption = !vid.show_caption,
+            Some(MediaItem::Photo(photo)) => photo.show_caption = !photo.show_caption,
+            _ => {}
+        }
+    }
+
+    /// Seconds a video runs ahead of the timeline it shares with its compare partner.
+    /// Photos and unloaded items have none.
+    fn sync_offset(&self, id: usize) -> f64 {
+        self.media
+            .iter()
+            .find_map(|m| match m {
+                MediaItem::Video(v) if v.id == id => Some(v.sync_offset),
+                _ => None,
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Move the compared B video's sync offset by `frames` of its own frames, and
+    /// bring the pair back together at the new offset.
+    fn adjust_sync_offset(&mut self, frames: i64) {
+        let Some(b) = self.compare.as_ref().map(|c| c.b) else {
+            return;
+        };
+        let Some(vid) = self.find_video_mut(b) else {
+            self.status = "Sync offset needs B to be a video".to_string();
+            return;
+        };
+        // Whole frames, so repeated steps don't drift between them
+        let offset = (vid.sync_offset * vid.timebase.fps).round() as i64 + frames;
+        vid.sync_offset = offset as f64 / vid.timebase.fps;
+        self.status = format!(
+            "Sync offset: B {}{}",
+            if offset < 0 { "-" } else { "+" },
+            crate::timecode::frames_to_timecode(offset.unsigned_abs(), vid.timebase.rate)
+        );
+        self.lock_compare_timeline();
+    }
+
+    /// Seek both compared videos to `timeline` seconds on their shared timeline, each
+    /// shifted by its own sync offset, leaving them paused or playing together.
+    fn seek_compare_timeline(&mut self, timeline: f64, paused: bool) {
+        let videos = self.compare_videos();
+        let targets: Vec<(usize, f64)> = videos
+            .iter()
+            .map(|&(id, _)| (id, (timeline + self.sync_offset(id)).max(0.0)))
+            .collect();
+        synchronized_group_set_paused(&videos, true);
+        for (&(id, video), &(_, target)) in videos.iter().zip(&targets) {
+            let _ = synchronized_seek(id, video, Duration::from_secs_f64(target), true);
+        }
+        if !paused {
+            synchronized_group_set_paused(&videos, false);
+        }
+        for (id, target) in targets {
+            if let Some(vid) = self.find_video_mut(id) {
+                vid.position = target;
+            }
+        }
+    }
+
+    /// Save the frame a video is showing as a full-resolution PNG, with its caption
+    /// burned in while the caption is shown.
+    fn export_still(&mut self, id: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        let (path, position) = (vid.path.clone(), vid.position);
+        let file_name = format!(
+            "{}_{}.png",
+            path.file_stem().unwrap_or_default().to_string_lossy(),
+            vid.timecode_at(position).replace([':', ';'], "-")
+        );
+        let lines = vid.show_caption.then(|| caption::video_lines(vid));
+        let burn_in = lines.map(|lines| stills::BurnIn {
+            lines,
+            position: self.settings.caption_position,
+            size: self.settings.caption_size,
+        });
+
+        let Some(out) = rfd::FileDialog::new().set_file_name(&file_name).save_file() else {
+            return;
+        };
+        self.status = "Exporting still...".to_string();
+        stills::export_async(path, position, burn_in, out, self.events.tx.clone());
+    }
+
+    /// Render a cell's scope on the worker from its latest frame. Videos are limited
+    /// to one request per `SCOPE_INTERVAL`; a request made while one is running is
+    /// issued once it finishes, and one made too soon when the interval runs out.
+    fn request_scope(&mut self, id: usize) {
+        let frame = self.latest_frame(id);
+        let Some((view, is_moving)) = self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Video(v) if v.id == id => Some((v.scope.as_mut()?, true)),
+            MediaItem::Photo(p) if p.id == id => Some((p.scope.as_mut()?, p.animation.is_some())),
+            _ => None,
+        }) else {
+            return;
+        };
+        if view.pending {
+            view.stale = true;
+            return;
+        }
+        if is_moving && view.requested.elapsed() < scopes::SCOPE_INTERVAL {
+            view.stale = true;
+            return;
+        }
+        let Some(frame) = frame else {
+            return;
+        };
+        view.pending = true;
+        view.stale = false;
+        view.requested = Instant::now();
+        self.scopes.request(scopes::Job {
+            id,
+            frame,
+            scope: view.scope,
+        });
+    }
+
+    /// Put both compared videos at A's point on their shared timeline, and A's pause
+    /// state.
+    fn lock_compare_timeline(&mut self) {
+        let Some(a) = self.compare.as_ref().map(|c| c.a) else {
+            return;
+        };
+        let Some((timeline, paused)) = self
+            .find_video_mut(a)
+            .map(|vid| (vid.position - vid.sync_offset, vid.video.paused()))
+        else {
+            return;
+        };
+        if self.compare_videos().len() < 2 {
+            return;
+        }
+        self.seek_compare_timeline(timeline, paused);
+    }
+
+    /// Compare mode keys. Returns whether the key was handled.
+    fn handle_compare_key(&mut self, key: &str) -> bool {
+        if key == "c" {
+            self.toggle_compare();
+            return true;
+        }
+        let Some(compare) = &mut self.compare else {
+            return false;
+        };
+        match key {
+            "x" => {
+                compare.flicker = Some(match compare.flicker {
+                    Some(CompareSide::A) => CompareSide::B,
+                    _ => CompareSide::A,
+                });
+            }
+            "v" => {
+                self.cycle_compare_mode();
+                return true;
+            }
+            "q" => {
+                self.start_quality();
+                return true;
+            }
+            "<" => {
+                self.adjust_sync_offset(-1);
+                return true;
+            }
+            ">" => {
+                self.adjust_sync_offset(1);
+                return true;
+            }
+            "w" => {
+                if compare.flicker.take().is_none() {
+                    compare.axis = match compare.axis {
+                        WipeAxis::Vertical => WipeAxis::Horizontal,
+                        WipeAxis::Horizontal => WipeAxis::Vertical,
+                    };
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether a media item is currently drawn: a focused item, or the visible page.
+    fn is_on_screen(&self, id: usize) -> bool {
+        match self.focused_ids() {
+            Some(ids) => ids.contains(&id),
+            None => self.media[self.page_range()].iter().any(|m| m.id() == id),
+        }
+    }
+
+    /// Number of grid pages (at least one, even when empty).
+    pub fn page_count(&self) -> usize {
+        self.media.len().div_ceil(self.settings.page_size).max(1)
+    }
+
+    /// Indices into `media` of the items on the visible page.
+    pub fn page_range(&self) -> std::ops::Range<usize> {
+        let size = self.settings.page_size;
+        let start = (self.page * size).min(self.media.len());
+        start..(start + size).min(self.media.len())
+    }
+
+    /// Switch to another grid page (clamped). Ignored while an item is fullscreen.
+    fn set_page(&mut self, page: usize) {
+        if self.media.iter().any(|m| m.is_fullscreen()) {
+            return;
+        }
+        let page = page.min(self.page_count() - 1);
+        if page != self.page {
+            log::info!("Page: {} -> {}", self.page, page);
+            self.page = page;
+            self.sync_page();
+        }
+    }
+
+    /// Start loading items on the visible page and release videos on other pages,
+    /// keeping their position and markers so they resume where they left off.
+    fn sync_page(&mut self) {
+        self.page = self.page.min(self.page_count() - 1);
+        let range = self.page_range();
+        let focused = self.focused_ids().unwrap_or_default();
+        let mut loads = Vec::new();
+
+        for (index, item) in self.media.iter_mut().enumerate() {
+            // The compared pair stays loaded wherever it sits in the grid
+            let wanted = range.contains(&index) || focused.contains(&item.id());
+            match item {
+                MediaItem::Pending(pending) if !wanted => pending.error = None,
+                MediaItem::Pending(pending) if !pending.loading && pending.error.is_none() => {
+                    pending.loading = true;
+                    loads.push((pending.id, pending.path.clone(), pending.software_decode));
+                }
+                MediaItem::Video(vid) if !wanted && !vid.fullscreen => {
+                    log::debug!(
+                        "Video paged out: video_id={}, position={:.2}s",
+                        vid.id,
+                        vid.position
+                    );
+                    *item = MediaItem::Pending(Self::unload_video(vid, &self.thumbnails));
+                }
+                _ => {}
+            }
+        }
+
+        for (id, path, software_decoding) in loads {
+            self.loading_count += 1;
+            let transform = self.media_prefs.get(&path).transform;
+            loader::load_media_async(
+                self.load_tx.clone(),
+                self.events.tx.clone(),
+                self.events.positions.clone(),
+                path,
+                id,
+                loader::LoadOptions {
+                    software_decoding,
+                    ..self.load_options()
+                },
+                transform,
+            );
+        }
+
+        let editing_id = self.marker_edit.as_ref().map(|e| e.video_id);
+        if editing_id.is_some_and(|id| self.find_video_mut(id).is_none()) {
+            self.marker_edit = None;
+        }
+        self.sync_frame_taps();
+        self.apply_hidden_playback();
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Pending item that reloads a video where it left off, keeping its markers.
+    fn unload_video(
+        vid: &mut crate::state::VideoInstance,
+        thumbnails: &thumbnails::Generator,
+    ) -> PendingMedia {
+        crate::gst_logger::log_video_destroyed(vid.id);
+        let mut pending = PendingMedia::new(vid.id, vid.path.clone());
+        thumbnails.request(vid.id, vid.path.clone());
+        pending.software_decode = vid.software_decode;
+        pending.resume = Some(ResumeState {
+            position: vid.position,
+            duration: vid.duration,
+            timebase: vid.timebase,
+            start_timecode: vid.start_timecode,
+            looping: vid.video.looping(),
+            show_info: vid.show_info,
+            show_caption: vid.show_caption,
+            subtitles: vid.subtitles,
+            subtitle_delay: vid.subtitle_delay,
+            sync_offset: vid.sync_offset,
+            audio_track: vid.pipeline.current_track(TrackKind::Audio),
+            video_track: vid.pipeline.current_track(TrackKind::Video),
+            view: vid.view,
+            markers: std::mem::take(&mut vid.markers),
+            letterbox: vid.letterbox,
+        });
+        pending
+    }
+
+    /// Logical size of the cell an item is drawn in.
+    fn cell_size(&self, id: usize) -> iced::Size {
+        let window = iced::Size::new(self.window_width, self.window_height);
+        if self.focused_ids().is_some_and(|ids| ids.contains(&id)) {
+            window
+        } else {
+            scaling::grid_cell_size(window, self.page_range().len(), self.grid_columns)
+        }
+    }
+
+    /// Change an item's zoom and pan, or every item's while zoom is linked.
+    fn update_view(&mut self, id: usize, change: impl Fn(&mut ZoomView, iced::Size, (u32, u32))) {
+        let cell = self.cell_size(id);
+        let Some(item) = self.media.iter_mut().find(|m| m.id() == id) else {
+            return;
+        };
+        let Some(source) = item.source_size() else {
+            return;
+        };
+        let Some(view) = item.view_mut() else {
+            return;
+        };
+        change(view, cell, source);
+        let view = *view;
+        if self.linked_zoom {
+            for item in &mut self.media {
+                if let Some(other) = item.view_mut() {
+                    *other = view;
+                }
+            }
+        }
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Switch between independent and linked zoom. Linking copies nothing until the
+    /// next zoom or pan, which is then mirrored to every cell.
+    fn toggle_linked_zoom(&mut self) {
+        self.linked_zoom = !self.linked_zoom;
+        self.status = if self.linked_zoom {
+            "Zoom linked across cells".to_string()
+        } else {
+            "Zoom independent per cell".to_string()
+        };
+    }
+
+    /// Cycle an item's fit mode. Landing on the global default clears the override,
+    /// so the cell follows later changes to the default.
+    fn cycle_fit(&mut self, id: usize) {
+        let default_fit = self.settings.fit_mode;
+        let Some((fit, path)) = self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Video(v) if v.id == id => Some((&mut v.fit, &v.path)),
+            MediaItem::Photo(p) if p.id == id => Some((&mut p.fit, &p.path)),
+            _ => None,
+        }) else {
+            return;
+        };
+        let next = fit.unwrap_or(default_fit).next();
+        *fit = Some(next).filter(|&f| f != default_fit);
+        let saved = *fit;
+        self.media_prefs.update(path, |prefs| prefs.fit = saved);
+        log::debug!("Fit changed: id={}, fit={}", id, next.as_str());
+        self.status = format!("Fit: {}", next.as_str());
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Cycle the fit of cells without their own fit mode.
+    fn cycle_default_fit(&mut self) {
+        self.settings.fit_mode = self.settings.fit_mode.next();
+        self.settings.save();
+        self.status = format!("Default fit: {}", self.settings.fit_mode.as_str());
+        self.update_decode_sizes();
+        self.update_photo_residency();
+    }
+
+    /// Change an item's rotation, flip or crop and remember it for the file. Videos
+    /// apply it in their running pipeline; photos are decoded again.
+    fn update_transform(&mut self, id: usize, change: impl FnOnce(&mut Transform)) {
+        let Some(item) = self.media.iter_mut().find(|m| m.id() == id) else {
+            return;
+        };
+        let (transform, path) = match item {
+            MediaItem::Video(vid) => {
+                change(&mut vid.transform);
+                vid.apply_geometry();
+                vid.view = ZoomView::default();
+                (vid.transform, vid.path.clone())
+            }
+            MediaItem::Photo(photo) => {
+                change(&mut photo.transform);
+                photo.handle = None;
+                photo.full_handle = None;
+                photo.aid_handle = None;
+                if let Some(animation) = &mut photo.animation {
+                    animation.frames.clear();
+                }
+                photo.reload_pending = false;
+                photo.reload_error = None;
+                photo.view = ZoomView::default();
+                (photo.transform, photo.path.clone())
+            }
+            MediaItem::Pending(_) => return,
+        };
+        self.media_prefs
+            .update(&path, |prefs| prefs.transform = transform);
+        log::info!("Transform changed: id={}, {:?}", id, transform);
+        self.update_decode_sizes();
+        self.update_photo_residency();
+        if self.in_compare(id) {
+            self.request_blend();
+        }
+    }
+
+    /// Switch a video between cropping its detected bars and showing them, and remember
+    /// the choice for the file.
+    fn toggle_letterbox(&mut self, id: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        if !matches!(vid.letterbox, Letterbox::Bars(_)) {
+            self.status = "No letterbox bars detected".to_string();
+            return;
+        }
+        vid.keep_bars = !vid.keep_bars;
+        vid.apply_geometry();
+        vid.view = ZoomView::default();
+        let (keep_bars, path) = (vid.keep_bars, vid.path.clone());
+        self.media_prefs
+            .update(&path, |prefs| prefs.keep_bars = keep_bars);
+        self.status = if keep_bars {
+            "Letterbox bars shown".to_string()
+        } else {
+            "Letterbox bars cropped".to_string()
+        };
+        self.update_decode_sizes();
+        if self.in_compare(id) {
+            self.request_blend();
+        }
+    }
+
+    /// Push a video's color adjustments and LUT (its own, or the all-cells LUT) to its
+    /// pipeline, or neutral ones while color is bypassed.
+    fn apply_color(&mut self, id: usize) {
+        let Some(vid) = self.media.iter().find_map(|m| match m {
+            MediaItem::Video(v) if v.id == id => Some(v),
+            _ => None,
+        }) else {
+            return;
+        };
+        if self.color_bypass {
+            vid.pipeline.apply_color(&ColorAdjust::default(), None);
+            return;
+        }
+        let lut = vid
+            .lut
+            .as_ref()
+            .or(self.settings.lut.as_ref())
+            .and_then(|path| self.luts.get(path));
+        vid.pipeline.apply_color(&vid.color, lut);
+    }
+
+    /// Re-apply color to every loaded video, after the all-cells LUT or bypass changed.
+    fn apply_color_all(&mut self) {
+        let ids: Vec<usize> = self
+            .media
+            .iter()
+            .filter(|m| matches!(m, MediaItem::Video(_)))
+            .map(MediaItem::id)
+            .collect();
+        for id in ids {
+            self.apply_color(id);
+        }
+    }
+
+    /// Change a video's color adjustments while a slider moves. Saved by `commit_color`.
+    fn adjust_color(&mut self, id: usize, adjust: ColorAdjust) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        vid.color = adjust;
+        self.apply_color(id);
+    }
+
+    /// Remember a video's color adjustments for the file.
+    fn commit_color(&mut self, id: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        let (color, path) = (vid.color, vid.path.clone());
+        self.media_prefs.update(&path, |prefs| prefs.color = color);
+    }
+
+    /// Pick a `.cube` file and apply it to a video, or to all cells without their own.
+    fn load_lut(&mut self, id: Option<usize>) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("3D LUT", &["cube", "CUBE"])
+            .pick_file()
+        else {
+            return;
+        };
+        let lut = match self.luts.load(&path) {
+            Ok(lut) => lut,
+            Err(e) => {
+                log::warn!("Failed to load LUT {}: {}", path.display(), e);
+                self.status = format!("Failed to load LUT: {}", e);
+                return;
+            }
+        };
+        match id {
+            Some(id) => {
+                let Some(vid) = self.find_video_mut(id) else {
+                    return;
+                };
+                vid.lut = Some(path.clone());
+                let video_path = vid.path.clone();
+                self.media_prefs
+                    .update(&video_path, |prefs| prefs.lut = Some(path));
+                self.status = format!("LUT: {}", lut.name);
+            }
+            None => {
+                self.settings.lut = Some(path);
+                self.settings.save();
+                self.status = format!("LUT for all cells: {}", lut.name);
+            }
+        }
+        self.apply_color_all();
+    }
+
+    /// Remove a video's own LUT, or the all-cells LUT if it has none.
+    fn clear_lut(&mut self, id: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        if vid.lut.take().is_some() {
+            let path = vid.path.clone();
+            self.media_prefs.update(&path, |prefs| prefs.lut = None);
+        } else {
+            self.settings.lut = None;
+            self.settings.save();
+        }
+        self.status = "LUT cleared".to_string();
+        self.apply_color_all();
+    }
+
+    /// Turn every cell's color adjustments and LUT off or back on, for before/after.
+    fn toggle_color_bypass(&mut self) {
+        self.color_bypass = !self.color_bypass;
+        self.status = if self.color_bypass {
+            "Color: before (adjustments off)".to_string()
+        } else {
+            "Color: after (adjustments on)".to_string()
+        };
+        self.apply_color_all();
+    }
+
+    /// Record detected bars and crop them unless the file keeps its bars. Videos paged
+    /// out meanwhile keep the result for when they load again.
+    fn apply_letterbox(&mut self, id: usize, crop: Option<CropRect>) {
+        let letterbox = match crop {
+            Some(crop) => Letterbox::Bars(crop),
+            None => Letterbox::NoBars,
+        };
+        match self.media.iter_mut().find(|m| m.id() == id) {
+            Some(MediaItem::Video(vid)) => {
+                vid.letterbox = letterbox;
+                if vid.letterbox_crop().is_none() {
+                    return;
+                }
+                log::info!("Cropping letterbox: id={}, crop={:?}", id, crop);
+                vid.apply_geometry();
+                vid.view = ZoomView::default();
+            }
+            Some(MediaItem::Pending(pending)) => {
+                if let Some(resume) = pending.resume.as_mut() {
+                    resume.letterbox = letterbox;
+                }
+                return;
+            }
+            _ => return,
+        }
+        self.update_decode_sizes();
+        if self.in_compare(id) {
+            self.request_blend();
+        }
+    }
+
+    /// Size and offset of an item's media within its cell, as currently drawn.
+    fn media_layout(&self, id: usize) -> Option<(iced::Size, iced::Point)> {
+        let cell = self.cell_size(id);
+        let (view, fit, source, full_size) = match self.media.iter().find(|m| m.id() == id)? {
+            MediaItem::Video(v) => (v.view, v.fit, v.source_size, v.source_size),
+            MediaItem::Photo(p) => (p.view, p.fit, p.size, p.full_size),
+            MediaItem::Pending(_) => return None,
+        };
+        if view.is_zoomed() {
+            return Some(view.layout(cell, source));
+        }
+        // The compare view always contains both sides
+        let fit = match fit {
+            _ if self.in_compare(id) => FitMode::Contain,
+            Some(fit) => fit,
+            None => self.settings.fit_mode,
+        };
+        Some(fit.layout(cell, full_size, self.scale_factor))
+    }
+
+    /// Crop an item to the rectangle dragged between two points of its cell. Cropping
+    /// a cropped item narrows the existing crop.
+    fn commit_crop(&mut self, id: usize, start: iced::Point, end: iced::Point) {
+        let Some((size, offset)) = self.media_layout(id) else {
+            return;
+        };
+        let normalize = |p: iced::Point| {
+            iced::Point::new(
+                (p.x - offset.x) / size.width,
+                (p.y - offset.y) / size.height,
+            )
+        };
+        let Some(rect) = CropRect::from_points(normalize(start), normalize(end)) else {
+            self.status = "Crop too small, ignored".to_string();
+            return;
+        };
+        self.update_transform(id, |t| t.crop = Some(rect.within(t.crop)));
+        self.status = "Cropped (Shift+K clears the crop)".to_string();
+    }
+
+    /// Suspend videos hidden behind a fullscreen item or compare view according to the
+    /// hidden playback policy, and resume them once the grid is visible again.
+    fn apply_hidden_playback(&mut self) {
+        self.set_hidden_videos(self.focused_ids());
+    }
+
+    /// Switch to the next hidden playback policy. Videos hidden right now are resumed
+    /// and suspended again under the new policy.
+    fn cycle_hidden_playback(&mut self) {
+        self.settings.hidden_playback = self.settings.hidden_playback.next();
+        self.settings.save();
+        self.set_hidden_videos(None);
+        self.apply_hidden_playback();
+    }
+
+    /// Suspend every video outside `focused`, or resume every video with None.
+    fn set_hidden_videos(&mut self, focused: Option<Vec<usize>>) {
+        let policy = self.settings.hidden_playback;
+
+        for item in &mut self.media {
+            let MediaItem::Video(vid) = item else {
+                continue;
+            };
+            let should_hide = focused.as_ref().is_some_and(|ids| !ids.contains(&vid.id));
+
+            if should_hide && vid.hidden.is_none() {
+                let resume_playing = !vid.video.paused();
+                match policy {
+                    HiddenPlayback::Play => continue,
+                    HiddenPlayback::Pause => {
+                        if resume_playing {
+                            synchronized_set_paused(vid.id, &vid.video, true);
+                        }
+                    }
+                    HiddenPlayback::Throttle => vid.pipeline.set_throttled(true),
+                }
+                log::debug!(
+                    "Hidden video suspended: video_id={}, policy={:?}",
+                    vid.id,
+                    policy
+                );
+                vid.hidden = Some(HiddenState {
+                    resume_playing,
+                    position: vid.position,
+                    since: Instant::now(),
+                });
+            } else if !should_hide {
+                let Some(hidden) = vid.hidden.take() else {
+                    continue;
+                };
+                // Undo whatever was applied, even if the policy changed meanwhile
+                vid.pipeline.set_throttled(false);
+                if hidden.resume_playing && vid.video.paused() {
+                    let target = crate::sync::expected_position(
+                        hidden.position,
+                        hidden.since.elapsed().as_secs_f64(),
+                        vid.duration,
+                        vid.video.looping(),
+                    );
+                    log::debug!(
+                        "Hidden video resumed: video_id={}, target={:.2}s",
+                        vid.id,
+                        target
+                    );
+                    vid.position = target;
+                    let _ = synchronized_seek(
+                        vid.id,
+                        &vid.video,
+                        Duration::from_secs_f64(target),
+                        true,
+                    );
+                    synchronized_set_paused(vid.id, &vid.video, false);
+                }
+            }
+        }
+    }
+
+    /// Retarget each video's decode resolution to its on-screen size.
+    /// Focused and zoomed videos decode at full resolution; hidden ones keep their last size.
+    fn update_decode_sizes(&mut self) {
+        let focused = self.focused_ids();
+        let cell = scaling::grid_cell_size(
+            iced::Size::new(self.window_width, self.window_height),
+            self.page_range().len(),
+            self.grid_columns,
+        );
+        let scale_factor = self.scale_factor;
+        let default_fit = self.settings.fit_mode;
+
+        for item in &mut self.media {
+            let MediaItem::Video(vid) = item else {
+                continue;
+            };
+            let target = match &focused {
+                Some(ids) if ids.contains(&vid.id) => None,
+                Some(_) => continue,
+                None if vid.view.is_zoomed() => None,
+                None => scaling::target_decode_size(
+                    vid.source_size,
+                    cell,
+                    scale_factor,
+                    vid.fit.unwrap_or(default_fit),
+                ),
+            };
+            if !scaling::needs_renegotiation(vid.decode_size, target) {
+                continue;
+            }
+            if let Some(filter) = &vid.pipeline.scale_filter {
+                log::debug!(
+                    "Decode size: video_id={}, {:?} -> {:?}",
+                    vid.id,
+                    vid.decode_size,
+                    target
+                );
+                scaling::apply_decode_size(filter, target);
+                vid.decode_size = target;
+            }
+        }
+    }
+
+    /// Keep photo pixels matched to what is on screen: request display-size decodes for
+    /// visible photos that were evicted or are too small for the window, full resolution
+    /// for a focused, zoomed or 1:1 photo, and evict off-screen photos once over the
+    /// memory budget.
+    fn update_photo_residency(&mut self) {
+        let max_dimension = photos::max_decode_dimension(
+            iced::Size::new(self.window_width, self.window_height),
+            self.scale_factor,
+        );
+        let on_screen: Vec<bool> = self
+            .media
+            .iter()
+            .map(|m| self.is_on_screen(m.id()))
+            .collect();
+        let focused = self.focused_ids().unwrap_or_default();
+        let default_fit = self.settings.fit_mode;
+        let now = Instant::now();
+        let mut reloads = Vec::new();
+        let mut redraws = Vec::new();
+
+        for (item, visible) in self.media.iter_mut().zip(&on_screen) {
+            let MediaItem::Photo(photo) = item else {
+                continue;
+            };
+            let wants_detail = focused.contains(&photo.id)
+                || photo.view.is_zoomed()
+                || photo.fit.unwrap_or(default_fit) == FitMode::Native;
+            if !wants_detail && photo.full_handle.take().is_some() && photo.aids.is_active() {
+                // Redraw the aids at display size instead of holding full resolution
+                photo.aid_handle = None;
+                redraws.push(photo.id);
+            }
+            if !visible {
+                continue;
+            }
+            photo.last_visible = now;
+            if photo.reload_pending || photo.reload_error.is_some() {
+                continue;
+            }
+            // Animations play at display size; every frame at full resolution costs too much
+            let wants_full = wants_detail
+                && photo.animation.is_none()
+                && photo.full_handle.is_none()
+                && photo.full_size != photo.size;
+            if wants_full {
+                reloads.push((photo.id, photo.path.clone(), None, photo.transform));
+            } else if photo.handle.is_none()
+                || photos::needs_larger_decode(photo.size, photo.full_size, max_dimension)
+            {
+                reloads.push((
+                    photo.id,
+                    photo.path.clone(),
+                    Some(max_dimension),
+                    photo.transform,
+                ));
+            } else {
+                continue;
+            }
+            photo.reload_pending = true;
+        }
+
+        for id in redraws {
+            self.request_photo_aids(id);
+        }
+
+        for (id, path, max, transform) in reloads {
+            log::debug!("Photo reload: id={}, max_dimension={:?}", id, max);
+            loader::reload_photo_async(
+                self.load_tx.clone(),
+                self.events.tx.clone(),
+                path,
+                id,
+                max,
+                transform,
+            );
+        }
+
+        // Evict off-screen photos, least recently visible first
+        let resident: u64 = self
+            .media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Photo(p) => Some(p.resident_bytes()),
+                _ => None,
+            })
+            .sum();
+        if resident <= photos::MEMORY_BUDGET_BYTES {
+            return;
+        }
+        let candidates = self
+            .media
+            .iter()
+            .zip(&on_screen)
+            .filter_map(|(m, visible)| match m {
+                MediaItem::Photo(p) if !visible && p.handle.is_some() => {
+                    Some((p.id, p.resident_bytes(), p.last_visible))
+                }
+                _ => None,
+            })
+            .collect();
+        let evicted = photos::select_evictions(resident, candidates);
+        for item in &mut self.media {
+            let MediaItem::Photo(photo) = item else {
+                continue;
+            };
+            if evicted.contains(&photo.id) {
+                log::debug!(
+                    "Photo evicted: id={}, bytes={}",
+                    photo.id,
+                    photo.resident_bytes()
+                );
+                photo.handle = None;
+                photo.full_handle = None;
+                photo.aid_handle = None;
+                if let Some(animation) = &mut photo.animation {
+                    animation.frames.clear();
+                }
+            }
+        }
+    }
+
+    /// Apply a photo decoded by [`loader::reload_photo_async`].
+    fn apply_photo_image(
+        &mut self,
+        id: usize,
+        image: Result<crate::state::DecodedPhoto, String>,
+        full_res: bool,
+    ) {
+        let focused = self.focused_ids().is_some_and(|ids| ids.contains(&id));
+        let default_fit = self.settings.fit_mode;
+        let Some(photo) = self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Photo(p) if p.id == id => Some(p),
+            _ => None,
+        }) else {
+            return;
+        };
+        match image {
+            // Dropped if fullscreen, compare, zoom or 1:1 was left while decoding
+            Ok(image) if full_res => {
+                let native = photo.fit.unwrap_or(default_fit) == FitMode::Native;
+                if focused || photo.view.is_zoomed() || native {
+                    photo.full_handle = Some(image.handle);
+                }
+            }
+            Ok(image) => {
+                photo.handle = Some(image.handle);
+                photo.size = image.size;
+                photo.full_size = image.full_size;
+                match image.frames {
+                    Some(frames) => match &mut photo.animation {
+                        Some(animation) => animation.replace_frames(frames),
+                        None => photo.animation = Some(Animation::new(frames)),
+                    },
+                    None => photo.animation = None,
+                }
+                photo.show_current_frame();
+            }
+            Err(e) => {
+                // Kept until the transform changes, so a file that stopped decoding
+                // isn't retried in a loop
+                log::error!("Photo reload failed: id={}, error={}", id, e);
+                photo.reload_pending = false;
+                photo.reload_error = Some(e);
+                return;
+            }
+        }
+        photo.reload_pending = false;
+        if self.in_compare(id) {
+            self.request_blend();
+        }
+        self.request_scope(id);
+        self.request_photo_aids(id);
+    }
+
+    /// Move finished background loads into the grid.
+    fn drain_loaded_media(&mut self) {
+        while let Ok(result) = self.load_rx.try_recv() {
+            // Reloads of photos already in the grid don't count as loading
+            if !matches!(result, crate::state::LoadResult::PhotoImage { .. }) {
+                self.loading_count = self.loading_count.saturating_sub(1);
+            }
+            match result {
+                crate::state::LoadResult::Video(mut video_instance) => {
+                    let vid_id = video_instance.id;
+                    let fps = video_instance.native_fps;
+                    let Some(slot) = self.media.iter_mut().find(|m| m.id() == vid_id) else {
+                        log::info!("Video removed while loading: id={}", vid_id);
+                        continue;
+                    };
+                    let resume = match slot {
+                        MediaItem::Pending(pending) => pending.resume.take(),
+                        _ => None,
+                    };
+                    if let Some(resume) = resume {
+                        Self::restore_video(&mut video_instance, resume);
+                    }
+                    let prefs = self.media_prefs.get(&video_instance.path);
+                    video_instance.fit = prefs.fit;
+                    video_instance.keep_bars = prefs.keep_bars;
+                    video_instance.color = prefs.color;
+                    video_instance.lut = prefs.lut;
+                    video_instance.aids.zebra_ire = self.settings.zebra_ire;
+                    // Bars are detected once per load; reloads reuse the earlier result
+                    match video_instance.letterbox {
+                        Letterbox::Unknown => letterbox::detect_async(
+                            video_instance.path.clone(),
+                            vid_id,
+                            self.events.tx.clone(),
+                        ),
+                        Letterbox::Bars(_) => video_instance.apply_geometry(),
+                        Letterbox::NoBars => {}
+                    }
+                    *slot = MediaItem::Video(video_instance);
+                    self.apply_color(vid_id);
+                    // A compared video that was paged out joins the pair where it stands
+                    if self
+                        .compare
+                        .as_ref()
+                        .is_some_and(|c| c.a == vid_id || c.b == vid_id)
+                    {
+                        self.lock_compare_timeline();
+                        self.sync_frame_taps();
+                    }
+                    log::info!(
+                        "Video ready: id={}, fps={}, total_media={}",
+                        vid_id,
+                        fps,
+                        self.media.len()
+                    );
+                    self.error = None;
+                }
+                crate::state::LoadResult::Photo(mut photo_instance) => {
+                    let photo_id = photo_instance.id;
+                    let filename = photo_instance.filename.clone();
+                    photo_instance.fit = self.media_prefs.get(&photo_instance.path).fit;
+                    photo_instance.aids.zebra_ire = self.settings.zebra_ire;
+                    let Some(slot) = self.media.iter_mut().find(|m| m.id() == photo_id) else {
+                        log::info!("Photo removed while loading: id={}", photo_id);
+                        continue;
+                    };
+                    *slot = MediaItem::Photo(photo_instance);
+                    log::info!(
+                        "Photo ready: id={}, name={}, total_media={}",
+                        photo_id,
+                        filename,
+                        self.media.len()
+                    );
+                    self.error = None;
+                }
+                crate::state::LoadResult::Error(id, e) => {
+                    log::error!("Media load error: id={}, {}", id, e);
+                    // A paged-out video keeps its resume state (markers, position and
+                    // cell settings) and shows the error; first loads are dropped
+                    match self.media.iter_mut().find(|m| m.id() == id) {
+                        Some(MediaItem::Pending(pending)) if pending.resume.is_some() => {
+                            pending.loading = false;
+                            pending.error = Some(e);
+                        }
+                        _ => {
+                            self.media.retain(|m| m.id() != id);
+                            self.error = Some(e);
+                        }
+                    }
+                }
+                crate::state::LoadResult::PhotoImage {
+                    id,
+                    image,
+                    full_res,
+                } => self.apply_photo_image(id, image, full_res),
+            }
+        }
+
+        if self.loading_count > 0 {
+            self.status = format!(
+                "Loading {} file{}...",
+                self.loading_count,
+                if self.loading_count == 1 { "" } else { "s" }
+            );
+        } else if !self.media.is_empty() {
+            self.status = format!("{} media loaded", self.media.len());
+        }
+        self.sync_page();
     }
 
-    /// Find a video by ID.
-    fn find_video_mut(&mut self, id: usize) -> Option<&mut crate::state::VideoInstance> {
-        self.media.iter_mut().find_map(|m| match m {
-            MediaItem::Video(v) if v.id == id => Some(v),
+    /// Restore the playback state a video had before it was paged out.
+    fn restore_video(vid: &mut crate::state::VideoInstance, resume: ResumeState) {
+        vid.markers = resume.markers;
+        vid.letterbox = resume.letterbox;
+        vid.show_info = resume.show_info;
+        vid.show_caption = resume.show_caption;
+        vid.subtitle_delay = resume.subtitle_delay;
+        vid.sync_offset = resume.sync_offset;
+        if let SubtitleSource::Embedded(track) = resume.subtitles {
+            vid.pipeline.select_track(TrackKind::Text, track);
+        }
+        for (kind, track) in [
+            (TrackKind::Audio, resume.audio_track),
+            (TrackKind::Video, resume.video_track),
+        ] {
+            if track > 0 {
+                vid.pipeline.select_track(kind, track);
+            }
+        }
+        vid.subtitles = resume.subtitles;
+        vid.view = resume.view;
+        vid.video.set_looping(resume.looping);
+        if resume.position > 0.0 {
+            vid.position = resume.position;
+            let _ = synchronized_seek(
+                vid.id,
+                &vid.video,
+                Duration::from_secs_f64(resume.position),
+                false,
+            );
+        }
+    }
+
+    /// Handle end of stream for a video.
+    fn handle_end_of_stream(&mut self, id: usize) {
+        if let Some(vid) = self.find_video_mut(id) {
+            log::info!(
+                "EOS: video_id={}, is_looping={}, position={:.2}s/{:.2}s",
+                id,
+                vid.video.looping(),
+                vid.position,
+                vid.duration
+            );
+
+            // Update duration to actual observed length if different
+            if vid.position > vid.duration && vid.position.is_finite() {
+                log::warn!(
+                    "Duration fix: video_id={}, was={:.2}s, actual={:.2}s",
+                    id,
+                    vid.duration,
+                    vid.position
+                );
+                vid.duration = vid.position;
+            }
+
+            // Loop restart is handled internally by iced_video_player's
+            // VideoPlayer widget (restart_stream). No need to seek here;
+            // a redundant flush-seek can stall the pipeline.
+        }
+    }
+
+    /// React to a notification pushed from a loader or video worker thread.
+    fn handle_worker_event(&mut self, event: WorkerEvent) {
+        match event {
+            WorkerEvent::MediaLoaded => self.drain_loaded_media(),
+            WorkerEvent::Positions => {
+                let mut compare_moved = false;
+                for id in self.events.positions.take() {
+                    // Read position and duration cached by the video's worker thread (non-blocking)
+                    if let Some(vid) = self.find_video_mut(id) {
+                        if !vid.dragging {
+                            vid.position = vid.video.cached_position().as_secs_f64();
+                            let cached_dur = vid.video.cached_duration().as_secs_f64();
+
+                            // Update duration from worker thread if it was re-queried
+                            if cached_dur > 0.0 && cached_dur.is_finite() {
+                                vid.duration = cached_dur;
+                            }
+                        }
+                    }
+                    compare_moved |= self.in_compare(id);
+                    self.request_scope(id);
+                }
+                if compare_moved {
+                    self.request_blend();
+                }
+            }
+            WorkerEvent::ScopeReady(id, image) => {
+                let Some(view) = self.media.iter_mut().find_map(|m| match m {
+                    MediaItem::Video(v) if v.id == id => v.scope.as_mut(),
+                    MediaItem::Photo(p) if p.id == id => p.scope.as_mut(),
+                    _ => None,
+                }) else {
+                    return;
+                };
+                view.pending = false;
+                if image.is_some() {
+                    view.image = image;
+                }
+                if view.stale {
+                    self.request_scope(id);
+                }
+            }
+            WorkerEvent::ExposureReady(id, image, aids) => {
+                // Results for aids changed since are dropped; the newer request follows
+                match self.media.iter_mut().find(|m| m.id() == id) {
+                    Some(MediaItem::Photo(photo)) if photo.aids == aids => {
+                        photo.aid_handle = Some(image);
+                    }
+                    _ => {}
+                }
+            }
+            WorkerEvent::LetterboxDetected(id, crop) => self.apply_letterbox(id, crop),
+            WorkerEvent::QualityFrames(run, frames, progress) => {
+                if let Some(analysis) = self.quality.as_mut().filter(|a| a.run == run) {
+                    analysis.push_frames(frames, progress);
+                }
+            }
+            WorkerEvent::QualityFinished(run, result) => {
+                let Some(analysis) = self.quality.as_mut().filter(|a| a.run == run) else {
+                    return;
+                };
+                match &result {
+                    Ok(()) => log::info!(
+                        "Quality analysis finished: run={}, frames={}",
+                        run,
+                        analysis.frames.len()
+                    ),
+                    Err(e) => log::warn!("Quality analysis failed: run={}, error={}", run, e),
+                }
+                analysis.result = Some(result);
+            }
+            WorkerEvent::BlendReady(generation, image) => {
+                // Blends requested for an earlier pair or mode are dropped
+                let Some(compare) = self
+                    .compare
+                    .as_mut()
+                    .filter(|c| c.blend_generation == generation)
+                else {
+                    return;
+                };
+                compare.blend_pending = false;
+                if image.is_some() {
+                    compare.blended = image;
+                }
+                if compare.blend_stale {
+                    self.request_blend();
+                }
+            }
+            WorkerEvent::EndOfStream(id) => self.handle_end_of_stream(id),
+            WorkerEvent::SubtitleCue(id, cue) => {
+                if let Some(vid) = self.find_video_mut(id) {
+                    if matches!(vid.subtitles, SubtitleSource::Embedded(_)) {
+                        subtitles::insert_cue(&mut vid.embedded_cues, cue);
+                    }
+                }
+            }
+            WorkerEvent::ThumbnailReady(id, file) => {
+                if let Some(MediaItem::Pending(pending)) =
+                    self.media.iter_mut().find(|m| m.id() == id)
+                {
+                    pending.thumbnail = Some(iced::widget::image::Handle::from_path(file));
+                }
+            }
+            WorkerEvent::StillExported(result) => {
+                self.status = match result {
+                    Ok(file) => {
+                        log::info!("Exported still to {}", file.display());
+                        format!("Exported still to {}", file.display())
+                    }
+                    Err(e) => {
+                        log::error!("Still export failed: {}", e);
+                        format!("Still export failed: {}", e)
+                    }
+                };
+            }
+            WorkerEvent::Error(id, error) => {
+                log::error!("Pipeline error: video_id={}, error={}", id, error);
+                self.status = format!("Video {} error: {}", id, error);
+            }
+            WorkerEvent::DecoderFailed(id, error) => {
+                let Some(item) = self.media.iter_mut().find(|m| m.id() == id) else {
+                    return;
+                };
+                // Late errors from a pipeline already replaced are ignored
+                let MediaItem::Video(vid) = item else {
+                    return;
+                };
+                // Nothing left to fall back to: report it like any other pipeline error
+                if vid.software_decode {
+                    log::error!("Decoder error: video_id={}, error={}", id, error);
+                    self.status = format!("Video {} error: {}", id, error);
+                    return;
+                }
+                log::warn!(
+                    "Hardware decoder failed: video_id={}, error={}; reloading in software",
+                    id,
+                    error
+                );
+                let mut pending = Self::unload_video(vid, &self.thumbnails);
+                pending.software_decode = true;
+                *item = MediaItem::Pending(pending);
+                self.status = format!("Video {} decoder failed, reloading in software", id);
+                self.sync_page();
+            }
+        }
+    }
+
+    /// Options passed to background loaders, derived from the current settings.
+    fn load_options(&self) -> loader::LoadOptions {
+        loader::LoadOptions {
+            framerate_mode: self.settings.framerate_mode,
+            software_decoding: false,
+            max_photo_dimension: photos::max_decode_dimension(
+                iced::Size::new(self.window_width, self.window_height),
+                self.scale_factor,
+            ),
+        }
+    }
+
+    /// Allocate an ID and add a media file to the grid. It loads in the background
+    /// once its page is visible.
+    fn start_loading(&mut self, path: std::path::PathBuf) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.thumbnails.request(id, path.clone());
+        self.media
+            .push(MediaItem::Pending(PendingMedia::new(id, path)));
+        self.sync_page();
+    }
+
+    /// Drop a marker at the current position of a video and start editing its note.
+    fn add_marker(&mut self, id: usize) -> Task<Message> {
+        let Some(vid) = self.find_video_mut(id) else {
+            return Task::none();
+        };
+        let position = vid.position;
+        let index = markers::insert_marker(&mut vid.markers, position);
+        log::info!("Marker added: video_id={}, position={:.3}s", id, position);
+        self.marker_edit = Some(MarkerEdit {
+            video_id: id,
+            index,
+            draft: String::new(),
+        });
+        iced::widget::operation::focus(ui::MARKER_NOTE_INPUT_ID)
+    }
+
+    /// Seek a video to its next or previous marker.
+    fn jump_to_marker(&mut self, id: usize, forward: bool) {
+        if let Some(vid) = self.find_video_mut(id) {
+            let target = if forward {
+                markers::next_marker(&vid.markers, vid.position)
+            } else {
+                markers::prev_marker(&vid.markers, vid.position)
+            };
+            if let Some(target) = target {
+                vid.position = target;
+                let _ = synchronized_seek(id, &vid.video, Duration::from_secs_f64(target), true);
+            }
+        }
+    }
+
+    /// Seek a video to the start of its next chapter, or back to the start of the
+    /// current (or previous) one.
+    fn jump_to_chapter(&mut self, id: usize, forward: bool) {
+        if let Some(vid) = self.find_video_mut(id) {
+            let target = if forward {
+                chapters::next_chapter(&vid.chapters, vid.position)
+            } else {
+                chapters::prev_chapter(&vid.chapters, vid.position)
+            };
+            if let Some(target) = target {
+                vid.position = target;
+                let _ = synchronized_seek(id, &vid.video, Duration::from_secs_f64(target), true);
+            }
+        }
+    }
+
+    /// Seek a video to the start of one of its chapters.
+    fn seek_to_chapter(&mut self, id: usize, index: usize) {
+        let Some(vid) = self.find_video_mut(id) else {
+            return;
+        };
+        let Some(target) = vid.chapters.get(index).map(|c| c.start) else {
+            return;
+        };
+        vid.position = target;
+        let _ = synchronized_seek(id, &vid.video, Duration::from_secs_f64(target), true);
+    }
+
+    /// Whether an item is an animated photo.
+    fn is_animation(&self, id: usize) -> bool {
+        self.media
+            .iter()
+            .any(|m| matches!(m, MediaItem::Photo(p) if p.id == id && p.animation.is_some()))
+    }
+
+    /// Change an animated photo's playback and show the frame it lands on.
+    fn update_animation(&mut self, id: usize, change: impl FnOnce(&mut Animation)) {
+        let Some(photo) = self.media.iter_mut().find_map(|m| match m {
+            MediaItem::Photo(p) if p.id == id => Some(p),
             _ => None,
-        })
+        }) else {
+            return;
+        };
+        let Some(animation) = &mut photo.animation else {
+            return;
+        };
+        let before = animation.frame_index();
+        change(animation);
+        if animation.frame_index() != before {
+            photo.show_current_frame();
+            self.refresh_animation_frame(id);
+        }
+    }
+
+    /// Advance every playing animation and show the frames that came due.
+    fn advance_animations(&mut self, now: Instant) {
+        let mut advanced = Vec::new();
+        for item in &mut self.media {
+            let MediaItem::Photo(photo) = item else {
+                continue;
+            };
+            let Some(animation) = &mut photo.animation else {
+                continue;
+            };
+            if animation.advance(now) {
+                photo.show_current_frame();
+                advanced.push(photo.id);
+            }
+        }
+        for id in advanced {
+            self.refresh_animation_frame(id);
+        }
+    }
+
+    /// Redo what is drawn from an animated photo's pixels after its frame changed.
+    fn refresh_animation_frame(&mut self, id: usize) {
+        if self.in_compare(id) {
+            self.request_blend();
+        }
+        self.request_scope(id);
+        self.request_photo_aids(id);
+    }
+
+    /// Switch positions between minutes and seconds and source timecode.
+    fn toggle_timecode(&mut self) {
+        self.settings.timecode = !self.settings.timecode;
+        self.settings.save();
+    }
+
+    /// Start typing a timecode to jump one video, or every video with `None`, to.
+    fn open_timecode_entry(&mut self, video_id: Option<usize>) -> Task<Message> {
+        let is_video = |id| {
+            self.media
+                .iter()
+                .any(|m| matches!(m, MediaItem::Video(v) if v.id == id))
+        };
+        if video_id.is_some_and(|id| !is_video(id)) {
+            return Task::none();
+        }
+        self.timecode_entry = Some(TimecodeEntry {
+            video_id,
+            draft: String::new(),
+        });
+        iced::widget::operation::focus(ui::TIMECODE_INPUT_ID)
+    }
+
+    /// Seek to the typed timecode. Every video is matched against its own start
+    /// timecode; videos it falls outside of stay where they are.
+    fn commit_timecode_entry(&mut self) {
+        let Some(entry) = self.timecode_entry.take() else {
+            return;
+        };
+        let mut jumped = 0;
+        let mut failure = None;
+        for item in &mut self.media {
+            if entry.video_id.is_some_and(|id| id != item.id()) {
+                continue;
+            }
+            match item {
+                MediaItem::Video(vid) => match vid.position_of_timecode(&entry.draft) {
+                    Ok(target) => {
+                        vid.position = target;
+                        let _ = synchronized_seek(
+                            vid.id,
+                            &vid.video,
+                            Duration::from_secs_f64(target),
+                            true,
+                        );
+                        jumped += 1;
+                    }
+                    Err(e) => failure = Some(e),
+                },
+                // Paged-out videos resume at the jumped-to frame when they load again
+                MediaItem::Pending(PendingMedia {
+                    filename,
+                    resume: Some(resume),
+                    ..
+                }) => match crate::state::position_of_timecode(
+                    &resume.timebase,
+                    resume.start_timecode,
+                    resume.duration,
+                    filename,
+                    &entry.draft,
+                ) {
+                    Ok(target) => {
+                        resume.position = target;
+                        jumped += 1;
+                    }
+                    Err(e) => failure = Some(e),
+                },
+                _ => {}
+            }
+        }
+        self.status = match (jumped, failure) {
+            (0, Some(e)) => e,
+            (0, None) => "No video to jump".to_string(),
+            (1, None) => format!("Jumped to {}", entry.draft.trim()),
+            (n, None) => format!("Jumped {} videos to {}", n, entry.draft.trim()),
+            (n, Some(e)) => format!("Jumped {} videos; {}", n, e),
+        };
+    }
+
+    /// Export markers of every video in the grid as CSV and CMX3600 EDL.
+    fn export_markers(&mut self) {
+        // Paged-out videos keep their markers in their resume state
+        let rows: Vec<markers::ExportRow<'_>> = self
+            .media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Video(v) => {
+                    Some((&v.filename, v.timebase, v.start_timecode, &v.markers))
+                }
+                MediaItem::Pending(p) => p
+                    .resume
+                    .as_ref()
+                    .map(|r| (&p.filename, r.timebase, r.start_timecode, &r.markers)),
+                _ => None,
+            })
+            .flat_map(|(filename, timebase, start_timecode, markers)| {
+                markers.iter().map(move |marker| markers::ExportRow {
+                    filename,
+                    timebase,
+                    start_timecode,
+                    marker,
+                })
+            })
+            .collect();
+
+        if rows.is_empty() {
+            self.status = "No markers to export".to_string();
+            return;
+        }
+
+        let Some(base) = rfd::FileDialog::new().set_file_name("markers").save_file() else {
+            return;
+        };
+
+        let count = rows.len();
+        self.status = match markers::export(&base, &rows) {
+            Ok(()) => {
+                log::info!("Exported {} markers to {}", count, base.display());
+                format!("Exported {} markers to {}.csv/.edl", count, base.display())
+            }
+            Err(e) => {
+                log::error!("Marker export failed: {}", e);
+                format!("Marker export failed: {}", e)
+            }
+        };
     }
 
     /// Handle UI messages and state updates.
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
         // Signal watchdog that UI thread is alive
         self.watchdog.heartbeat();
 
+        let mut task = Task::none();
+
         match message {
             Message::BrowseFile => {
                 if let Some(path) = rfd::FileDialog::new()
@@ -50,20 +1989,14 @@ impl App {
                     )
                     .pick_file()
                 {
-                    let id = self.next_id;
-                    self.next_id += 1;
-                    self.loading_count += 1;
+                    self.start_loading(path);
                     self.status = "Loading...".to_string();
-                    loader::load_media_async(self.load_tx.clone(), path, id);
                 }
             }
             Message::EventOccurred(event) => match event {
                 iced::Event::Window(iced::window::Event::FileDropped(path)) => {
-                    let id = self.next_id;
-                    self.next_id += 1;
-                    self.loading_count += 1;
+                    self.start_loading(path);
                     self.status = "Loading...".to_string();
-                    loader::load_media_async(self.load_tx.clone(), path, id);
                 }
                 iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                     key: iced::keyboard::Key::Named(key),
@@ -71,40 +2004,59 @@ impl App {
                 }) => match key {
                     iced::keyboard::key::Named::ArrowRight
                     | iced::keyboard::key::Named::ArrowUp => {
-                        if self.grid_columns < 10 {
-                            self.grid_columns += 1;
-                        }
+                        self.set_grid_columns(self.grid_columns + 1);
                     }
                     iced::keyboard::key::Named::ArrowLeft
                     | iced::keyboard::key::Named::ArrowDown => {
-                        if self.grid_columns > 1 {
-                            self.grid_columns -= 1;
-                        }
+                        self.set_grid_columns(self.grid_columns.saturating_sub(1));
                     }
+                    iced::keyboard::key::Named::PageDown => self.set_page(self.page + 1),
+                    iced::keyboard::key::Named::PageUp => {
+                        self.set_page(self.page.saturating_sub(1));
+                    }
+                    iced::keyboard::key::Named::Home => self.set_page(0),
+                    iced::keyboard::key::Named::End => self.set_page(self.page_count() - 1),
                     iced::keyboard::key::Named::Space => {
                         if let Some(id) = self.shortcut_target_id() {
                             if let Some(vid) = self.find_video_mut(id) {
                                 let new_paused = !vid.video.paused();
                                 synchronized_set_paused(id, &vid.video, new_paused);
+                            } else {
+                                self.update_animation(id, Animation::toggle_pause);
                             }
                         }
                     }
+                    iced::keyboard::key::Named::Escape => {
+                        // Cancel a crop being drawn, or keep the marker and drop the
+                        // unsaved note
+                        if self.crop_draw.take().is_some() {
+                            self.status = "Crop cancelled".to_string();
+                        } else if self.timecode_entry.take().is_some() {
+                            self.status = "Timecode jump cancelled".to_string();
+                        } else if self.marker_edit.take().is_none() && self.compare.is_some() {
+                            self.toggle_compare();
+                        }
+                    }
                     _ => {}
                 },
                 iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                     key: iced::keyboard::Key::Character(ch),
                     ..
                 }) => {
+                    if self.handle_compare_key(ch.as_str()) {
+                        return task;
+                    }
+                    match ch.as_str() {
+                        "T" => {
+                            self.toggle_timecode();
+                            return task;
+                        }
+                        "J" => return self.open_timecode_entry(None),
+                        _ => {}
+                    }
                     if let Some(id) = self.shortcut_target_id() {
                         match ch.as_str() {
-                            "f" => {
-                                if let Some(item) = self.media.iter_mut().find(|m| m.id() == id) {
-                                    match item {
-                                        MediaItem::Video(v) => v.fullscreen = !v.fullscreen,
-                                        MediaItem::Photo(p) => p.fullscreen = !p.fullscreen,
-                                    }
-                                }
-                            }
+                            "f" => self.toggle_fullscreen(id),
                             "m" => {
                                 if let Some(vid) = self.find_video_mut(id) {
                                     let enabled = vid.video.audio_enabled();
@@ -114,8 +2066,53 @@ impl App {
                             "l" => {
                                 if let Some(vid) = self.find_video_mut(id) {
                                     vid.video.set_looping(!vid.video.looping());
+                                } else {
+                                    self.update_animation(id, |a| a.looping = !a.looping);
+                                }
+                            }
+                            "i" => {
+                                if let Some(vid) = self.find_video_mut(id) {
+                                    vid.show_info = !vid.show_info;
+                                }
+                            }
+                            "0" => self.update_view(id, |view, _, _| *view = ZoomView::default()),
+                            "z" => self.toggle_linked_zoom(),
+                            "a" => self.cycle_fit(id),
+                            "b" => self.toggle_letterbox(id),
+                            "g" => {
+                                if let Some(vid) = self.find_video_mut(id) {
+                                    vid.show_color = !vid.show_color;
                                 }
                             }
+                            "G" => self.toggle_color_bypass(),
+                            "s" => self.cycle_scope(id),
+                            "t" => self.toggle_caption(id),
+                            "S" => self.export_still(id),
+                            "y" => self.cycle_subtitles(id),
+                            "p" => self.toggle_tracks(id),
+                            "," => self.adjust_subtitle_delay(id, -subtitles::DELAY_STEP),
+                            "." => self.adjust_subtitle_delay(id, subtitles::DELAY_STEP),
+                            "e" => self.update_aids(id, |a| a.zebras = !a.zebras),
+                            "E" => self.update_aids(id, |a| a.false_color = !a.false_color),
+                            "r" => self.update_transform(id, Transform::rotate_clockwise),
+                            "h" => self.update_transform(id, Transform::flip_horizontal),
+                            "u" => self.update_transform(id, Transform::flip_vertical),
+                            "k" => {
+                                self.crop_draw = Some(CropDraw {
+                                    id,
+                                    start: None,
+                                    current: iced::Point::ORIGIN,
+                                });
+                                self.status =
+                                    "Drag over the cell to crop, Esc to cancel".to_string();
+                            }
+                            "K" => self.update_transform(id, |t| t.crop = None),
+                            "n" => task = self.add_marker(id),
+                            "j" => task = self.open_timecode_entry(Some(id)),
+                            "[" => self.jump_to_marker(id, false),
+                            "]" => self.jump_to_marker(id, true),
+                            "{" => self.jump_to_chapter(id, false),
+                            "}" => self.jump_to_chapter(id, true),
                             _ => {}
                         }
                     }
@@ -123,6 +2120,18 @@ impl App {
                 iced::Event::Mouse(iced::mouse::Event::ButtonReleased(
                     iced::mouse::Button::Left,
                 )) => {
+                    self.pan = None;
+                    if let Some(compare) = &mut self.compare {
+                        compare.dragging = false;
+                    }
+                    if let Some(CropDraw {
+                        id,
+                        start: Some(start),
+                        current,
+                    }) = self.crop_draw.take_if(|d| d.start.is_some())
+                    {
+                        self.commit_crop(id, start, current);
+                    }
                     if let Some(source_id) = self.drag_source_id.take() {
                         if let Some((target_id, insert_before)) = self.drag_target.take() {
                             // Find indices by ID
@@ -143,19 +2152,100 @@ impl App {
                     }
                     self.drag_target = None;
                 }
-                iced::Event::Window(iced::window::Event::Resized(size)) => {
+                iced::Event::Window(iced::window::Event::Opened { size, .. })
+                | iced::Event::Window(iced::window::Event::Resized(size)) => {
                     self.window_width = size.width;
+                    self.window_height = size.height;
+                    self.update_decode_sizes();
+                    self.update_photo_residency();
+                }
+                iced::Event::Window(iced::window::Event::Rescaled(scale_factor)) => {
+                    self.scale_factor = scale_factor;
+                    self.update_decode_sizes();
+                    self.update_photo_residency();
                 }
                 _ => {}
             },
-            Message::IncreaseColumns => {
-                if self.grid_columns < 10 {
-                    self.grid_columns += 1;
+            Message::IncreaseColumns => self.set_grid_columns(self.grid_columns + 1),
+            Message::NextPage => self.set_page(self.page + 1),
+            Message::PrevPage => self.set_page(self.page.saturating_sub(1)),
+            Message::DecreaseColumns => {
+                self.set_grid_columns(self.grid_columns.saturating_sub(1));
+            }
+            Message::ToggleFramerateMode => {
+                self.settings.framerate_mode = match self.settings.framerate_mode {
+                    FramerateMode::Native => FramerateMode::Fixed(30),
+                    FramerateMode::Fixed(_) => FramerateMode::Native,
+                };
+                self.settings.save();
+                self.status = "Framerate mode applies to newly loaded videos".to_string();
+            }
+            Message::CycleFit(id) => self.cycle_fit(id),
+            Message::CycleDefaultFit => self.cycle_default_fit(),
+            Message::ToggleLetterbox(id) => self.toggle_letterbox(id),
+            Message::ToggleColorPanel(id) => {
+                if let Some(vid) = self.find_video_mut(id) {
+                    vid.show_color = !vid.show_color;
                 }
             }
-            Message::DecreaseColumns => {
-                if self.grid_columns > 1 {
-                    self.grid_columns -= 1;
+            Message::AdjustColor(id, adjust) => self.adjust_color(id, adjust),
+            Message::CommitColor(id) => self.commit_color(id),
+            Message::ResetColor(id) => {
+                self.adjust_color(id, ColorAdjust::default());
+                self.commit_color(id);
+            }
+            Message::LoadLut(id) => self.load_lut(id),
+            Message::ClearLut(id) => self.clear_lut(id),
+            Message::ToggleColorBypass => self.toggle_color_bypass(),
+            Message::CycleScope(id) => self.cycle_scope(id),
+            Message::ToggleZebras(id) => self.update_aids(id, |a| a.zebras = !a.zebras),
+            Message::ToggleFalseColor(id) => {
+                self.update_aids(id, |a| a.false_color = !a.false_color);
+            }
+            Message::CycleZebraLevel => self.cycle_zebra_level(),
+            Message::ToggleCaption(id) => self.toggle_caption(id),
+            Message::ExportStill(id) => self.export_still(id),
+            Message::CycleSubtitles(id) => self.cycle_subtitles(id),
+            Message::SetSubtitles(id, source) => self.set_subtitles(id, source),
+            Message::ToggleTracks(id) => self.toggle_tracks(id),
+            Message::SelectTrack(id, kind, index) => self.select_track(id, kind, index),
+            Message::CycleCaptionPosition => {
+                self.settings.caption_position = self.settings.caption_position.next();
+                self.settings.save();
+            }
+            Message::CycleCaptionSize => {
+                self.settings.caption_size = self.settings.caption_size.next();
+                self.settings.save();
+            }
+            Message::CycleHiddenPlayback => self.cycle_hidden_playback(),
+            Message::CycleDecoderPreference => {
+                self.settings.decoders = self.settings.decoders.next_preset();
+                self.settings.save();
+                self.status = match crate::decoders::apply(&self.settings.decoders) {
+                    Ok(()) => "Decoder preference applies to newly loaded videos".to_string(),
+                    Err(e) => format!("Failed to apply decoder preference: {}", e),
+                };
+            }
+            Message::TogglePause(id) if self.is_animation(id) => {
+                self.update_animation(id, Animation::toggle_pause);
+            }
+            Message::ToggleLoop(id) if self.is_animation(id) => {
+                self.update_animation(id, |a| a.looping = !a.looping);
+            }
+            Message::Seek(id, secs) if self.is_animation(id) => {
+                self.update_animation(id, |a| {
+                    a.dragging = true;
+                    a.seek(secs);
+                });
+            }
+            Message::SeekRelease(id) if self.is_animation(id) => {
+                self.update_animation(id, |a| a.dragging = false);
+            }
+            Message::TogglePause(id) if self.in_compare(id) => {
+                if let Some(vid) = self.find_video_mut(id) {
+                    let new_paused = !vid.video.paused();
+                    synchronized_group_set_paused(&self.compare_videos(), new_paused);
+                    log::debug!("Compare pause toggled: paused={}", new_paused);
                 }
             }
             Message::TogglePause(id) => {
@@ -176,14 +2266,39 @@ impl App {
                     let _ = vid.video.set_audio_enabled(!enabled);
                 }
             }
-            Message::ToggleFullscreen(id) => {
-                if let Some(item) = self.media.iter_mut().find(|m| m.id() == id) {
-                    match item {
-                        MediaItem::Video(v) => v.fullscreen = !v.fullscreen,
-                        MediaItem::Photo(p) => p.fullscreen = !p.fullscreen,
+            Message::ToggleInfo(id) => {
+                if let Some(vid) = self.find_video_mut(id) {
+                    vid.show_info = !vid.show_info;
+                }
+            }
+            Message::AddMarker(id) => task = self.add_marker(id),
+            Message::PrevMarker(id) => self.jump_to_marker(id, false),
+            Message::NextMarker(id) => self.jump_to_marker(id, true),
+            Message::JumpToChapter(id, index) => self.seek_to_chapter(id, index),
+            Message::MarkerNoteChanged(draft) => {
+                if let Some(edit) = &mut self.marker_edit {
+                    edit.draft = draft;
+                }
+            }
+            Message::CommitMarkerNote => {
+                if let Some(edit) = self.marker_edit.take() {
+                    if let Some(vid) = self.find_video_mut(edit.video_id) {
+                        if let Some(marker) = vid.markers.get_mut(edit.index) {
+                            marker.note = edit.draft.trim().to_string();
+                        }
                     }
                 }
             }
+            Message::ExportMarkers => self.export_markers(),
+            Message::ToggleTimecode => self.toggle_timecode(),
+            Message::OpenTimecodeEntry(id) => task = self.open_timecode_entry(id),
+            Message::TimecodeEntryChanged(draft) => {
+                if let Some(entry) = &mut self.timecode_entry {
+                    entry.draft = draft;
+                }
+            }
+            Message::CommitTimecodeEntry => self.commit_timecode_entry(),
+            Message::ToggleFullscreen(id) => self.toggle_fullscreen(id),
             Message::Seek(id, secs) => {
                 if let Some(vid) = self.find_video_mut(id) {
                     // Validate secs is a valid number
@@ -195,6 +2310,17 @@ impl App {
                     }
                 }
             }
+            Message::SeekRelease(id) if self.in_compare(id) => {
+                if let Some(vid) = self.find_video_mut(id) {
+                    vid.dragging = false;
+                    if vid.position.is_finite() && vid.position >= 0.0 {
+                        let timeline = vid.position - vid.sync_offset;
+                        let paused = vid.video.paused();
+                        log::info!("Compare seeking: timeline={:.2}s", timeline);
+                        self.seek_compare_timeline(timeline, paused);
+                    }
+                }
+            }
             Message::SeekRelease(id) => {
                 if let Some(vid) = self.find_video_mut(id) {
                     vid.dragging = false;
@@ -211,83 +2337,125 @@ impl App {
                     }
                 }
             }
-            Message::EndOfStream(id) => {
-                if let Some(vid) = self.find_video_mut(id) {
-                    log::info!(
-                        "EOS: video_id={}, is_looping={}, position={:.2}s/{:.2}s",
-                        id,
-                        vid.video.looping(),
-                        vid.position,
-                        vid.duration
-                    );
-
-                    // Update duration to actual observed length if different
-                    if vid.position > vid.duration && vid.position.is_finite() {
-                        log::warn!(
-                            "Duration fix: video_id={}, was={:.2}s, actual={:.2}s",
-                            id,
-                            vid.duration,
-                            vid.position
-                        );
-                        vid.duration = vid.position;
+            Message::ToggleSelect(id) => self.toggle_select(id),
+            Message::ToggleCompare => self.toggle_compare(),
+            Message::CompareWipeStart => {
+                if let Some(compare) = &mut self.compare {
+                    compare.dragging = true;
+                    compare.flicker = None;
+                }
+            }
+            Message::CompareMoved(point) => {
+                if let Some(compare) = self.compare.as_mut().filter(|c| c.dragging) {
+                    compare.wipe = match compare.axis {
+                        WipeAxis::Vertical => point.x / self.window_width,
+                        WipeAxis::Horizontal => point.y / self.window_height,
                     }
-
-                    // Loop restart is handled internally by iced_video_player's
-                    // VideoPlayer widget (restart_stream). No need to seek here;
-                    // a redundant flush-seek can stall the pipeline.
+                    .clamp(0.0, 1.0);
+                }
+            }
+            Message::CycleCompareMode => self.cycle_compare_mode(),
+            Message::StartQuality => self.start_quality(),
+            Message::ExportQuality => self.export_quality(),
+            Message::CloseQuality => self.quality = None,
+            Message::BlendAmountChanged(amount) => {
+                if let Some(compare) = &mut self.compare {
+                    match compare.mode {
+                        CompareMode::Difference => compare.gain = amount,
+                        CompareMode::Onion => compare.opacity = amount,
+                        CompareMode::Wipe => {}
+                    }
+                }
+                self.request_blend();
+            }
+            Message::FlipCompare => {
+                self.handle_compare_key("x");
+            }
+            Message::CycleWipeAxis => {
+                self.handle_compare_key("w");
+            }
+            Message::AnimationTick(now) => self.advance_animations(now),
+            Message::ScopeTick(now) => {
+                let due: Vec<usize> = self
+                    .media
+                    .iter()
+                    .filter_map(|m| match m {
+                        MediaItem::Video(v) => Some((v.id, v.scope.as_ref()?)),
+                        MediaItem::Photo(p) => Some((p.id, p.scope.as_ref()?)),
+                        MediaItem::Pending(_) => None,
+                    })
+                    .filter(|(_, view)| {
+                        view.stale
+                            && !view.pending
+                            && now >= view.requested + scopes::SCOPE_INTERVAL
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+                for id in due {
+                    self.request_scope(id);
                 }
             }
             Message::UiFadeTick => {
-                // Update position and duration from video's background worker thread (non-blocking)
+                // Nothing to update: the tick only re-renders so overlay opacity can progress
+            }
+            Message::StallCheck => {
+                // Refresh delivered framerate from the appsink frame counters
                 for item in &mut self.media {
                     if let MediaItem::Video(vid) = item {
-                        if !vid.dragging {
-                            vid.position = vid.video.cached_position().as_secs_f64();
-                            let cached_dur = vid.video.cached_duration().as_secs_f64();
-
-                            // Update duration from worker thread if it was re-queried
-                            if cached_dur > 0.0 && cached_dur.is_finite() {
-                                vid.duration = cached_dur;
-                            }
+                        let frames = vid.pipeline.delivered_frames.load(Ordering::Relaxed);
+                        let (since, start_frames) = vid.fps_sample;
+                        let elapsed = since.elapsed().as_secs_f64();
+                        if elapsed > 0.0 {
+                            vid.measured_fps = frames.saturating_sub(start_frames) as f64 / elapsed;
                         }
+                        vid.fps_sample = (Instant::now(), frames);
                     }
                 }
 
                 // Detect stalled videos and recover ONE per cycle (every ~1s).
-                self.stall_check_counter += 1;
-                if self.stall_check_counter >= 10 {
-                    self.stall_check_counter = 0;
-
-                    // Find first stalled video and recover it
-                    let mut stalled_id = None;
-                    for item in &self.media {
-                        if let MediaItem::Video(vid) = item {
-                            if !vid.video.paused() && vid.video.looping() {
-                                let stall_time = vid.video.time_since_position_change();
-                                if stall_time > Duration::from_secs(3) {
-                                    log::warn!(
-                                        "Stalled video: video_id={}, position={:.2}s, stalled={:.1}s",
-                                        vid.id,
-                                        vid.position,
-                                        stall_time.as_secs_f64()
-                                    );
-                                    stalled_id = Some(vid.id);
-                                    break; // Only recover one per cycle
-                                }
+                // Find first stalled video and recover it
+                let mut stalled_id = None;
+                for item in &self.media {
+                    if let MediaItem::Video(vid) = item {
+                        if !vid.video.paused() && vid.video.looping() {
+                            let stall_time = vid.video.time_since_position_change();
+                            if stall_time > Duration::from_secs(3) {
+                                log::warn!(
+                                    "Stalled video: video_id={}, position={:.2}s, stalled={:.1}s",
+                                    vid.id,
+                                    vid.position,
+                                    stall_time.as_secs_f64()
+                                );
+                                stalled_id = Some(vid.id);
+                                break; // Only recover one per cycle
                             }
                         }
                     }
+                }
+
+                if let Some(id) = stalled_id {
+                    if let Some(vid) = self.find_video_mut(id) {
+                        log::warn!("Recovering stalled video_id={}", id);
+                        let pos = vid.video.cached_position();
+                        let _ = synchronized_seek(id, &vid.video, pos, false);
+                        synchronized_set_paused(id, &vid.video, false);
+                    }
+                }
 
-                    if let Some(id) = stalled_id {
-                        if let Some(vid) = self.find_video_mut(id) {
-                            log::warn!("Recovering stalled video_id={}", id);
-                            let pos = vid.video.cached_position();
-                            let _ = synchronized_seek(id, &vid.video, pos, false);
-                            synchronized_set_paused(id, &vid.video, false);
-                        }
+                // Pull a compared pair back together if playback drifted apart
+                let drift = match self.compare_videos()[..] {
+                    [(a_id, a), (b_id, b)] if !a.paused() && !b.paused() => {
+                        (a.cached_position().as_secs_f64() - self.sync_offset(a_id))
+                            - (b.cached_position().as_secs_f64() - self.sync_offset(b_id))
                     }
+                    _ => 0.0,
+                };
+                if drift.abs() > COMPARE_MAX_DRIFT_SECS {
+                    log::debug!("Compare drift {:.3}s, resyncing", drift);
+                    self.lock_compare_timeline();
                 }
             }
+            Message::Worker(event) => self.handle_worker_event(event),
             Message::RemoveMedia(id) => {
                 let before_count = self.media.len();
                 // Log video destruction before removing
@@ -297,6 +2465,18 @@ impl App {
                     }
                 }
                 self.media.retain(|m| m.id() != id);
+                self.selection.retain(|&s| s != id);
+                if self.in_compare(id) {
+                    self.compare = None;
+                    self.quality = None;
+                }
+                self.sync_page();
+                if self.marker_edit.as_ref().is_some_and(|e| e.video_id == id) {
+                    self.marker_edit = None;
+                }
+                if self.crop_draw.as_ref().is_some_and(|d| d.id == id) {
+                    self.crop_draw = None;
+                }
                 if before_count != self.media.len() {
                     log::info!(
                         "Media removed: id={}, remaining_media={}",
@@ -321,13 +2501,51 @@ impl App {
                                 p.last_mouse_activity = now;
                             }
                         }
+                        MediaItem::Pending(_) => {}
                     }
                 }
             }
             Message::DragStart(id) => {
-                self.drag_source_id = Some(id);
-                self.drag_target = None;
+                let cursor = self
+                    .cursor
+                    .filter(|(cid, _)| *cid == id)
+                    .map(|(_, point)| point)
+                    .unwrap_or_default();
+                if let Some(draw) = self.crop_draw.as_mut().filter(|d| d.id == id) {
+                    draw.start = Some(cursor);
+                    draw.current = cursor;
+                    return task;
+                }
+                // Zoomed cells pan instead of starting a reorder
+                let zoomed = self
+                    .media
+                    .iter_mut()
+                    .find(|m| m.id() == id)
+                    .and_then(|m| m.view_mut())
+                    .is_some_and(|v| v.is_zoomed());
+                if zoomed {
+                    self.pan = Some(PanDrag { id, last: cursor });
+                } else {
+                    self.drag_source_id = Some(id);
+                    self.drag_target = None;
+                }
+            }
+            Message::ZoomScroll(id, delta) => {
+                let lines = zoom::scroll_lines(delta);
+                let cursor = self
+                    .cursor
+                    .filter(|(cid, _)| *cid == id)
+                    .map(|(_, point)| point);
+                self.update_view(id, |view, cell, source| {
+                    let anchor =
+                        cursor.unwrap_or(iced::Point::new(cell.width / 2.0, cell.height / 2.0));
+                    view.zoom_at(lines, anchor, cell, source);
+                });
+            }
+            Message::ResetZoom(id) => {
+                self.update_view(id, |view, _, _| *view = ZoomView::default());
             }
+            Message::ToggleLinkedZoom => self.toggle_linked_zoom(),
             Message::MouseMoved(id, point) => {
                 // Always update mouse activity for UI fade
                 if let Some(item) = self.media.iter_mut().find(|m| m.id() == id) {
@@ -335,8 +2553,29 @@ impl App {
                     match item {
                         MediaItem::Video(v) => v.last_mouse_activity = now,
                         MediaItem::Photo(p) => p.last_mouse_activity = now,
+                        MediaItem::Pending(_) => {}
                     }
                 }
+                self.cursor = Some((id, point));
+                if let Some(draw) = self
+                    .crop_draw
+                    .as_mut()
+                    .filter(|d| d.id == id && d.start.is_some())
+                {
+                    draw.current = point;
+                    return task;
+                }
+                if let Some(pan) = &mut self.pan {
+                    let delta = (pan.id == id).then(|| {
+                        let delta = point - pan.last;
+                        pan.last = point;
+                        delta
+                    });
+                    if let Some(delta) = delta {
+                        self.update_view(id, |view, cell, source| view.pan_by(delta, cell, source));
+                    }
+                    return task;
+                }
                 // Update drag target if dragging over a different cell
                 if let Some(source_id) = self.drag_source_id {
                     if id != source_id {
@@ -350,83 +2589,86 @@ impl App {
             }
             Message::LoadInitialFiles(paths) => {
                 for path in paths {
-                    let id = self.next_id;
-                    self.next_id += 1;
-                    self.loading_count += 1;
-                    loader::load_media_async(self.load_tx.clone(), path, id);
+                    self.start_loading(path);
                 }
                 if self.loading_count > 0 {
                     self.status = format!("Loading {} files...", self.loading_count);
                 }
             }
-            Message::CheckLoadedMedia => {
-                while let Ok(result) = self.load_rx.try_recv() {
-                    self.loading_count = self.loading_count.saturating_sub(1);
-                    match result {
-                        crate::state::LoadResult::Video(video_instance) => {
-                            let vid_id = video_instance.id;
-                            let fps = video_instance.native_fps;
-                            self.media.push(MediaItem::Video(video_instance));
-                            log::info!(
-                                "Video ready: id={}, fps={}, total_media={}",
-                                vid_id,
-                                fps,
-                                self.media.len()
-                            );
-                            self.error = None;
-                        }
-                        crate::state::LoadResult::Photo(photo_instance) => {
-                            let photo_id = photo_instance.id;
-                            let filename = photo_instance.filename.clone();
-                            self.media.push(MediaItem::Photo(photo_instance));
-                            log::info!(
-                                "Photo ready: id={}, name={}, total_media={}",
-                                photo_id,
-                                filename,
-                                self.media.len()
-                            );
-                            self.error = None;
-                        }
-                        crate::state::LoadResult::Error(e) => {
-                            log::error!("Media load error: {}", e);
-                            self.error = Some(e);
-                        }
-                    }
-                }
-
-                if self.loading_count > 0 {
-                    self.status = format!(
-                        "Loading {} file{}...",
-                        self.loading_count,
-                        if self.loading_count == 1 { "" } else { "s" }
-                    );
-                } else if !self.media.is_empty() {
-                    self.status = format!("{} media loaded", self.media.len());
-                }
-            }
         }
+
+        task
     }
 
     /// Subscribe to events.
     pub fn subscription(&self) -> Subscription<Message> {
-        let has_hovered_media = self.media.iter().any(|m| match m {
-            MediaItem::Video(v) => v.hovered,
-            MediaItem::Photo(p) => p.hovered,
-        });
+        let fade_phase = self
+            .media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Video(v) if v.hovered => Some(ui::fade_phase(v.last_mouse_activity)),
+                MediaItem::Photo(p) if p.hovered => Some(ui::fade_phase(p.last_mouse_activity)),
+                _ => None,
+            })
+            .min();
 
         let has_videos = self.media.iter().any(|m| matches!(m, MediaItem::Video(_)));
 
-        let mut subscriptions = vec![event::listen().map(Message::EventOccurred)];
+        let mut subscriptions = vec![
+            event::listen().map(Message::EventOccurred),
+            // Load results, positions, EOS and errors are pushed from worker threads
+            self.events.subscription(),
+        ];
 
-        // Tick for UI fade + position updates (100ms when videos or hovered media present)
-        if has_videos || has_hovered_media {
-            subscriptions.push(time::every(Duration::from_millis(33)).map(|_| Message::UiFadeTick));
+        // Re-render quickly only while an overlay is fading; check back slowly
+        // while one is fully visible so the fade starts on time.
+        match fade_phase {
+            Some(ui::FadePhase::Fading) => {
+                subscriptions
+                    .push(time::every(Duration::from_millis(33)).map(|_| Message::UiFadeTick));
+            }
+            Some(ui::FadePhase::Visible) => {
+                subscriptions
+                    .push(time::every(Duration::from_millis(250)).map(|_| Message::UiFadeTick));
+            }
+            Some(ui::FadePhase::Hidden) | None => {}
         }
 
-        // Poll for loaded media results when background loads are in-flight
-        if self.loading_count > 0 {
-            subscriptions
-                .push(time::every(Duration::from_millis(100)).map(|_| Message::CheckLoadedMedia));
+        if has_videos {
+            subscriptions.push(time::every(Duration::from_secs(1)).map(|_| Message::StallCheck));
+        }
+
+        // Wake once, when the earliest next frame of a playing animation is due
+        let next_frame = self
+            .media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Photo(p) => p.animation.as_ref()?.next_due(),
+                _ => None,
+            })
+            .min();
+        if let Some(due) = next_frame {
+            subscriptions.push(
+                Subscription::run_with(due, crate::animation::tick_at).map(Message::AnimationTick),
+            );
+        }
+
+        // Wake once, when the earliest scope held back by its rate limit may render
+        let scope_due = self
+            .media
+            .iter()
+            .filter_map(|m| match m {
+                MediaItem::Video(v) => v.scope.as_ref(),
+                MediaItem::Photo(p) => p.scope.as_ref(),
+                MediaItem::Pending(_) => None,
+            })
+            .filter(|view| view.stale && !view.pending)
+            .map(|view| view.requested + scopes::SCOPE_INTERVAL)
+            .min();
+        if let Some(due) = scope_due {
+            subscriptions.push(
+                Subscription::run_with(due, crate::animation::tick_at).map(Message::ScopeTick),
+            );
         }
 
         Subscription::batch(subscriptions)
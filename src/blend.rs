@@ -0,0 +1,241 @@
+//! Difference and onion-skin blends of two frames, computed on the CPU.
+//!
+//! Video frames are tapped from the appsink as decoded NV12 buffers while a blend view
+//! needs them; photos are read from their RGBA pixels. B is resampled onto A's pixel
+//! grid, so the pair doesn't need matching sizes, and blending runs on a worker thread
+//! so it works without a GPU and without stalling the UI.
+
+use gstreamer as gst;
+use gstreamer_video as gst_video;
+use iced::widget::image::Handle;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::events::{EventSender, WorkerEvent};
+use crate::nv12::{self, Planes};
+use crate::worker::LatestWorker;
+
+/// Longest side of a blended image, bounding the work per frame.
+const MAX_BLEND_DIMENSION: u32 = 1920;
+
+/// How the two frames are combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    /// Absolute per-channel difference, multiplied by `gain`.
+    Difference { gain: f32 },
+    /// B laid over A at `opacity`.
+    Onion { opacity: f32 },
+}
+
+/// Decoded pixels of one frame.
+#[derive(Clone)]
+enum Pixels {
+    /// NV12 buffer from the appsink, laid out as `info` and its video meta describe.
+    Nv12(gst::Buffer, gst_video::VideoInfo),
+    /// Tightly packed RGBA from a photo handle.
+    Rgba(Handle),
+}
+
+/// A frame ready to blend.
+#[derive(Clone)]
+pub struct Frame {
+    width: u32,
+    height: u32,
+    pixels: Pixels,
+}
+
+impl Frame {
+    /// Frame for a photo decoded to RGBA pixels.
+    pub fn from_handle(handle: &Handle) -> Option<Frame> {
+        match handle {
+            Handle::Rgba { width, height, .. } => Some(Frame {
+                width: *width,
+                height: *height,
+                pixels: Pixels::Rgba(handle.clone()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Width and height in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Run `f` with the frame's pixels mapped for reading.
+    pub fn with_pixels<R>(&self, f: impl FnOnce(Mapped<'_>) -> R) -> Option<R> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        match &self.pixels {
+            Pixels::Nv12(buffer, info) => {
+                nv12::with_planes(buffer, info, |planes| f(Mapped::Nv12(planes)))
+            }
+            Pixels::Rgba(Handle::Rgba { pixels, .. }) => {
+                if pixels.len() < width * height * 4 {
+                    return None;
+                }
+                Some(f(Mapped::Rgba {
+                    pixels,
+                    stride: width * 4,
+                }))
+            }
+            Pixels::Rgba(_) => None,
+        }
+    }
+}
+
+/// Pixel data of a frame, borrowed while its buffer is mapped.
+pub enum Mapped<'a> {
+    Nv12(Planes<&'a [u8]>),
+    Rgba { pixels: &'a [u8], stride: usize },
+}
+
+impl Mapped<'_> {
+    /// RGB of the pixel at `(x, y)`. NV12 is converted with BT.709 limited-range
+    /// coefficients; both sides go through the same conversion, so small errors cancel
+    /// in the difference.
+    pub fn rgb(&self, x: usize, y: usize) -> [f32; 3] {
+        match self {
+            Mapped::Nv12(planes) => {
+                let c = (planes.luma[y * planes.luma_stride + x] as f32 - 16.0) * 1.164;
+                let uv = (y / 2) * planes.chroma_stride + (x / 2) * 2;
+                let d = planes.chroma[uv] as f32 - 128.0;
+                let e = planes.chroma[uv + 1] as f32 - 128.0;
+                [c + 1.793 * e, c - 0.213 * d - 0.533 * e, c + 2.112 * d]
+            }
+            Mapped::Rgba { pixels, stride } => {
+                let i = y * stride + x * 4;
+                [pixels[i] as f32, pixels[i + 1] as f32, pixels[i + 2] as f32]
+            }
+        }
+    }
+}
+
+/// Blend `b` onto `a`'s pixel grid, downscaled to at most `MAX_BLEND_DIMENSION`.
+pub fn blend(a: &Frame, b: &Frame, mode: BlendMode) -> Option<Handle> {
+    if [a.width, a.height, b.width, b.height].contains(&0) {
+        return None;
+    }
+    let scale = (MAX_BLEND_DIMENSION as f32 / a.width.max(a.height).max(1) as f32).min(1.0);
+    let width = ((a.width as f32 * scale) as u32).max(1);
+    let height = ((a.height as f32 * scale) as u32).max(1);
+
+    // Nearest source column/row for each output column/row
+    let sample = |out_len: u32, src_len: u32| -> Vec<usize> {
+        (0..out_len)
+            .map(|i| {
+                let pos = (i as f32 + 0.5) / out_len as f32 * src_len as f32;
+                (pos as usize).min(src_len as usize - 1)
+            })
+            .collect()
+    };
+    let (a_cols, a_rows) = (sample(width, a.width), sample(height, a.height));
+    let (b_cols, b_rows) = (sample(width, b.width), sample(height, b.height));
+
+    let pixels = a
+        .with_pixels(|pa| {
+            b.with_pixels(|pb| {
+                let mut out = Vec::with_capacity(width as usize * height as usize * 4);
+                for (&ay, &by) in a_rows.iter().zip(&b_rows) {
+                    for (&ax, &bx) in a_cols.iter().zip(&b_cols) {
+                        let ca = pa.rgb(ax, ay);
+                        let cb = pb.rgb(bx, by);
+                        for ch in 0..3 {
+                            let value = match mode {
+                                BlendMode::Difference { gain } => (ca[ch] - cb[ch]).abs() * gain,
+                                BlendMode::Onion { opacity } => {
+                                    ca[ch] * (1.0 - opacity) + cb[ch] * opacity
+                                }
+                            };
+                            out.push(value.clamp(0.0, 255.0) as u8);
+                        }
+                        out.push(255);
+                    }
+                }
+                out
+            })
+        })
+        .flatten()?;
+
+    Some(Handle::from_rgba(width, height, pixels))
+}
+
+/// Latest decoded frame of a video, kept only while a blend view needs it.
+#[derive(Default)]
+pub struct FrameTap {
+    enabled: AtomicBool,
+    latest: Mutex<Option<Frame>>,
+}
+
+impl FrameTap {
+    /// Start or stop keeping frames. Stopping releases the held buffer.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if enabled {
+            return;
+        }
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = None;
+        }
+    }
+
+    /// Keep a reference to a buffer arriving at the appsink (no copy).
+    pub fn offer(&self, pad: &gst::Pad, buffer: &gst::Buffer) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(info) = nv12::pad_video_info(pad) else {
+            return;
+        };
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = Some(Frame {
+                width: info.width().max(1),
+                height: info.height().max(1),
+                pixels: Pixels::Nv12(buffer.clone(), info),
+            });
+        }
+    }
+
+    /// The most recent frame, if tapping is enabled and one has arrived.
+    pub fn latest(&self) -> Option<Frame> {
+        self.latest.lock().ok()?.clone()
+    }
+}
+
+/// A pair of frames to blend.
+pub struct Job {
+    pub a: Frame,
+    pub b: Frame,
+    pub mode: BlendMode,
+}
+
+/// Background worker blending frame pairs, skipping ahead to the newest request.
+pub struct Worker {
+    worker: LatestWorker<(), (u64, Job)>,
+    generation: AtomicU64,
+}
+
+impl Worker {
+    /// Spawn the worker. Each result is announced with [`WorkerEvent::BlendReady`]
+    /// under its request's generation; failed blends (unmappable frames) are announced
+    /// without an image.
+    pub fn spawn(events: EventSender) -> Self {
+        let worker = LatestWorker::spawn("blend", move |(), (generation, job): (u64, Job)| {
+            let image = blend(&job.a, &job.b, job.mode);
+            if image.is_none() {
+                log::debug!("Blend skipped: frame could not be read");
+            }
+            let _ = events.unbounded_send(WorkerEvent::BlendReady(generation, image));
+        });
+        Worker {
+            worker,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue a blend, returning the generation its result will be announced under.
+    pub fn request(&self, job: Job) -> u64 {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        self.worker.request((), (generation, job));
+        generation
+    }
+}
@@ -0,0 +1,131 @@
+//! Always-visible caption of a cell: filename, timecode, frame number and sync offset.
+//!
+//! Unlike the overlay controls, the caption does not fade, so it reads like a burn-in
+//! when cells are reviewed side by side or captured from the screen. Stills exported
+//! from a video burn the same lines in (see `stills`).
+
+use crate::state::{PhotoInstance, VideoInstance};
+use crate::timecode;
+
+/// Corner of the cell the caption sits in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptionPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl CaptionPosition {
+    pub fn next(self) -> Self {
+        match self {
+            CaptionPosition::TopLeft => CaptionPosition::TopRight,
+            CaptionPosition::TopRight => CaptionPosition::BottomRight,
+            CaptionPosition::BottomRight => CaptionPosition::BottomLeft,
+            CaptionPosition::BottomLeft => CaptionPosition::TopLeft,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptionPosition::TopLeft => "top-left",
+            CaptionPosition::TopRight => "top-right",
+            CaptionPosition::BottomLeft => "bottom-left",
+            CaptionPosition::BottomRight => "bottom-right",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "top-left" => Some(CaptionPosition::TopLeft),
+            "top-right" => Some(CaptionPosition::TopRight),
+            "bottom-left" => Some(CaptionPosition::BottomLeft),
+            "bottom-right" => Some(CaptionPosition::BottomRight),
+            _ => None,
+        }
+    }
+
+    pub fn is_top(self) -> bool {
+        matches!(self, CaptionPosition::TopLeft | CaptionPosition::TopRight)
+    }
+
+    pub fn is_left(self) -> bool {
+        matches!(self, CaptionPosition::TopLeft | CaptionPosition::BottomLeft)
+    }
+}
+
+/// Text size of the caption.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptionSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl CaptionSize {
+    pub fn next(self) -> Self {
+        match self {
+            CaptionSize::Small => CaptionSize::Medium,
+            CaptionSize::Medium => CaptionSize::Large,
+            CaptionSize::Large => CaptionSize::Small,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptionSize::Small => "small",
+            CaptionSize::Medium => "medium",
+            CaptionSize::Large => "large",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "small" => Some(CaptionSize::Small),
+            "medium" => Some(CaptionSize::Medium),
+            "large" => Some(CaptionSize::Large),
+            _ => None,
+        }
+    }
+
+    /// Text size in logical pixels.
+    pub fn text_size(self) -> f32 {
+        match self {
+            CaptionSize::Small => 11.0,
+            CaptionSize::Medium => 14.0,
+            CaptionSize::Large => 20.0,
+        }
+    }
+}
+
+/// Caption lines of a video at its current position: source timecode and frame index
+/// in its own timebase, and its compare sync offset when it has one.
+pub fn video_lines(vid: &VideoInstance) -> Vec<String> {
+    let timebase = &vid.timebase;
+    let mut lines = vec![
+        vid.filename.clone(),
+        vid.timecode_at(vid.position),
+        format!("frame {}", timebase.frames(vid.position)),
+    ];
+    if vid.sync_offset != 0.0 {
+        lines.push(format!(
+            "sync {}{}",
+            if vid.sync_offset < 0.0 { "-" } else { "+" },
+            timecode::frames_to_timecode(timebase.frames(vid.sync_offset.abs()), timebase.rate)
+        ));
+    }
+    lines
+}
+
+/// Caption lines of a photo: its filename, and the frame shown if it is animated.
+pub fn photo_lines(photo: &PhotoInstance) -> Vec<String> {
+    let mut lines = vec![photo.filename.clone()];
+    if let Some(animation) = &photo.animation {
+        lines.push(format!(
+            "frame {} / {}",
+            animation.frame_index() + 1,
+            animation.frame_count()
+        ));
+    }
+    lines
+}
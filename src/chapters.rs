@@ -0,0 +1,162 @@
+//! Chapters of videos whose container carries them (Matroska, MP4).
+//!
+//! Demuxers publish chapters as a table of contents (TOC), sent downstream as a sticky
+//! event and answered to TOC queries. It is read once the video has prerolled.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+/// Chapters closer than this to the current position are skipped when jumping back,
+/// so a press just after a chapter starts goes to the one before it.
+const JUMP_BACK_SECS: f64 = 2.0;
+
+/// A chapter starting `start` seconds into the video.
+#[derive(Clone, Debug)]
+pub struct Chapter {
+    pub start: f64,
+    pub title: String,
+}
+
+/// Chapters of a TOC, sorted by start. Editions are looked through; sub-chapters are
+/// left out. Untitled chapters are numbered.
+fn from_toc(toc: &gst::TocRef) -> Vec<Chapter> {
+    fn collect(entry: &gst::TocEntryRef, chapters: &mut Vec<Chapter>) {
+        match entry.entry_type() {
+            gst::TocEntryType::Chapter => {
+                let Some((start, _)) = entry.start_stop_times().filter(|(s, _)| *s >= 0) else {
+                    return;
+                };
+                let title = entry
+                    .tags()
+                    .and_then(|tags| tags.get::<gst::tags::Title>().map(|v| v.get().to_string()))
+                    .filter(|t| !t.trim().is_empty());
+                chapters.push(Chapter {
+                    start: start as f64 / 1_000_000_000.0,
+                    title: title.unwrap_or_default(),
+                });
+            }
+            _ => {
+                for sub in entry.sub_entries() {
+                    collect(&sub, chapters);
+                }
+            }
+        }
+    }
+
+    let mut chapters = Vec::new();
+    for entry in toc.entries() {
+        collect(&entry, &mut chapters);
+    }
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters.dedup_by(|a, b| a.start == b.start);
+    for (i, chapter) in chapters.iter_mut().enumerate() {
+        if chapter.title.is_empty() {
+            chapter.title = format!("Chapter {}", i + 1);
+        }
+    }
+    chapters
+}
+
+/// Chapters of a prerolled video: from the TOC event that reached its video sink, or
+/// failing that from a TOC query. Empty when the file has none.
+pub fn read(pipeline: &gst::Pipeline) -> Vec<Chapter> {
+    let video_sink: gst::Element = pipeline.property("video-sink");
+    let sticky = video_sink
+        .pads()
+        .first()
+        .and_then(|pad| pad.sticky_event::<gst::event::Toc>(0));
+    if let Some(event) = sticky {
+        let (toc, _) = event.toc();
+        return from_toc(toc);
+    }
+    let mut query = gst::query::Toc::new(gst::TocScope::Global);
+    if pipeline.query(&mut query) {
+        let (toc, _) = query.result();
+        return from_toc(toc);
+    }
+    Vec::new()
+}
+
+/// Index of the chapter playing at `position`, if it is past the first one's start.
+pub fn index_at(chapters: &[Chapter], position: f64) -> Option<usize> {
+    chapters
+        .partition_point(|c| c.start <= position)
+        .checked_sub(1)
+}
+
+/// Start of the chapter after the one at `position`, if any.
+pub fn next_chapter(chapters: &[Chapter], position: f64) -> Option<f64> {
+    let next = index_at(chapters, position).map_or(0, |i| i + 1);
+    chapters.get(next).map(|c| c.start)
+}
+
+/// Start of the chapter at `position`, or of the one before it when `position` is
+/// just past its start. Before the first chapter, and just past its start, this is
+/// the start of the video. None if the video has no chapters.
+pub fn prev_chapter(chapters: &[Chapter], position: f64) -> Option<f64> {
+    if chapters.is_empty() {
+        return None;
+    }
+    let Some(current) = index_at(chapters, position) else {
+        return Some(0.0);
+    };
+    if position - chapters[current].start > JUMP_BACK_SECS {
+        return Some(chapters[current].start);
+    }
+    Some(current.checked_sub(1).map_or(0.0, |i| chapters[i].start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapters(starts: &[f64]) -> Vec<Chapter> {
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| Chapter {
+                start,
+                title: format!("Chapter {}", i + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn index_is_the_last_chapter_started() {
+        let chapters = chapters(&[5.0, 60.0, 120.0]);
+        assert_eq!(index_at(&chapters, 0.0), None);
+        assert_eq!(index_at(&chapters, 5.0), Some(0));
+        assert_eq!(index_at(&chapters, 59.9), Some(0));
+        assert_eq!(index_at(&chapters, 60.0), Some(1));
+        assert_eq!(index_at(&chapters, 500.0), Some(2));
+        assert_eq!(index_at(&[], 10.0), None);
+    }
+
+    #[test]
+    fn next_goes_to_the_following_chapter() {
+        let chapters = chapters(&[5.0, 60.0, 120.0]);
+        assert_eq!(next_chapter(&chapters, 0.0), Some(5.0));
+        assert_eq!(next_chapter(&chapters, 5.0), Some(60.0));
+        assert_eq!(next_chapter(&chapters, 90.0), Some(120.0));
+        assert_eq!(next_chapter(&chapters, 130.0), None);
+        assert_eq!(next_chapter(&[], 0.0), None);
+    }
+
+    #[test]
+    fn prev_restarts_the_chapter_or_goes_back_one_just_after_its_start() {
+        let chapters = chapters(&[5.0, 60.0, 120.0]);
+        assert_eq!(prev_chapter(&chapters, 90.0), Some(60.0));
+        assert_eq!(prev_chapter(&chapters, 61.0), Some(5.0));
+        assert_eq!(prev_chapter(&chapters, 125.0), Some(120.0));
+        assert_eq!(prev_chapter(&chapters, 121.5), Some(60.0));
+        assert_eq!(prev_chapter(&[], 30.0), None);
+    }
+
+    #[test]
+    fn prev_near_the_first_chapter_goes_to_the_start() {
+        assert_eq!(prev_chapter(&chapters(&[5.0, 60.0]), 6.0), Some(0.0));
+        assert_eq!(prev_chapter(&chapters(&[5.0, 60.0]), 3.0), Some(0.0));
+        assert_eq!(prev_chapter(&chapters(&[0.0, 60.0]), 1.0), Some(0.0));
+        assert_eq!(prev_chapter(&chapters(&[0.0, 60.0]), 10.0), Some(0.0));
+    }
+}
@@ -0,0 +1,74 @@
+//! Per-cell brightness, contrast, saturation and gamma.
+//!
+//! Applied to videos by the `videobalance` and `gamma` elements at the end of the
+//! pipeline, after decoding is scaled to the cell, so adjusting is cheap and live.
+
+use serde_json::{Map, Value, json};
+use std::ops::RangeInclusive;
+
+/// Color adjustments of one cell. The defaults leave frames untouched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorAdjust {
+    pub brightness: f32, // Added to luma, -1..1
+    pub contrast: f32,   // Luma gain, 0..2
+    pub saturation: f32, // Chroma gain, 0..2
+    pub gamma: f32,      // Above 1 lifts the midtones
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        ColorAdjust {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl ColorAdjust {
+    pub const BRIGHTNESS_RANGE: RangeInclusive<f32> = -1.0..=1.0;
+    pub const CONTRAST_RANGE: RangeInclusive<f32> = 0.0..=2.0;
+    pub const SATURATION_RANGE: RangeInclusive<f32> = 0.0..=2.0;
+    pub const GAMMA_RANGE: RangeInclusive<f32> = 0.1..=4.0;
+
+    pub fn is_neutral(&self) -> bool {
+        *self == ColorAdjust::default()
+    }
+
+    /// Read from a JSON object; missing or out-of-range values keep their defaults.
+    pub fn from_json(value: &Value) -> Self {
+        let neutral = ColorAdjust::default();
+        let get = |key: &str, range: RangeInclusive<f32>, default: f32| {
+            value
+                .get(key)
+                .and_then(Value::as_f64)
+                .map(|v| v as f32)
+                .filter(|v| range.contains(v))
+                .unwrap_or(default)
+        };
+        ColorAdjust {
+            brightness: get("brightness", Self::BRIGHTNESS_RANGE, neutral.brightness),
+            contrast: get("contrast", Self::CONTRAST_RANGE, neutral.contrast),
+            saturation: get("saturation", Self::SATURATION_RANGE, neutral.saturation),
+            gamma: get("gamma", Self::GAMMA_RANGE, neutral.gamma),
+        }
+    }
+
+    /// JSON object holding only the values changed from the defaults.
+    pub fn to_json(&self) -> Value {
+        let neutral = ColorAdjust::default();
+        let mut map = Map::new();
+        for (key, value, default) in [
+            ("brightness", self.brightness, neutral.brightness),
+            ("contrast", self.contrast, neutral.contrast),
+            ("saturation", self.saturation, neutral.saturation),
+            ("gamma", self.gamma, neutral.gamma),
+        ] {
+            if value != default {
+                map.insert(key.to_string(), json!(value));
+            }
+        }
+        Value::Object(map)
+    }
+}
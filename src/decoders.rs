@@ -0,0 +1,233 @@
+//! Video decoder family preferences.
+//!
+//! Preferences are applied by adjusting GStreamer plugin feature ranks, so playbin's
+//! autoplugging picks (or skips) decoders without changing the pipeline description.
+//! Ranks are process-wide and only changed from the UI thread; a video whose hardware
+//! decoder fails is reloaded with [`force_software`], which affects that pipeline only.
+
+use gstreamer as gst;
+use gstreamer::glib;
+use gstreamer::prelude::*;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Groups of video decoders that can be preferred or forbidden together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecoderFamily {
+    VaApi,
+    Nvidia,
+    VideoToolbox,
+    Direct3D,
+    V4l2,
+    /// Everything that isn't a known hardware family (`avdec_*`, `dav1ddec`, ...).
+    Software,
+}
+
+impl DecoderFamily {
+    pub const HARDWARE: [DecoderFamily; 5] = [
+        DecoderFamily::VaApi,
+        DecoderFamily::Nvidia,
+        DecoderFamily::VideoToolbox,
+        DecoderFamily::Direct3D,
+        DecoderFamily::V4l2,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DecoderFamily::VaApi => "vaapi",
+            DecoderFamily::Nvidia => "nvidia",
+            DecoderFamily::VideoToolbox => "videotoolbox",
+            DecoderFamily::Direct3D => "d3d",
+            DecoderFamily::V4l2 => "v4l2",
+            DecoderFamily::Software => "software",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::HARDWARE
+            .into_iter()
+            .chain([DecoderFamily::Software])
+            .find(|f| f.as_str() == name)
+    }
+
+    /// Family of a decoder element factory, by name.
+    pub fn of(factory_name: &str) -> Self {
+        let n = factory_name;
+        if n.starts_with("vaapi") || (n.starts_with("va") && n.ends_with("dec")) {
+            DecoderFamily::VaApi
+        } else if n.starts_with("nv") && n.contains("dec") {
+            DecoderFamily::Nvidia
+        } else if n.starts_with("vtdec") {
+            DecoderFamily::VideoToolbox
+        } else if n.starts_with("d3d11") || n.starts_with("d3d12") {
+            DecoderFamily::Direct3D
+        } else if n.starts_with("v4l2") {
+            DecoderFamily::V4l2
+        } else {
+            DecoderFamily::Software
+        }
+    }
+}
+
+/// Which decoder families to rank above the rest, and which to disable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecoderPreferences {
+    pub prefer: Vec<DecoderFamily>,
+    pub forbid: Vec<DecoderFamily>,
+}
+
+impl DecoderPreferences {
+    /// Prefer every hardware family; software remains as fallback.
+    pub fn hardware() -> Self {
+        DecoderPreferences {
+            prefer: DecoderFamily::HARDWARE.to_vec(),
+            forbid: Vec::new(),
+        }
+    }
+
+    /// Forbid every hardware family.
+    pub fn software() -> Self {
+        DecoderPreferences {
+            prefer: Vec::new(),
+            forbid: DecoderFamily::HARDWARE.to_vec(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.prefer.is_empty() && self.forbid.is_empty()
+    }
+
+    /// Short label for the controls bar.
+    pub fn label(&self) -> &'static str {
+        if self.is_default() {
+            "auto"
+        } else if *self == Self::hardware() {
+            "hardware"
+        } else if *self == Self::software() {
+            "software"
+        } else {
+            "custom"
+        }
+    }
+
+    /// Next preset in the auto -> hardware -> software cycle.
+    pub fn next_preset(&self) -> Self {
+        match self.label() {
+            "auto" => Self::hardware(),
+            "hardware" => Self::software(),
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Ranks as registered by the plugins, captured before the first change.
+static ORIGINAL_RANKS: OnceLock<HashMap<String, gst::Rank>> = OnceLock::new();
+
+/// All registered video decoder factories.
+fn video_decoder_factories() -> Vec<gst::ElementFactory> {
+    gst::Registry::get()
+        .features(gst::ElementFactory::static_type())
+        .into_iter()
+        .filter_map(|f| f.downcast::<gst::ElementFactory>().ok())
+        .filter(is_video_decoder)
+        .collect()
+}
+
+fn is_video_decoder(factory: &gst::ElementFactory) -> bool {
+    factory
+        .metadata(gst::ELEMENT_METADATA_KLASS)
+        .is_some_and(|k| k.contains("Decoder") && k.contains("Video"))
+}
+
+fn is_hardware_decoder(factory: &gst::ElementFactory) -> bool {
+    is_video_decoder(factory) && DecoderFamily::of(&factory.name()) != DecoderFamily::Software
+}
+
+/// Adjust decoder ranks to match `prefs`. Idempotent. Call from the UI thread only,
+/// at startup and when the preference changes; loaders never touch the ranks.
+/// Default preferences restore the plugins' own ranks.
+pub fn apply(prefs: &DecoderPreferences) -> Result<(), glib::Error> {
+    gst::init()?;
+    let factories = video_decoder_factories();
+    let original = ORIGINAL_RANKS.get_or_init(|| {
+        factories
+            .iter()
+            .map(|f| (f.name().to_string(), f.rank()))
+            .collect()
+    });
+
+    for factory in &factories {
+        let name = factory.name();
+        let Some(&rank) = original.get(name.as_str()) else {
+            continue;
+        };
+        let family = DecoderFamily::of(&name);
+        let new_rank = if prefs.forbid.contains(&family) {
+            gst::Rank::NONE
+        } else if prefs.prefer.contains(&family) {
+            rank.max(gst::Rank::PRIMARY) + 1
+        } else {
+            rank
+        };
+        if factory.rank() != new_rank {
+            factory.set_rank(new_rank);
+        }
+    }
+    Ok(())
+}
+
+/// Make this pipeline skip hardware video decoders, whatever their rank. Must be
+/// called before the pipeline leaves the NULL state.
+pub fn force_software(pipeline: &gst::Pipeline) {
+    pipeline.connect("deep-element-added", false, |args| {
+        let element = args[2].get::<gst::Element>().ok()?;
+        if element.factory().is_some_and(|f| f.name() == "decodebin") {
+            skip_hardware_in(&element);
+        }
+        None
+    });
+}
+
+/// Answer decodebin's `autoplug-select` with SKIP for hardware video decoders.
+fn skip_hardware_in(decodebin: &gst::Element) {
+    // GstAutoplugSelectResult is registered by the playback plugin, not gstreamer-rs
+    let Some(class) =
+        glib::Type::from_name("GstAutoplugSelectResult").and_then(glib::EnumClass::with_type)
+    else {
+        log::warn!("GstAutoplugSelectResult not registered; cannot force software decoding");
+        return;
+    };
+    let (Some(proceed), Some(skip)) = (class.to_value(0), class.to_value(2)) else {
+        return;
+    };
+    decodebin.connect("autoplug-select", false, move |args| {
+        let hardware = args[3]
+            .get::<gst::ElementFactory>()
+            .is_ok_and(|f| is_hardware_decoder(&f));
+        Some(if hardware {
+            skip.clone()
+        } else {
+            proceed.clone()
+        })
+    });
+}
+
+/// Whether a bus message was posted by a hardware video decoder.
+pub fn from_hardware_decoder(msg: &gst::Message) -> bool {
+    msg.src()
+        .and_then(|src| src.downcast_ref::<gst::Element>())
+        .and_then(|element| element.factory())
+        .is_some_and(|f| is_hardware_decoder(&f))
+}
+
+/// Name of the video decoder factory autoplugged into a running pipeline.
+pub fn active_video_decoder(pipeline: &gst::Pipeline) -> Option<String> {
+    pipeline
+        .iterate_recurse()
+        .into_iter()
+        .filter_map(Result::ok)
+        .find_map(|element| {
+            let factory = element.factory()?;
+            is_video_decoder(&factory).then(|| factory.name().to_string())
+        })
+}
@@ -0,0 +1,169 @@
+//! Push-based events from loader and video worker threads.
+//!
+//! Background threads send [`WorkerEvent`]s over an unbounded channel; the UI
+//! receives them through a single long-lived subscription instead of polling.
+//! Position updates are coalesced on a [`PositionBoard`], so a full grid of playing
+//! videos wakes the UI once per frame interval rather than once per frame per video.
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+use futures::stream::BoxStream;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use crate::message::Message;
+
+/// Sending half handed to loader threads and pipeline probes.
+pub type EventSender = mpsc::UnboundedSender<WorkerEvent>;
+
+/// Shortest time between two position wake-ups (one frame at 30 fps).
+const POSITION_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Something happened on a background thread that the UI should react to.
+#[derive(Clone, Debug)]
+pub enum WorkerEvent {
+    /// A load result is waiting in the load channel.
+    MediaLoaded,
+    /// Videos on the [`PositionBoard`] have new frames; their cached positions moved.
+    Positions,
+    /// End of stream reached the appsink.
+    EndOfStream(usize),
+    /// The pipeline posted an error.
+    Error(usize, String),
+    /// A hardware video decoder failed mid-stream; the video should be reloaded in software.
+    DecoderFailed(usize, String),
+    /// The item's thumbnail is cached in this file.
+    ThumbnailReady(usize, PathBuf),
+    /// A still was written to this file, or failed to export.
+    StillExported(Result<PathBuf, String>),
+    /// A difference or onion-skin blend finished, with the generation of its request
+    /// (None if a frame couldn't be read).
+    BlendReady(u64, Option<iced::widget::image::Handle>),
+    /// New per-frame scores from a quality analysis run, with the fraction done.
+    QualityFrames(u64, Vec<crate::quality::FrameMetrics>, f64),
+    /// A quality analysis run finished, failed or was cancelled.
+    QualityFinished(u64, Result<(), String>),
+    /// A scope of a cell was rendered (None if its frame couldn't be read).
+    ScopeReady(usize, Option<iced::widget::image::Handle>),
+    /// Exposure aids were drawn on a photo's pixels, with the aids used.
+    ExposureReady(
+        usize,
+        iced::widget::image::Handle,
+        crate::exposure::ExposureAids,
+    ),
+    /// Letterbox detection finished for a video (None if it has no bars).
+    LetterboxDetected(usize, Option<crate::transform::CropRect>),
+    /// An embedded subtitle cue was demuxed.
+    SubtitleCue(usize, crate::subtitles::Cue),
+}
+
+/// Channel pair; the receiver is moved into the subscription on first use.
+pub struct EventChannel {
+    pub tx: EventSender,
+    pub positions: Arc<PositionBoard>,
+    rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<WorkerEvent>>>>,
+}
+
+impl Default for EventChannel {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded();
+        let positions = Arc::new(PositionBoard::default());
+        positions.clone().spawn_wakeups(tx.clone());
+        EventChannel {
+            tx,
+            positions,
+            rx: Arc::new(Mutex::new(Some(rx))),
+        }
+    }
+}
+
+/// Videos whose cached position moved since the UI last read them.
+#[derive(Default)]
+pub struct PositionBoard {
+    state: Mutex<BoardState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct BoardState {
+    dirty: BTreeSet<usize>,
+    /// A wake-up was sent and the UI has not taken the marked videos yet.
+    awaiting_ui: bool,
+}
+
+impl PositionBoard {
+    /// Mark a video as having a new frame. Called from its appsink probe.
+    pub fn mark(&self, video_id: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.dirty.insert(video_id) {
+                self.changed.notify_one();
+            }
+        }
+    }
+
+    /// Videos marked since the last call, clearing the marks.
+    pub fn take(&self) -> Vec<usize> {
+        let Ok(mut state) = self.state.lock() else {
+            return Vec::new();
+        };
+        state.awaiting_ui = false;
+        self.changed.notify_one();
+        std::mem::take(&mut state.dirty).into_iter().collect()
+    }
+
+    /// Send [`WorkerEvent::Positions`] whenever videos are marked, at most once per
+    /// [`POSITION_INTERVAL`] and never while the UI has one still to handle.
+    fn spawn_wakeups(self: Arc<Self>, tx: EventSender) {
+        std::thread::Builder::new()
+            .name("position-wakeups".to_string())
+            .spawn(move || {
+                loop {
+                    {
+                        let Ok(state) = self.state.lock() else {
+                            return;
+                        };
+                        let Ok(mut state) = self
+                            .changed
+                            .wait_while(state, |s| s.dirty.is_empty() || s.awaiting_ui)
+                        else {
+                            return;
+                        };
+                        state.awaiting_ui = true;
+                    }
+                    if tx.unbounded_send(WorkerEvent::Positions).is_err() {
+                        return;
+                    }
+                    std::thread::sleep(POSITION_INTERVAL);
+                }
+            })
+            .expect("Failed to spawn position wake-up thread");
+    }
+}
+
+impl EventChannel {
+    /// Subscription delivering worker events as messages.
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        iced::Subscription::run_with(Receiver(self.rx.clone()), Receiver::stream)
+    }
+}
+
+/// Subscription data; hashes to a constant so the stream is built exactly once.
+struct Receiver(Arc<Mutex<Option<mpsc::UnboundedReceiver<WorkerEvent>>>>);
+
+impl Hash for Receiver {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        "cineplex-worker-events".hash(state);
+    }
+}
+
+impl Receiver {
+    fn stream(&self) -> BoxStream<'static, Message> {
+        match self.0.lock().ok().and_then(|mut rx| rx.take()) {
+            Some(rx) => rx.map(Message::Worker).boxed(),
+            None => futures::stream::empty().boxed(),
+        }
+    }
+}
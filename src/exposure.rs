@@ -0,0 +1,190 @@
+//! Exposure aids: zebra stripes over bright areas and a false-color exposure map.
+//!
+//! Both are applied to decoded pixels. Videos are processed in place by a probe on the
+//! appsink, after the frame tap, so blends and scopes still see the clean frame. Photos
+//! are processed on a background thread from their decoded pixels into a separate
+//! handle that is shown instead while an aid is on.
+
+use gstreamer as gst;
+use iced::widget::image::Handle;
+use std::sync::Mutex;
+
+use crate::events::{EventSender, WorkerEvent};
+use crate::nv12::{self, Planes};
+
+/// Zebra thresholds offered, in IRE.
+pub const ZEBRA_LEVELS: [u8; 5] = [70, 80, 90, 95, 100];
+/// Width of a zebra stripe, in frame pixels.
+const STRIPE_WIDTH: usize = 4;
+
+/// False-color bands: IRE range and the color it is painted. Levels outside every band
+/// are shown in grayscale.
+const FALSE_COLOR_BANDS: [(f32, f32, [u8; 3]); 7] = [
+    (f32::MIN, 2.5, [128, 0, 160]), // Crushed blacks
+    (2.5, 10.0, [0, 64, 255]),      // Near black
+    (38.0, 42.0, [0, 170, 60]),     // 18% gray
+    (52.0, 56.0, [255, 128, 170]),  // Skin, one stop over
+    (90.0, 97.0, [230, 230, 230]),  // Bright highlights
+    (97.0, 99.0, [255, 220, 0]),    // Near clipping
+    (99.0, f32::MAX, [255, 0, 0]),  // Clipped
+];
+
+/// Which aids a cell shows, and the zebra threshold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExposureAids {
+    pub zebras: bool,
+    pub false_color: bool,
+    pub zebra_ire: u8, // Zebras cover levels at or above this
+}
+
+impl Default for ExposureAids {
+    fn default() -> Self {
+        ExposureAids {
+            zebras: false,
+            false_color: false,
+            zebra_ire: 95,
+        }
+    }
+}
+
+impl ExposureAids {
+    pub fn is_active(&self) -> bool {
+        self.zebras || self.false_color
+    }
+
+    /// Next zebra threshold in [`ZEBRA_LEVELS`], wrapping around.
+    pub fn next_zebra_level(ire: u8) -> u8 {
+        ZEBRA_LEVELS
+            .iter()
+            .copied()
+            .find(|&level| level > ire)
+            .unwrap_or(ZEBRA_LEVELS[0])
+    }
+
+    /// False color of an IRE level, or None to keep it in grayscale.
+    fn false_color(ire: f32) -> Option<[u8; 3]> {
+        FALSE_COLOR_BANDS
+            .iter()
+            .find(|(low, high, _)| (*low..*high).contains(&ire))
+            .map(|&(_, _, color)| color)
+    }
+
+    /// Whether `(x, y)` is on a dark stripe of the diagonal zebra pattern.
+    fn on_stripe(x: usize, y: usize) -> bool {
+        (x + y) / STRIPE_WIDTH % 2 == 0
+    }
+
+    /// Apply the aids to a mapped NV12 frame in place (BT.709 limited range).
+    fn apply_nv12(&self, planes: Planes<&mut [u8]>) {
+        let Planes {
+            luma,
+            chroma,
+            luma_stride,
+            chroma_stride,
+            width,
+            height,
+        } = planes;
+        let ire = |y: u8| (y as f32 - 16.0) / 219.0 * 100.0;
+        let zebra_luma = 16.0 + self.zebra_ire as f32 * 2.19;
+
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * luma_stride + x;
+                let level = luma[i];
+                if self.false_color {
+                    // Chroma is shared by 2x2 blocks; the block's top-left pixel picks it
+                    let color = Self::false_color(ire(level));
+                    if let Some(rgb) = color {
+                        luma[i] = rgb_to_yuv(rgb)[0];
+                    }
+                    if x % 2 == 0 && y % 2 == 0 {
+                        let uv = (y / 2) * chroma_stride + x;
+                        let [_, u, v] = color.map_or([0, 128, 128], rgb_to_yuv);
+                        chroma[uv] = u;
+                        chroma[uv + 1] = v;
+                    }
+                }
+                if self.zebras && level as f32 >= zebra_luma && Self::on_stripe(x, y) {
+                    luma[i] = 16;
+                }
+            }
+        }
+    }
+
+    /// Apply the aids to tightly packed RGBA pixels (full range) in place.
+    fn apply_rgba(&self, pixels: &mut [u8], width: usize) {
+        for (index, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (index % width, index / width);
+            let luma =
+                0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
+            let ire = luma / 255.0 * 100.0;
+            if self.false_color {
+                let gray = luma.round() as u8;
+                let rgb = Self::false_color(ire).unwrap_or([gray; 3]);
+                pixel[..3].copy_from_slice(&rgb);
+            }
+            if self.zebras && ire >= self.zebra_ire as f32 && Self::on_stripe(x, y) {
+                pixel[..3].fill(0);
+            }
+        }
+    }
+}
+
+/// RGB to BT.709 limited-range YUV.
+fn rgb_to_yuv([r, g, b]: [u8; 3]) -> [u8; 3] {
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    [
+        16.0 + (0.2126 * r + 0.7152 * g + 0.0722 * b) * 219.0 / 255.0,
+        128.0 + (-0.1146 * r - 0.3854 * g + 0.5 * b) * 224.0 / 255.0,
+        128.0 + (0.5 * r - 0.4542 * g - 0.0458 * b) * 224.0 / 255.0,
+    ]
+    .map(|c| c.round().clamp(0.0, 255.0) as u8)
+}
+
+/// The aids a video's frames are drawn with, changed at runtime.
+#[derive(Default)]
+pub struct ExposureStage {
+    aids: Mutex<ExposureAids>,
+}
+
+impl ExposureStage {
+    pub fn set(&self, aids: ExposureAids) {
+        if let Ok(mut current) = self.aids.lock() {
+            *current = aids;
+        }
+    }
+
+    /// Draw the aids on a buffer arriving at the appsink, copying it first if shared.
+    pub fn process(&self, pad: &gst::Pad, buffer: &mut gst::Buffer) {
+        let Some(aids) = self.aids.lock().ok().map(|a| *a).filter(|a| a.is_active()) else {
+            return;
+        };
+        let Some(info) = nv12::pad_video_info(pad) else {
+            return;
+        };
+        nv12::with_planes_mut(buffer.make_mut(), &info, |planes| aids.apply_nv12(planes));
+    }
+}
+
+/// Draw the aids on a photo's pixels on a background thread. The result arrives as
+/// [`WorkerEvent::ExposureReady`] with the aids it was drawn with.
+pub fn apply_to_photo_async(id: usize, handle: Handle, aids: ExposureAids, events: EventSender) {
+    std::thread::Builder::new()
+        .name(format!("exposure-{}", id))
+        .spawn(move || {
+            let Handle::Rgba {
+                width,
+                height,
+                pixels,
+                ..
+            } = handle
+            else {
+                return;
+            };
+            let mut pixels = pixels.to_vec();
+            aids.apply_rgba(&mut pixels, width as usize);
+            let image = Handle::from_rgba(width, height, pixels);
+            let _ = events.unbounded_send(WorkerEvent::ExposureReady(id, image, aids));
+        })
+        .expect("Failed to spawn exposure thread");
+}
@@ -0,0 +1,84 @@
+//! How media is fitted into its cell when not zoomed.
+
+use iced::{ContentFit, Point, Size};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FitMode {
+    /// Whole frame visible, letterboxed to the cell's aspect ratio.
+    Contain,
+    /// Cell filled, cropping whatever overflows.
+    Cover,
+    /// Stretched to the cell, ignoring the aspect ratio.
+    Fill,
+    /// One source pixel per screen pixel, centered and cropped to the cell.
+    Native,
+}
+
+impl FitMode {
+    pub fn next(self) -> Self {
+        match self {
+            FitMode::Contain => FitMode::Cover,
+            FitMode::Cover => FitMode::Fill,
+            FitMode::Fill => FitMode::Native,
+            FitMode::Native => FitMode::Contain,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FitMode::Contain => "contain",
+            FitMode::Cover => "cover",
+            FitMode::Fill => "fill",
+            FitMode::Native => "1:1",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "contain" => Some(FitMode::Contain),
+            "cover" => Some(FitMode::Cover),
+            "fill" => Some(FitMode::Fill),
+            "1:1" | "native" => Some(FitMode::Native),
+            _ => None,
+        }
+    }
+
+    /// Widget content fit for the scaled modes (`Native` is laid out by [`native_layout`]).
+    pub fn content_fit(self) -> ContentFit {
+        match self {
+            FitMode::Contain => ContentFit::Contain,
+            FitMode::Cover => ContentFit::Cover,
+            FitMode::Fill => ContentFit::Fill,
+            FitMode::Native => ContentFit::None,
+        }
+    }
+
+    /// Size and top-left offset of `source` pixels drawn in `cell` at this fit.
+    pub fn layout(self, cell: Size, source: (u32, u32), scale_factor: f32) -> (Size, Point) {
+        let (src_w, src_h) = (source.0.max(1) as f32, source.1.max(1) as f32);
+        let scale = match self {
+            FitMode::Contain => (cell.width / src_w).min(cell.height / src_h),
+            FitMode::Cover => (cell.width / src_w).max(cell.height / src_h),
+            FitMode::Fill => return (cell, Point::ORIGIN),
+            FitMode::Native => return native_layout(cell, source, scale_factor),
+        };
+        let size = Size::new(src_w * scale, src_h * scale);
+        let offset = Point::new(
+            (cell.width - size.width) / 2.0,
+            (cell.height - size.height) / 2.0,
+        );
+        (size, offset)
+    }
+}
+
+/// Size and top-left offset of `source` pixels shown 1:1 and centered in `cell`.
+/// The scale factor converts physical pixels to the logical units layout works in.
+pub fn native_layout(cell: Size, source: (u32, u32), scale_factor: f32) -> (Size, Point) {
+    let scale = scale_factor.max(f32::EPSILON);
+    let size = Size::new(source.0 as f32 / scale, source.1 as f32 / scale);
+    let offset = Point::new(
+        (cell.width - size.width) / 2.0,
+        (cell.height - size.height) / 2.0,
+    );
+    (size, offset)
+}
@@ -0,0 +1,152 @@
+//! Detection of black letterbox and pillarbox bars baked into videos.
+//!
+//! A few frames spread over the video are decoded to luma on a background thread.
+//! Rows and columns that stay black in every sampled frame are bars; frames that are
+//! black throughout (fades, title cards) say nothing about bars and are skipped.
+//! Detected bars are cropped in the pipeline unless the cell turns the crop off.
+
+use std::path::{Path, PathBuf};
+
+use crate::events::{EventSender, WorkerEvent};
+use crate::quality::{LumaDecoder, LumaFrame};
+use crate::transform::CropRect;
+
+/// Frames sampled, evenly spaced over the duration.
+const SAMPLE_FRAMES: usize = 8;
+/// Luma at or below this counts as black (video black is 16).
+const BLACK_LUMA: u8 = 32;
+/// Fraction of brighter pixels a black row or column may have (grain, compression noise).
+const BRIGHT_TOLERANCE: f32 = 0.02;
+/// Bars thinner than this fraction of the frame are left alone.
+const MIN_BAR_FRACTION: f32 = 0.01;
+
+/// What is known about a video's bars.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Letterbox {
+    /// Not detected yet, or detection is running.
+    Unknown,
+    NoBars,
+    /// Bars found; the crop keeps the picture between them.
+    Bars(CropRect),
+}
+
+/// Detect bars on a background thread. The result arrives as
+/// [`WorkerEvent::LetterboxDetected`]; failures are reported as no bars.
+pub fn detect_async(path: PathBuf, id: usize, events: EventSender) {
+    std::thread::Builder::new()
+        .name(format!("letterbox-{}", id))
+        .spawn(move || {
+            let crop = detect(&path).unwrap_or_else(|e| {
+                log::warn!("Letterbox detection failed: id={}, error={}", id, e);
+                None
+            });
+            log::debug!("Letterbox detected: id={}, crop={:?}", id, crop);
+            let _ = events.unbounded_send(WorkerEvent::LetterboxDetected(id, crop));
+        })
+        .expect("Failed to spawn letterbox detection thread");
+}
+
+/// Crop removing the bars of a video file (upright, before any manual transform),
+/// or None if it has none.
+fn detect(path: &Path) -> Result<Option<CropRect>, Box<dyn std::error::Error>> {
+    let decoder = LumaDecoder::open(path, None, 0.0)?;
+    let duration = decoder.duration().unwrap_or(0.0);
+    let mut bars: Option<Edges> = None;
+    let mut size = (0, 0);
+
+    for i in 0..SAMPLE_FRAMES {
+        // Without a duration, the first frames are all there is to go on
+        if duration > 0.0 {
+            decoder.seek_keyframe(duration * (i as f64 + 0.5) / SAMPLE_FRAMES as f64)?;
+        }
+        let Some(frame) = decoder.next()? else {
+            break;
+        };
+        let Some(edges) = frame_edges(&frame) else {
+            continue;
+        };
+        size = (frame.width, frame.height);
+        // Bars must be constant: keep the thinnest seen on each side
+        bars = Some(match bars {
+            Some(b) => Edges {
+                left: b.left.min(edges.left),
+                top: b.top.min(edges.top),
+                right: b.right.min(edges.right),
+                bottom: b.bottom.min(edges.bottom),
+            },
+            None => edges,
+        });
+    }
+
+    Ok(bars.and_then(|b| b.to_crop(size)))
+}
+
+/// Black rows or columns on each side of a frame.
+#[derive(Clone, Copy, Debug)]
+struct Edges {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+}
+
+impl Edges {
+    /// Crop keeping the picture inside bars of useful thickness.
+    fn to_crop(self, size: (usize, usize)) -> Option<CropRect> {
+        let (width, height) = (size.0 as f32, size.1 as f32);
+        if width == 0.0 || height == 0.0 {
+            return None;
+        }
+        let fraction = |px: usize, len: f32| {
+            let f = px as f32 / len;
+            if f < MIN_BAR_FRACTION { 0.0 } else { f }
+        };
+        let (left, right) = (fraction(self.left, width), fraction(self.right, width));
+        let (top, bottom) = (fraction(self.top, height), fraction(self.bottom, height));
+        if left + right + top + bottom == 0.0 {
+            return None;
+        }
+        Some(CropRect {
+            x: left,
+            y: top,
+            width: 1.0 - left - right,
+            height: 1.0 - top - bottom,
+        })
+        .filter(|c| c.width > MIN_BAR_FRACTION && c.height > MIN_BAR_FRACTION)
+    }
+}
+
+/// Bars of one frame, or None if the whole frame is black.
+fn frame_edges(frame: &LumaFrame) -> Option<Edges> {
+    let (width, height) = (frame.width, frame.height);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let data = &frame.data;
+    let row_tolerance = (width as f32 * BRIGHT_TOLERANCE) as usize;
+    let column_tolerance = (height as f32 * BRIGHT_TOLERANCE) as usize;
+    let row_black = |y: &usize| {
+        data[y * width..(y + 1) * width]
+            .iter()
+            .filter(|&&v| v > BLACK_LUMA)
+            .count()
+            <= row_tolerance
+    };
+    let column_black = |x: &usize| {
+        (0..height)
+            .filter(|y| data[y * width + x] > BLACK_LUMA)
+            .count()
+            <= column_tolerance
+    };
+
+    let top = (0..height).take_while(row_black).count();
+    if top == height {
+        return None;
+    }
+    Some(Edges {
+        left: (0..width).take_while(column_black).count(),
+        top,
+        right: (0..width).rev().take_while(column_black).count(),
+        bottom: (0..height).rev().take_while(row_black).count(),
+    })
+}
@@ -3,13 +3,30 @@ use gstreamer::prelude::*;
 use gstreamer_app as gst_app;
 use iced::widget::image::Handle;
 use iced_video_player::Video;
+use image::imageops::FilterType;
 use image::{DynamicImage, ImageDecoder, ImageReader};
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::time::Instant;
 
-use crate::state::{LoadResult, PhotoInstance, VideoInstance};
+use crate::animation::{self, Animation};
+use crate::blend::FrameTap;
+use crate::chapters;
+use crate::color::ColorAdjust;
+use crate::decoders;
+use crate::events::{EventSender, PositionBoard, WorkerEvent};
+use crate::exposure::{ExposureAids, ExposureStage};
+use crate::letterbox::Letterbox;
+use crate::lut::LutStage;
+use crate::settings::FramerateMode;
+use crate::state::{DecodedPhoto, LoadResult, PhotoInstance, PipelineHandles, VideoInstance};
+use crate::subtitles::{self, SubtitleSource};
+use crate::timecode::Timebase;
+use crate::transform::Transform;
+use crate::zoom::ZoomView;
 
 /// Supported video extensions (case-insensitive check performed separately).
 const VIDEO_EXTENSIONS: &[&str] = &["mov", "mp4", "m4v", "mkv", "avi", "webm"];
@@ -19,8 +36,21 @@ const IMAGE_EXTENSIONS: &[&str] = &[
     "jpg", "jpeg", "png", "gif", "bmp", "webp", "tiff", "tif", "heic", "heif",
 ];
 
+/// Fixed rate used in native mode when a stream reports 0 fps (VFR content).
+const VFR_FALLBACK_FPS: u32 = 30;
+
+/// Settings that affect how media is loaded.
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    pub framerate_mode: FramerateMode,
+    /// Skip hardware video decoders, after one failed on this file.
+    pub software_decoding: bool,
+    /// Longest side photos are decoded at (see [`crate::photos::max_decode_dimension`]).
+    pub max_photo_dimension: u32,
+}
+
 /// Determine if a path is a video file.
-fn is_video_file(path: &PathBuf) -> bool {
+pub fn is_video_file(path: &PathBuf) -> bool {
     path.extension()
         .and_then(OsStr::to_str)
         .map(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
@@ -40,54 +70,181 @@ pub fn is_supported_media_file(path: &PathBuf) -> bool {
     is_video_file(path) || is_image_file(path)
 }
 
-/// Spawn a background thread to load a media file asynchronously.
-/// Results are sent back via the provided channel.
-pub fn load_media_async(tx: mpsc::Sender<LoadResult>, path: PathBuf, id: usize) {
+/// Spawn a background thread to load a media file asynchronously, with the file's
+/// manual `transform` applied. Results are sent back via the provided channel, followed
+/// by a [`WorkerEvent::MediaLoaded`] notification on the event channel.
+pub fn load_media_async(
+    tx: mpsc::Sender<LoadResult>,
+    events: EventSender,
+    positions: Arc<PositionBoard>,
+    path: PathBuf,
+    id: usize,
+    options: LoadOptions,
+    transform: Transform,
+) {
     std::thread::Builder::new()
         .name(format!("media-loader-{}", id))
         .spawn(move || {
             let result = if is_video_file(&path) {
-                load_video_on_thread(&path, id)
+                load_video_on_thread(&path, id, &options, transform, &events, &positions)
             } else if is_image_file(&path) {
-                load_photo_on_thread(&path, id)
+                load_photo_on_thread(&path, id, options.max_photo_dimension, transform)
             } else {
-                LoadResult::Error(format!(
-                    "Unsupported file type: {}",
-                    path.extension()
-                        .and_then(OsStr::to_str)
-                        .unwrap_or("unknown")
-                ))
+                LoadResult::Error(
+                    id,
+                    format!(
+                        "Unsupported file type: {}",
+                        path.extension()
+                            .and_then(OsStr::to_str)
+                            .unwrap_or("unknown")
+                    ),
+                )
             };
             let _ = tx.send(result);
+            let _ = events.unbounded_send(WorkerEvent::MediaLoaded);
         })
         .expect("Failed to spawn media loader thread");
 }
 
+/// Decode a loaded photo again on a background thread, at most `max_dimension` on its
+/// longest side (None for full resolution). The result arrives as
+/// [`LoadResult::PhotoImage`], followed by a [`WorkerEvent::MediaLoaded`] notification.
+pub fn reload_photo_async(
+    tx: mpsc::Sender<LoadResult>,
+    events: EventSender,
+    path: PathBuf,
+    id: usize,
+    max_dimension: Option<u32>,
+    transform: Transform,
+) {
+    std::thread::Builder::new()
+        .name(format!("photo-reload-{}", id))
+        .spawn(move || {
+            let image = decode_photo(&path, max_dimension, &transform).map_err(|e| e.to_string());
+            let _ = tx.send(LoadResult::PhotoImage {
+                id,
+                image,
+                full_res: max_dimension.is_none(),
+            });
+            let _ = events.unbounded_send(WorkerEvent::MediaLoaded);
+        })
+        .expect("Failed to spawn photo reload thread");
+}
+
+/// Build the playbin description for a video.
+///
+/// videoflip applies automatic rotation based on metadata. videorate feeds a capsfilter
+/// that pins the framerate in fixed mode (also needed for VFR content that reports 0 fps)
+/// and is left open in native mode; it is also lowered to throttle hidden videos.
+/// The capsfilter after videoscale starts unrestricted and is narrowed to the cell size later.
+/// The first videocrop removes detected letterbox bars; the named videoflip and the second
+/// videocrop apply the manual transform. All three are updated at runtime.
+/// videobalance and gamma apply the cell's color adjustments (passthrough at the defaults);
+/// a LUT, if any, is applied by a probe on the appsink.
+/// text-sink takes embedded subtitles off the frames; a probe turns them into cues.
+/// audio-sink=fakesink prevents CoreAudio mutex contention for muted videos.
+/// When the user unmutes, the audio sink is swapped to autoaudiosink in app.rs.
+fn build_video_pipeline(
+    url: &url::Url,
+    framerate_mode: FramerateMode,
+    transform: &Transform,
+) -> String {
+    let rate_caps = match framerate_mode {
+        FramerateMode::Native => "video/x-raw".to_string(),
+        FramerateMode::Fixed(fps) => format!("video/x-raw,framerate={}/1", fps),
+    };
+    format!(
+        "playbin uri=\"{}\" audio-sink=fakesink \
+         text-sink=\"appsink name=cineplex_text sync=false async=false drop=true max-buffers=1\" \
+         video-sink=\"videoflip method=automatic ! \
+         videocrop name=cineplex_letterbox ! \
+         videoflip name=cineplex_flip method={} ! videocrop name=cineplex_crop ! \
+         videorate ! capsfilter name=cineplex_rate caps={} ! \
+         videoscale ! capsfilter name=cineplex_scale ! videoconvert ! \
+         videobalance name=cineplex_balance ! gamma name=cineplex_gamma ! \
+         appsink name=iced_video drop=true caps=video/x-raw,format=NV12,pixel-aspect-ratio=1/1\"",
+        url.as_str(),
+        transform.videoflip_method(),
+        rate_caps
+    )
+}
+
 /// Load a video on a background thread, returning a LoadResult.
-fn load_video_on_thread(video_path: &PathBuf, video_id: usize) -> LoadResult {
+fn load_video_on_thread(
+    video_path: &PathBuf,
+    video_id: usize,
+    options: &LoadOptions,
+    transform: Transform,
+    events: &EventSender,
+    positions: &Arc<PositionBoard>,
+) -> LoadResult {
     let url = match url::Url::from_file_path(video_path) {
         Ok(u) => u,
-        Err(_) => return LoadResult::Error("Invalid video path".to_string()),
+        Err(_) => return LoadResult::Error(video_id, "Invalid video path".to_string()),
     };
 
-    // Create pipeline with videoflip for automatic rotation based on metadata
-    // videorate ensures a fixed framerate (needed for VFR content that reports 0 fps)
-    // audio-sink=fakesink prevents CoreAudio mutex contention for muted videos.
-    // When the user unmutes, the audio sink is swapped to autoaudiosink in app.rs.
-    let pipeline_str = format!(
-        "playbin uri=\"{}\" audio-sink=fakesink \
-         video-sink=\"videoflip method=automatic ! videorate ! video/x-raw,framerate=30/1 ! \
-         videoscale ! videoconvert ! \
-         appsink name=iced_video drop=true caps=video/x-raw,format=NV12,pixel-aspect-ratio=1/1\"",
-        url.as_str()
-    );
-
-    let video = match create_video_from_pipeline(&pipeline_str) {
+    let pipeline_str = build_video_pipeline(&url, options.framerate_mode, &transform);
+    let mut software = options.software_decoding;
+    let created =
+        match create_video_from_pipeline(&pipeline_str, video_id, events, positions, software) {
+            // A hardware decoder may be missing or unusable on this machine;
+            // retry this video with software decoding only.
+            Err(e) if !software => {
+                log::warn!("Load failed, retrying with software decoding: {}", e);
+                software = true;
+                create_video_from_pipeline(&pipeline_str, video_id, events, positions, software)
+            }
+            result => result,
+        };
+    let (mut video, mut pipeline) = match created {
         Ok(v) => v,
-        Err(e) => return LoadResult::Error(format!("Failed to load video: {}", e)),
+        Err(e) => return LoadResult::Error(video_id, format!("Failed to load video: {}", e)),
     };
 
+    // Without a fixed rate, VFR streams report 0 fps and can't be paced; resample those.
+    if options.framerate_mode == FramerateMode::Native {
+        let fps = video.framerate();
+        if !fps.is_finite() || fps <= 0.0 {
+            log::warn!(
+                "No native framerate for {} (reported {}), falling back to {} fps",
+                video_path.display(),
+                fps,
+                VFR_FALLBACK_FPS
+            );
+            drop(video);
+            let pipeline_str =
+                build_video_pipeline(&url, FramerateMode::Fixed(VFR_FALLBACK_FPS), &transform);
+            (video, pipeline) = match create_video_from_pipeline(
+                &pipeline_str,
+                video_id,
+                events,
+                positions,
+                software,
+            ) {
+                Ok(v) => v,
+                Err(e) => {
+                    return LoadResult::Error(video_id, format!("Failed to load video: {}", e));
+                }
+            };
+        }
+    }
+
     let native_fps = video.framerate();
+    let decoder = decoders::active_video_decoder(&pipeline.pipeline);
+    let timebase = Timebase::new(source_fps(&pipeline.pipeline).unwrap_or(native_fps));
+    let start_timecode = start_timecode(&pipeline.pipeline, &timebase);
+    if let Some(start) = start_timecode {
+        log::info!(
+            "Start timecode of {}: {}",
+            video_path.display(),
+            timebase.format(start)
+        );
+    }
+    // Prerolled with the orientation but no crops yet; undo the turns for the upright size
+    let (width, height) = video.size();
+    let upright_size = transform.oriented_size((width.max(0) as u32, height.max(0) as u32));
+    pipeline.apply_transform(&transform, upright_size);
+    let source_size = transform.output_size(upright_size);
     let duration = {
         let raw_duration = video.duration().as_secs_f64();
         log::info!(
@@ -106,9 +263,21 @@ fn load_video_on_thread(video_path: &PathBuf, video_id: usize) -> LoadResult {
             1.0
         }
     };
+    let filename = video_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let sidecars = subtitles::load_sidecars(video_path);
+    let chapters = chapters::read(&pipeline.pipeline);
+    if !chapters.is_empty() {
+        log::info!("{} chapters in {}", chapters.len(), video_path.display());
+    }
     let video_instance = VideoInstance {
         id: video_id,
         video,
+        path: video_path.clone(),
+        filename,
         position: 0.0,
         duration,
         dragging: false,
@@ -117,36 +286,92 @@ fn load_video_on_thread(video_path: &PathBuf, video_id: usize) -> LoadResult {
         fullscreen: false,
         _temp_dir: None,
         native_fps,
+        timebase,
+        start_timecode: start_timecode.unwrap_or(0),
+        measured_fps: 0.0,
+        fps_sample: (Instant::now(), 0),
+        pipeline,
+        source_size,
+        upright_size,
+        transform,
+        letterbox: Letterbox::Unknown,
+        keep_bars: false,
+        color: ColorAdjust::default(),
+        lut: None,
+        show_color: false,
+        scope: None,
+        aids: ExposureAids::default(),
+        show_caption: false,
+        subtitles: if sidecars.is_empty() {
+            SubtitleSource::Off
+        } else {
+            SubtitleSource::Sidecar(0)
+        },
+        sidecars,
+        embedded_cues: Vec::new(),
+        subtitle_delay: 0.0,
+        sync_offset: 0.0,
+        tracks: None,
+        decode_size: None,
+        hidden: None,
+        decoder: decoder.clone(),
+        software_decode: software,
+        show_info: false,
+        view: ZoomView::default(),
+        fit: None,
+        markers: Vec::new(),
+        chapters,
         last_mouse_activity: Instant::now(),
     };
 
     crate::gst_logger::log_video_created(video_id, &video_path.display().to_string());
     log::info!(
-        "Video loaded (async): id={}, path={}, fps={}",
+        "Video loaded (async): id={}, path={}, fps={}, decoder={}",
         video_id,
         video_path.display(),
         native_fps,
+        decoder.as_deref().unwrap_or("unknown"),
     );
 
     LoadResult::Video(video_instance)
 }
 
 /// Load a photo on a background thread, returning a LoadResult.
-fn load_photo_on_thread(photo_path: &PathBuf, photo_id: usize) -> LoadResult {
+fn load_photo_on_thread(
+    photo_path: &PathBuf,
+    photo_id: usize,
+    max_dimension: u32,
+    transform: Transform,
+) -> LoadResult {
     let filename = photo_path
         .file_name()
         .unwrap_or_default()
         .to_string_lossy()
         .to_string();
 
-    let handle = match load_image_with_orientation(photo_path) {
-        Ok(h) => h,
-        Err(e) => return LoadResult::Error(format!("Failed to load image: {}", e)),
+    let image = match decode_photo(photo_path, Some(max_dimension), &transform) {
+        Ok(image) => image,
+        Err(e) => return LoadResult::Error(photo_id, format!("Failed to load image: {}", e)),
     };
 
     let photo_instance = PhotoInstance {
         id: photo_id,
-        handle,
+        path: photo_path.clone(),
+        handle: Some(image.handle),
+        size: image.size,
+        full_size: image.full_size,
+        full_handle: None,
+        reload_pending: false,
+        reload_error: None,
+        last_visible: Instant::now(),
+        view: ZoomView::default(),
+        fit: None,
+        transform,
+        scope: None,
+        aids: ExposureAids::default(),
+        aid_handle: None,
+        animation: image.frames.map(Animation::new),
+        show_caption: false,
         hovered: false,
         fullscreen: false,
         filename: filename.clone(),
@@ -154,35 +379,105 @@ fn load_photo_on_thread(photo_path: &PathBuf, photo_id: usize) -> LoadResult {
     };
 
     log::info!(
-        "Photo loaded (async): id={}, path={}",
+        "Photo loaded (async): id={}, path={}, size={:?}, full_size={:?}, frames={}",
         photo_id,
         photo_path.display(),
+        photo_instance.size,
+        photo_instance.full_size,
+        photo_instance
+            .animation
+            .as_ref()
+            .map_or(1, |a| a.frame_count()),
     );
 
     LoadResult::Photo(photo_instance)
 }
 
 /// Load an image file and apply EXIF orientation correction.
-fn load_image_with_orientation(path: &PathBuf) -> Result<Handle, Box<dyn std::error::Error>> {
+pub fn open_oriented(path: &Path) -> Result<DynamicImage, Box<dyn std::error::Error>> {
     let mut decoder = ImageReader::open(path)?.into_decoder()?;
     let orientation = decoder.orientation()?;
     let mut img = DynamicImage::from_decoder(decoder)?;
     img.apply_orientation(orientation);
+    Ok(img)
+}
+
+/// Load an image file, apply EXIF orientation correction and the manual `transform`,
+/// and downscale it so its longest side is at most `max_dimension` (None keeps full
+/// resolution). Animated images are decoded frame by frame.
+fn decode_photo(
+    path: &Path,
+    max_dimension: Option<u32>,
+    transform: &Transform,
+) -> Result<DecodedPhoto, Box<dyn std::error::Error>> {
+    if let Some(frames) = animation::decode(path, max_dimension, transform)? {
+        return Ok(DecodedPhoto {
+            handle: frames.handles[0].clone(),
+            size: frames.size,
+            full_size: frames.full_size,
+            frames: Some(frames),
+        });
+    }
+
+    let mut img = transform.apply_to_image(open_oriented(path)?);
+
+    let full_size = (img.width(), img.height());
+    if let Some(max) = max_dimension {
+        if full_size.0.max(full_size.1) > max {
+            img = img.resize(max, max, FilterType::Triangle);
+        }
+    }
 
     let rgba = img.to_rgba8();
     let (width, height) = rgba.dimensions();
     let pixels = rgba.into_raw();
 
-    Ok(Handle::from_rgba(width, height, pixels))
+    Ok(DecodedPhoto {
+        handle: Handle::from_rgba(width, height, pixels),
+        size: (width, height),
+        full_size,
+        frames: None,
+    })
+}
+
+/// Framerate of the decoded stream, before it is resampled to a fixed rate.
+fn source_fps(pipeline: &gst::Pipeline) -> Option<f64> {
+    let video_sink: gst::Element = pipeline.property("video-sink");
+    let caps = video_sink.pads().first()?.current_caps()?;
+    let rate = caps.structure(0)?.get::<gst::Fraction>("framerate").ok()?;
+    (rate.numer() > 0 && rate.denom() > 0).then(|| rate.numer() as f64 / rate.denom() as f64)
+}
+
+/// Start timecode of a video in frames, from its container or stream tags.
+fn start_timecode(pipeline: &gst::Pipeline, timebase: &Timebase) -> Option<u64> {
+    let tags = pipeline.emit_by_name::<Option<gst::TagList>>("get-video-tags", &[&0i32])?;
+    tags.iter_generic().find_map(|(name, mut values)| {
+        values.find_map(|value| timebase.parse_tag(name, value.get::<&str>().ok()?))
+    })
 }
 
 /// Create a Video from a custom GStreamer pipeline string.
-fn create_video_from_pipeline(pipeline_str: &str) -> Result<Video, Box<dyn std::error::Error>> {
+///
+/// Also returns handles to the elements adjusted at runtime, including a counter of
+/// frames delivered to the appsink (the framerate actually reaching the screen).
+/// New frames are marked on `positions`; end-of-stream and error notifications are
+/// pushed to `events`.
+/// With `software`, hardware video decoders are skipped in this pipeline.
+fn create_video_from_pipeline(
+    pipeline_str: &str,
+    video_id: usize,
+    events: &EventSender,
+    positions: &Arc<PositionBoard>,
+    software: bool,
+) -> Result<(Video, PipelineHandles), Box<dyn std::error::Error>> {
     gst::init()?;
 
     let pipeline = gst::parse::launch(pipeline_str)?
         .downcast::<gst::Pipeline>()
         .map_err(|_| "Failed to cast to Pipeline")?;
+    if software {
+        decoders::force_software(&pipeline);
+    }
 
     // Get the video sink from playbin and extract the appsink
     let video_sink: gst::Element = pipeline.property("video-sink");
@@ -202,13 +497,111 @@ fn create_video_from_pipeline(pipeline_str: &str) -> Result<Video, Box<dyn std::
         .downcast::<gst_app::AppSink>()
         .map_err(|_| "Failed to cast to AppSink")?;
 
+    let scale_filter = bin.by_name("cineplex_scale");
+    let letterbox = bin.by_name("cineplex_letterbox");
+    let flip = bin.by_name("cineplex_flip");
+    let crop = bin.by_name("cineplex_crop");
+    let balance = bin.by_name("cineplex_balance");
+    let gamma = bin.by_name("cineplex_gamma");
+    let rate_filter = bin.by_name("cineplex_rate");
+    let rate_caps = rate_filter
+        .as_ref()
+        .map(|f| f.property::<gst::Caps>("caps"));
+
+    let delivered_frames = Arc::new(AtomicU64::new(0));
+    let frame_tap = Arc::new(FrameTap::default());
+    let lut = Arc::new(LutStage::default());
+    let exposure = Arc::new(ExposureStage::default());
+    if let Some(sink_pad) = video_sink.static_pad("sink") {
+        // Grade frames before anything else sees them
+        let stage = lut.clone();
+        sink_pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+            if let Some(gst::PadProbeData::Buffer(ref mut buffer)) = info.data {
+                stage.process(pad, buffer);
+            }
+            gst::PadProbeReturn::Ok
+        });
+
+        // Count frames, mark the position as moved and feed the frame tap
+        let counter = delivered_frames.clone();
+        let tap = frame_tap.clone();
+        let board = positions.clone();
+        sink_pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            if let Some(gst::PadProbeData::Buffer(ref buffer)) = info.data {
+                tap.offer(pad, buffer);
+            }
+            board.mark(video_id);
+            gst::PadProbeReturn::Ok
+        });
+
+        // Exposure aids go on after the tap, so blends and scopes see the clean frame
+        let stage = exposure.clone();
+        sink_pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+            if let Some(gst::PadProbeData::Buffer(ref mut buffer)) = info.data {
+                stage.process(pad, buffer);
+            }
+            gst::PadProbeReturn::Ok
+        });
+
+        let events_tx = events.clone();
+        sink_pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+            if let Some(gst::PadProbeData::Event(ref event)) = info.data {
+                if event.type_() == gst::EventType::Eos {
+                    let _ = events_tx.unbounded_send(WorkerEvent::EndOfStream(video_id));
+                }
+            }
+            gst::PadProbeReturn::Ok
+        });
+    }
+
+    // Embedded subtitles reach the text sink as cues instead of being burned in
+    let text_sink: Option<gst::Element> = pipeline.property("text-sink");
+    if let Some(text_pad) = text_sink.and_then(|s| s.pads().first().cloned()) {
+        subtitles::forward_cues(&text_pad, video_id, events.clone());
+    }
+
     // Set mute/volume on the playbin BEFORE from_gst_pipeline starts playback,
     // otherwise audio briefly plays when loading many videos at once.
     pipeline.set_property("mute", true);
     pipeline.set_property("volume", 0.0f64);
 
+    let handles = PipelineHandles {
+        pipeline: pipeline.clone(),
+        scale_filter,
+        letterbox,
+        flip,
+        crop,
+        balance,
+        gamma,
+        lut,
+        exposure,
+        rate_filter,
+        rate_caps,
+        delivered_frames,
+        frame_tap,
+    };
+
     let video = Video::from_gst_pipeline(pipeline, video_sink, None)?;
     video.set_looping(true);
 
-    Ok(video)
+    // Observe errors without consuming them: sync-message signals sit alongside any
+    // sync handler and leave every message on the bus for the player to pop.
+    if let Some(bus) = handles.pipeline.bus() {
+        let events_tx = events.clone();
+        bus.enable_sync_message_emission();
+        bus.connect_sync_message(Some("error"), move |_, msg| {
+            if let gst::MessageView::Error(err) = msg.view() {
+                let error = err.error().to_string();
+                let event = if !software && decoders::from_hardware_decoder(msg) {
+                    WorkerEvent::DecoderFailed(video_id, error)
+                } else {
+                    WorkerEvent::Error(video_id, error)
+                };
+                let _ = events_tx.unbounded_send(event);
+            }
+        });
+    }
+
+    Ok((video, handles))
 }
@@ -0,0 +1,368 @@
+//! 3D LUTs loaded from `.cube` files, applied to decoded video frames.
+//!
+//! There is no stock GStreamer element for 3D LUTs, so frames are graded in place by a
+//! probe on the appsink, after decoding is scaled to the cell. The LUT is resampled once
+//! into the NV12 frames' own YUV space (BT.709 limited range) and packed into a table
+//! holding the graded pixel for every luma value and 64 levels of each chroma channel,
+//! so grading costs one table read per pixel and no arithmetic beyond averaging the
+//! chroma each 2x2 block shares. The table takes 3 MB per LUT file.
+
+use gstreamer as gst;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::nv12::{self, Planes};
+
+/// Grid points per axis of the resampled YUV table.
+const YUV_GRID: usize = 33;
+/// Levels per chroma axis of the packed table. Chroma is read to the nearest level,
+/// within two code values, which the LUT's own smoothness hides.
+const CHROMA_LEVELS: usize = 64;
+/// Largest `LUT_3D_SIZE` accepted; real LUTs stay well below.
+const MAX_CUBE_SIZE: usize = 256;
+
+/// A 3D LUT as stored in a `.cube` file: RGB in the domain, RGB out, red fastest.
+struct Cube {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    data: Vec<[f32; 3]>,
+}
+
+impl Cube {
+    fn parse(contents: &str) -> Result<Cube, String> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut data = Vec::new();
+        let triple = |fields: &[&str]| -> Option<[f32; 3]> {
+            match fields {
+                [r, g, b] => Some([r.parse().ok()?, g.parse().ok()?, b.parse().ok()?]),
+                _ => None,
+            }
+        };
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let invalid = || format!("line {}: invalid {:?}", number + 1, line);
+            match fields[0] {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err("1D LUTs are not supported".to_string()),
+                "LUT_3D_SIZE" => {
+                    size = fields
+                        .get(1)
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .filter(|s| (2..=MAX_CUBE_SIZE).contains(s));
+                    if size.is_none() {
+                        return Err(invalid());
+                    }
+                }
+                "DOMAIN_MIN" => domain_min = triple(&fields[1..]).ok_or_else(invalid)?,
+                "DOMAIN_MAX" => domain_max = triple(&fields[1..]).ok_or_else(invalid)?,
+                // Resolve's spelling of one domain for all three channels
+                "LUT_3D_INPUT_RANGE" => {
+                    let [min, max] = &fields[1..] else {
+                        return Err(invalid());
+                    };
+                    let (Ok(min), Ok(max)) = (min.parse::<f32>(), max.parse::<f32>()) else {
+                        return Err(invalid());
+                    };
+                    domain_min = [min; 3];
+                    domain_max = [max; 3];
+                }
+                // Other tools' keywords don't change how the table is read
+                keyword if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    log::debug!("Skipping .cube keyword {}", keyword);
+                }
+                _ => data.push(triple(&fields).ok_or_else(invalid)?),
+            }
+        }
+
+        let size = size.ok_or("missing LUT_3D_SIZE")?;
+        if data.len() != size * size * size {
+            return Err(format!(
+                "expected {} entries for size {}, found {}",
+                size * size * size,
+                size,
+                data.len()
+            ));
+        }
+        if (0..3).any(|c| domain_max[c] <= domain_min[c]) {
+            return Err("empty DOMAIN_MIN..DOMAIN_MAX".to_string());
+        }
+        Ok(Cube {
+            size,
+            domain_min,
+            domain_max,
+            data,
+        })
+    }
+
+    /// Graded color of an RGB input in 0..1.
+    fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max = (self.size - 1) as f32;
+        let pos = |c: usize| {
+            ((rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]))
+                .clamp(0.0, 1.0)
+                * max
+        };
+        trilinear(&self.data, self.size, [pos(0), pos(1), pos(2)])
+    }
+}
+
+/// A LUT resampled for grading NV12 frames.
+pub struct Lut {
+    /// File name, for display.
+    pub name: String,
+    /// Graded YUV for every luma value and `CHROMA_LEVELS`² chroma values, Y fastest.
+    packed: Vec<[u8; 3]>,
+}
+
+impl Lut {
+    /// Read and resample a `.cube` file.
+    pub fn load(path: &Path) -> Result<Lut, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let cube = Cube::parse(&contents)?;
+
+        // Graded YUV (0..255) on a coarse grid over YUV inputs, Y fastest
+        let step = 255.0 / (YUV_GRID - 1) as f32;
+        let mut table = Vec::with_capacity(YUV_GRID * YUV_GRID * YUV_GRID);
+        for v in 0..YUV_GRID {
+            for u in 0..YUV_GRID {
+                for y in 0..YUV_GRID {
+                    let yuv = [y as f32 * step, u as f32 * step, v as f32 * step];
+                    let rgb = yuv_to_rgb(yuv).map(|c| (c / 255.0).clamp(0.0, 1.0));
+                    table.push(rgb_to_yuv(cube.sample(rgb).map(|c| c * 255.0)));
+                }
+            }
+        }
+
+        // Interpolated once per packed entry, not once per pixel
+        let scale = (YUV_GRID - 1) as f32 / 255.0;
+        let chroma_step = 255.0 / (CHROMA_LEVELS - 1) as f32;
+        let mut packed = Vec::with_capacity(256 * CHROMA_LEVELS * CHROMA_LEVELS);
+        for v in 0..CHROMA_LEVELS {
+            for u in 0..CHROMA_LEVELS {
+                for y in 0..256 {
+                    let yuv = [y as f32, u as f32 * chroma_step, v as f32 * chroma_step];
+                    let graded = trilinear(&table, YUV_GRID, yuv.map(|c| c * scale));
+                    packed.push(graded.map(|c| c.round().clamp(0.0, 255.0) as u8));
+                }
+            }
+        }
+        Ok(Lut {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            packed,
+        })
+    }
+
+    /// Grade a mapped NV12 frame in place.
+    fn apply_nv12(&self, planes: Planes<&mut [u8]>) {
+        let Planes {
+            luma,
+            chroma,
+            luma_stride,
+            chroma_stride,
+            width,
+            height,
+        } = planes;
+        let level = |c: u8| (c as usize * (CHROMA_LEVELS - 1) + 127) / 255;
+        for by in (0..height).step_by(2) {
+            for bx in (0..width).step_by(2) {
+                let uv = (by / 2) * chroma_stride + bx;
+                // The block's row of the table, indexed by luma
+                let graded = &self.packed
+                    [(level(chroma[uv]) + level(chroma[uv + 1]) * CHROMA_LEVELS) * 256..][..256];
+                let (mut sum_u, mut sum_v, mut count) = (0, 0, 0);
+                for y in by..(by + 2).min(height) {
+                    for x in bx..(bx + 2).min(width) {
+                        let i = y * luma_stride + x;
+                        let [gy, gu, gv] = graded[luma[i] as usize];
+                        luma[i] = gy;
+                        sum_u += gu as u32;
+                        sum_v += gv as u32;
+                        count += 1;
+                    }
+                }
+                chroma[uv] = ((sum_u + count / 2) / count) as u8;
+                chroma[uv + 1] = ((sum_v + count / 2) / count) as u8;
+            }
+        }
+    }
+}
+
+/// Trilinear lookup in a `size`³ table at grid position `pos`, first axis fastest.
+fn trilinear(table: &[[f32; 3]], size: usize, pos: [f32; 3]) -> [f32; 3] {
+    let max = size - 1;
+    let lower = pos.map(|p| (p.floor() as usize).min(max.saturating_sub(1)));
+    let frac = [0, 1, 2].map(|c| (pos[c] - lower[c] as f32).clamp(0.0, 1.0));
+    let at = |x: usize, y: usize, z: usize| table[x + y * size + z * size * size];
+
+    let mut out = [0.0; 3];
+    for (dz, wz) in [(0, 1.0 - frac[2]), (1, frac[2])] {
+        for (dy, wy) in [(0, 1.0 - frac[1]), (1, frac[1])] {
+            for (dx, wx) in [(0, 1.0 - frac[0]), (1, frac[0])] {
+                let weight = wx * wy * wz;
+                if weight == 0.0 {
+                    continue;
+                }
+                let value = at(lower[0] + dx, lower[1] + dy, lower[2] + dz);
+                for (o, v) in out.iter_mut().zip(value) {
+                    *o += v * weight;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// BT.709 limited-range YUV to RGB, both 0..255 (RGB unclamped).
+fn yuv_to_rgb([y, u, v]: [f32; 3]) -> [f32; 3] {
+    let c = (y - 16.0) * 1.164;
+    let (d, e) = (u - 128.0, v - 128.0);
+    [c + 1.793 * e, c - 0.213 * d - 0.533 * e, c + 2.112 * d]
+}
+
+/// RGB to BT.709 limited-range YUV, both 0..255.
+fn rgb_to_yuv([r, g, b]: [f32; 3]) -> [f32; 3] {
+    [
+        16.0 + (0.2126 * r + 0.7152 * g + 0.0722 * b) * 219.0 / 255.0,
+        128.0 + (-0.1146 * r - 0.3854 * g + 0.5 * b) * 224.0 / 255.0,
+        128.0 + (0.5 * r - 0.4542 * g - 0.0458 * b) * 224.0 / 255.0,
+    ]
+}
+
+/// The LUT a video's frames are graded with, swapped at runtime.
+#[derive(Default)]
+pub struct LutStage {
+    lut: Mutex<Option<Arc<Lut>>>,
+}
+
+impl LutStage {
+    pub fn set(&self, lut: Option<Arc<Lut>>) {
+        if let Ok(mut current) = self.lut.lock() {
+            *current = lut;
+        }
+    }
+
+    /// Grade a buffer arriving at the appsink, copying it first if it is shared.
+    pub fn process(&self, pad: &gst::Pad, buffer: &mut gst::Buffer) {
+        let Some(lut) = self.lut.lock().ok().and_then(|l| l.clone()) else {
+            return;
+        };
+        let Some(info) = nv12::pad_video_info(pad) else {
+            return;
+        };
+        nv12::with_planes_mut(buffer.make_mut(), &info, |planes| lut.apply_nv12(planes));
+    }
+}
+
+/// LUTs loaded so far, so cells sharing a file share one table.
+#[derive(Default)]
+pub struct LutCache {
+    entries: HashMap<PathBuf, Option<Arc<Lut>>>,
+}
+
+impl LutCache {
+    /// The LUT in `path`, loaded on first use. Files that fail to load are logged once
+    /// and treated as no LUT.
+    pub fn get(&mut self, path: &Path) -> Option<Arc<Lut>> {
+        self.entries
+            .entry(path.to_path_buf())
+            .or_insert_with(|| match Lut::load(path) {
+                Ok(lut) => Some(Arc::new(lut)),
+                Err(e) => {
+                    log::warn!("Failed to load LUT {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .clone()
+    }
+
+    /// Load (or reload) the LUT in `path`, reporting why it failed.
+    pub fn load(&mut self, path: &Path) -> Result<Arc<Lut>, String> {
+        let lut = Arc::new(Lut::load(path)?);
+        self.entries.insert(path.to_path_buf(), Some(lut.clone()));
+        Ok(lut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A size-2 identity cube with `header` before its entries.
+    fn identity(header: &str) -> String {
+        let mut contents = format!("{}\nLUT_3D_SIZE 2\n", header);
+        for b in 0..2 {
+            for g in 0..2 {
+                for r in 0..2 {
+                    contents.push_str(&format!("{} {} {}\n", r, g, b));
+                }
+            }
+        }
+        contents
+    }
+
+    #[test]
+    fn header_lines_are_read_before_the_entries() {
+        let cube = Cube::parse(&identity("# comment\nTITLE \"Identity\"")).unwrap();
+        assert_eq!(cube.size, 2);
+        assert_eq!(cube.data.len(), 8);
+        assert_eq!(cube.data[1], [1.0, 0.0, 0.0]);
+        assert_eq!(cube.data[2], [0.0, 1.0, 0.0]);
+        assert_eq!(cube.data[4], [0.0, 0.0, 1.0]);
+        assert_eq!(cube.sample([0.25, 0.5, 0.75]), [0.25, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn domain_keywords_rescale_the_input() {
+        let cube = Cube::parse(&identity("DOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 4 1")).unwrap();
+        assert_eq!(cube.domain_min, [0.0, 0.0, 0.0]);
+        assert_eq!(cube.domain_max, [2.0, 4.0, 1.0]);
+        assert_eq!(cube.sample([1.0, 1.0, 1.0]), [0.5, 0.25, 1.0]);
+
+        let cube = Cube::parse(&identity("LUT_3D_INPUT_RANGE -0.5 1.5")).unwrap();
+        assert_eq!(cube.domain_min, [-0.5; 3]);
+        assert_eq!(cube.domain_max, [1.5; 3]);
+
+        assert!(Cube::parse(&identity("DOMAIN_MIN 1 1 1\nDOMAIN_MAX 1 1 1")).is_err());
+        assert!(Cube::parse(&identity("LUT_3D_INPUT_RANGE 0")).is_err());
+        assert!(Cube::parse(&identity("DOMAIN_MIN 0 0")).is_err());
+    }
+
+    #[test]
+    fn entry_count_must_match_the_size() {
+        let mut contents = identity("");
+        contents.push_str("1 1 1\n");
+        let err = Cube::parse(&contents).err().unwrap();
+        assert_eq!(err, "expected 8 entries for size 2, found 9");
+
+        assert!(Cube::parse("0 0 0\n").is_err());
+        assert!(Cube::parse("LUT_3D_SIZE 1\n0 0 0\n").is_err());
+        assert!(Cube::parse("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_err());
+        assert!(Cube::parse(&identity("").replace("1 1 1", "1 1")).is_err());
+    }
+
+    #[test]
+    fn resolve_files_parse_with_their_extra_keywords() {
+        let header = "# Created by: DaVinci Resolve\n\
+            LUT_3D_INPUT_RANGE 0.0000000000 1.0000000000\n\
+            LUT_IN_VIDEO_RANGE\n\
+            LUT_OUT_VIDEO_RANGE";
+        let cube = Cube::parse(&identity(header)).unwrap();
+        assert_eq!(cube.size, 2);
+        assert_eq!(cube.domain_min, [0.0; 3]);
+        assert_eq!(cube.domain_max, [1.0; 3]);
+        assert_eq!(cube.data.len(), 8);
+    }
+}
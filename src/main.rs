@@ -1,11 +1,39 @@
+mod animation;
 mod app;
+mod blend;
+mod caption;
+mod chapters;
+mod color;
+mod decoders;
+mod events;
+mod exposure;
+mod fit;
 mod gst_logger;
+mod letterbox;
 mod loader;
+mod lut;
+mod markers;
+mod media_prefs;
 mod message;
+mod nv12;
+mod photos;
+mod quality;
+mod scaling;
+mod scopes;
+mod settings;
 mod state;
+mod stills;
+mod subtitles;
 mod sync;
+mod thumbnails;
+mod timecode;
+mod tracks;
+mod transform;
 mod ui;
+mod viewport;
 mod watchdog;
+mod worker;
+mod zoom;
 
 use iced::Task;
 use message::Message;
@@ -20,6 +48,15 @@ fn main() -> iced::Result {
     // Register HEIC/HEIF decoder hooks for the image crate
     libheif_rs::integration::image::register_all_decoding_hooks();
 
+    // Headless subcommands run without opening a window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("quality") {
+        std::process::exit(quality::run_cli(&args[1..]));
+    }
+    if args.iter().any(|arg| arg == "--clear-thumbnails") {
+        std::process::exit(thumbnails::run_clear_cli());
+    }
+
     // Collect initial files from command-line arguments
     let initial_files = collect_initial_files();
 
@@ -0,0 +1,220 @@
+//! Timestamped review markers and their CSV / CMX3600 EDL export.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::timecode::Timebase;
+
+/// Markers closer than this to the current position are skipped when jumping,
+/// so repeated presses move past the marker we just landed on.
+const JUMP_EPSILON_SECS: f64 = 0.25;
+
+/// Record timecode the exported EDL starts at (01:00:00:00 is the usual convention).
+const EDL_RECORD_START_HOURS: u64 = 1;
+
+/// A single marker dropped at a position in a media item.
+#[derive(Clone, Debug)]
+pub struct Marker {
+    pub position: f64,
+    pub note: String,
+}
+
+/// A marker together with the media it belongs to, for export.
+pub struct ExportRow<'a> {
+    pub filename: &'a str,
+    pub timebase: Timebase,
+    pub start_timecode: u64, // Source timecode of the media's first frame, in frames
+    pub marker: &'a Marker,
+}
+
+impl ExportRow<'_> {
+    /// Source frame (as timecode counts it) the marker sits on.
+    fn source_frame(&self) -> u64 {
+        self.start_timecode + self.timebase.frames(self.marker.position)
+    }
+}
+
+/// Insert a marker keeping the list sorted by position. Returns its index.
+pub fn insert_marker(markers: &mut Vec<Marker>, position: f64) -> usize {
+    let idx = markers.partition_point(|m| m.position <= position);
+    markers.insert(
+        idx,
+        Marker {
+            position,
+            note: String::new(),
+        },
+    );
+    idx
+}
+
+/// Position of the first marker after `position`, if any.
+pub fn next_marker(markers: &[Marker], position: f64) -> Option<f64> {
+    markers
+        .iter()
+        .map(|m| m.position)
+        .find(|&p| p > position + JUMP_EPSILON_SECS)
+}
+
+/// Position of the last marker before `position`, if any.
+pub fn prev_marker(markers: &[Marker], position: f64) -> Option<f64> {
+    markers
+        .iter()
+        .rev()
+        .map(|m| m.position)
+        .find(|&p| p < position - JUMP_EPSILON_SECS)
+}
+
+/// Write `<base>.csv` and `<base>.edl` next to each other.
+pub fn export(base: &Path, rows: &[ExportRow<'_>]) -> std::io::Result<()> {
+    std::fs::write(base.with_extension("csv"), to_csv(rows))?;
+    let title = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Cineplex Markers".to_string());
+    std::fs::write(base.with_extension("edl"), to_edl(&title, rows))?;
+    Ok(())
+}
+
+/// Render markers as CSV (one row per marker).
+pub fn to_csv(rows: &[ExportRow<'_>]) -> String {
+    let mut out = String::from("file,seconds,timecode,fps,note\n");
+    for row in rows {
+        let _ = writeln!(
+            out,
+            "{},{:.3},{},{:.3},{}",
+            csv_escape(row.filename),
+            row.marker.position,
+            row.timebase.format(row.source_frame()),
+            row.timebase.fps,
+            csv_escape(&row.marker.note)
+        );
+    }
+    out
+}
+
+/// Render markers as a CMX3600 EDL with one single-frame event per marker, at each
+/// media's source timecode.
+pub fn to_edl(title: &str, rows: &[ExportRow<'_>]) -> String {
+    // Record side is laid out back-to-back, one frame per marker, using the
+    // timebase of the first item so the record timeline stays continuous.
+    let record = rows
+        .first()
+        .map(|r| r.timebase)
+        .unwrap_or_else(|| Timebase::new(30.0));
+    let mut record_frame = record
+        .parse(&format!("{:02}:00:00:00", EDL_RECORD_START_HOURS))
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "TITLE: {}", title);
+    let _ = writeln!(
+        out,
+        "FCM: {}",
+        if record.drop_frame {
+            "DROP FRAME"
+        } else {
+            "NON-DROP FRAME"
+        }
+    );
+    let _ = writeln!(out);
+
+    for (i, row) in rows.iter().enumerate() {
+        let src_in = row.source_frame();
+        let _ = writeln!(
+            out,
+            "{:03}  AX       V     C        {} {} {} {}",
+            i + 1,
+            row.timebase.format(src_in),
+            row.timebase.format(src_in + 1),
+            record.format(record_frame),
+            record.format(record_frame + 1),
+        );
+        let _ = writeln!(out, "* FROM CLIP NAME: {}", row.filename);
+        if !row.marker.note.is_empty() {
+            // EDL comments are single-line
+            let _ = writeln!(out, "* COMMENT: {}", row.marker.note.replace('\n', " "));
+        }
+        let _ = writeln!(out);
+        record_frame += 1;
+    }
+    out
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(position: f64, note: &str) -> Marker {
+        Marker {
+            position,
+            note: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn markers_stay_sorted_and_jumps_skip_the_current_one() {
+        let mut markers = Vec::new();
+        for position in [5.0, 1.0, 3.0] {
+            insert_marker(&mut markers, position);
+        }
+        let positions: Vec<f64> = markers.iter().map(|m| m.position).collect();
+        assert_eq!(positions, [1.0, 3.0, 5.0]);
+        assert_eq!(next_marker(&markers, 3.1), Some(5.0));
+        assert_eq!(prev_marker(&markers, 3.1), Some(1.0));
+        assert_eq!(next_marker(&markers, 5.0), None);
+    }
+
+    #[test]
+    fn csv_uses_source_timecode_and_escapes_fields() {
+        let m = marker(2.0, "say \"hi\"");
+        let rows = [ExportRow {
+            filename: "a,b.mov",
+            timebase: Timebase::new(25.0),
+            start_timecode: 25 * 3600,
+            marker: &m,
+        }];
+        assert_eq!(
+            to_csv(&rows),
+            "file,seconds,timecode,fps,note\n\
+             \"a,b.mov\",2.000,01:00:02:00,25.000,\"say \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn edl_events_use_each_source_timebase_and_a_continuous_record_side() {
+        let (first, second) = (marker(60.0, "line one\nline two"), marker(2.0, ""));
+        let rows = [
+            ExportRow {
+                filename: "ntsc.mov",
+                timebase: Timebase::new(30000.0 / 1001.0),
+                start_timecode: 0,
+                marker: &first,
+            },
+            ExportRow {
+                filename: "pal.mov",
+                timebase: Timebase::new(25.0),
+                start_timecode: 25 * 3600,
+                marker: &second,
+            },
+        ];
+        let edl = to_edl("Review", &rows);
+        assert!(edl.starts_with("TITLE: Review\nFCM: DROP FRAME\n"));
+        assert!(edl.contains(
+            "001  AX       V     C        00:00:59;28 00:00:59;29 01:00:00;00 01:00:00;01\n"
+        ));
+        assert!(edl.contains(
+            "002  AX       V     C        01:00:02:00 01:00:02:01 01:00:00;01 01:00:00;02\n"
+        ));
+        assert!(edl.contains("* FROM CLIP NAME: ntsc.mov\n* COMMENT: line one line two\n"));
+        assert!(!edl.contains("* FROM CLIP NAME: pal.mov\n* COMMENT"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Per-file display preferences.
+//!
+//! Stored as JSON in `$XDG_CONFIG_HOME/cineplex/media.json`, keyed by the file's
+//! canonical path, so a file is shown the same way whenever it is opened again.
+//! Files left at the defaults have no entry.
+
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::color::ColorAdjust;
+use crate::fit::FitMode;
+use crate::settings::config_dir;
+use crate::transform::{CropRect, Transform};
+
+/// Display overrides remembered for one file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaPrefs {
+    /// Fit mode of the file's cell, None to follow the global default.
+    pub fit: Option<FitMode>,
+    /// Manual rotation, flip and crop.
+    pub transform: Transform,
+    /// Show detected letterbox bars instead of cropping them.
+    pub keep_bars: bool,
+    /// Brightness, contrast, saturation and gamma.
+    pub color: ColorAdjust,
+    /// LUT of the file's cell, None to follow the all-cells LUT.
+    pub lut: Option<PathBuf>,
+}
+
+impl MediaPrefs {
+    fn from_json(value: &Value) -> Self {
+        let crop = value
+            .get("crop")
+            .and_then(Value::as_array)
+            .map(|v| v.iter().filter_map(Value::as_f64).collect::<Vec<_>>())
+            .and_then(|v| match v[..] {
+                [x, y, width, height] => Some(CropRect {
+                    x: x as f32,
+                    y: y as f32,
+                    width: width as f32,
+                    height: height as f32,
+                }),
+                _ => None,
+            })
+            .filter(|c| c.width > 0.0 && c.height > 0.0);
+        // Rotation is stored in degrees, clockwise
+        let degrees = value.get("rotation").and_then(Value::as_u64).unwrap_or(0);
+        MediaPrefs {
+            fit: value
+                .get("fit")
+                .and_then(Value::as_str)
+                .and_then(FitMode::parse),
+            transform: Transform {
+                quarter_turns: (degrees / 90 % 4) as u8,
+                mirrored: value
+                    .get("mirrored")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                crop,
+            },
+            keep_bars: value
+                .get("keep_bars")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            color: value
+                .get("color")
+                .map(ColorAdjust::from_json)
+                .unwrap_or_default(),
+            lut: value.get("lut").and_then(Value::as_str).map(PathBuf::from),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut map = Map::new();
+        if let Some(fit) = self.fit {
+            map.insert("fit".to_string(), json!(fit.as_str()));
+        }
+        let transform = &self.transform;
+        if transform.quarter_turns != 0 {
+            map.insert(
+                "rotation".to_string(),
+                json!(transform.quarter_turns as u32 * 90),
+            );
+        }
+        if transform.mirrored {
+            map.insert("mirrored".to_string(), json!(true));
+        }
+        if let Some(crop) = transform.crop {
+            map.insert(
+                "crop".to_string(),
+                json!([crop.x, crop.y, crop.width, crop.height]),
+            );
+        }
+        if self.keep_bars {
+            map.insert("keep_bars".to_string(), json!(true));
+        }
+        if !self.color.is_neutral() {
+            map.insert("color".to_string(), self.color.to_json());
+        }
+        if let Some(lut) = &self.lut {
+            map.insert("lut".to_string(), json!(lut.to_string_lossy()));
+        }
+        Value::Object(map)
+    }
+}
+
+/// Preferences of every file that has any, loaded once and written on change.
+#[derive(Default)]
+pub struct PrefsStore {
+    entries: HashMap<String, MediaPrefs>,
+}
+
+impl PrefsStore {
+    /// Load the store from disk. A missing or malformed file starts empty.
+    pub fn load() -> Self {
+        let Some(path) = store_path() else {
+            return PrefsStore::default();
+        };
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            return PrefsStore::default();
+        };
+        let entries = match serde_json::from_str::<Value>(&contents) {
+            Ok(Value::Object(map)) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), MediaPrefs::from_json(value)))
+                .filter(|(_, prefs)| *prefs != MediaPrefs::default())
+                .collect(),
+            Ok(_) | Err(_) => {
+                log::warn!("Ignoring malformed media preferences {}", path.display());
+                HashMap::new()
+            }
+        };
+        PrefsStore { entries }
+    }
+
+    /// Preferences for a file (defaults when it has none).
+    pub fn get(&self, path: &Path) -> MediaPrefs {
+        self.entries.get(&key(path)).cloned().unwrap_or_default()
+    }
+
+    /// Change a file's preferences and write the store.
+    pub fn update(&mut self, path: &Path, change: impl FnOnce(&mut MediaPrefs)) {
+        let key = key(path);
+        let mut prefs = self.entries.remove(&key).unwrap_or_default();
+        change(&mut prefs);
+        if prefs != MediaPrefs::default() {
+            self.entries.insert(key, prefs);
+        }
+        self.save();
+    }
+
+    /// Write the store to disk. Failures are logged, not fatal.
+    fn save(&self) {
+        let Some(path) = store_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let map: Map<String, Value> = self
+            .entries
+            .iter()
+            .map(|(key, prefs)| (key.clone(), prefs.to_json()))
+            .collect();
+        let contents = serde_json::to_string_pretty(&Value::Object(map)).unwrap_or_default();
+        if let Err(e) = std::fs::write(&path, contents) {
+            log::warn!(
+                "Failed to save media preferences to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Store key for a file: its canonical path, so different spellings share one entry.
+fn key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn store_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("media.json"))
+}
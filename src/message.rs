@@ -1,3 +1,7 @@
+use crate::color::ColorAdjust;
+use crate::events::WorkerEvent;
+use crate::subtitles::SubtitleSource;
+use crate::tracks::TrackKind;
 use iced::Event;
 use std::path::PathBuf;
 
@@ -8,22 +12,92 @@ pub enum Message {
     ToggleLoop(usize),
     Seek(usize, f64),
     SeekRelease(usize),
-    EndOfStream(usize),
     ToggleMute(usize),
+    ToggleInfo(usize),
+    // Review markers
+    AddMarker(usize),
+    PrevMarker(usize),
+    NextMarker(usize),
+    MarkerNoteChanged(String),
+    CommitMarkerNote,
+    ExportMarkers,
+    // Chapters
+    JumpToChapter(usize, usize),
     // Shared messages (work for both videos and photos)
     RemoveMedia(usize),
     MediaHoverChanged(usize, bool),
     MouseMoved(usize, iced::Point),
     DragStart(usize),
     ToggleFullscreen(usize),
-    // UI fade timer + position polling
+    // Zoom and pan
+    ZoomScroll(usize, iced::mouse::ScrollDelta),
+    ResetZoom(usize),
+    ToggleLinkedZoom,
+    // Fit modes
+    CycleFit(usize),
+    CycleDefaultFit,
+    ToggleLetterbox(usize),
+    // Color adjustments and LUTs
+    ToggleColorPanel(usize),
+    AdjustColor(usize, ColorAdjust),
+    CommitColor(usize),
+    ResetColor(usize),
+    LoadLut(Option<usize>), // None loads the LUT for all cells
+    ClearLut(usize),
+    ToggleColorBypass,
+    // Scopes and exposure aids
+    CycleScope(usize),
+    ToggleZebras(usize),
+    ToggleFalseColor(usize),
+    CycleZebraLevel,
+    // Captions and subtitles
+    ToggleCaption(usize),
+    ExportStill(usize),
+    CycleSubtitles(usize),
+    SetSubtitles(usize, SubtitleSource),
+    CycleCaptionPosition,
+    CycleCaptionSize,
+    // Track picker
+    ToggleTracks(usize),
+    SelectTrack(usize, TrackKind, i32),
+    // Timecode
+    ToggleTimecode,
+    OpenTimecodeEntry(Option<usize>), // None jumps every video
+    TimecodeEntryChanged(String),
+    CommitTimecodeEntry,
+    // Compare mode
+    ToggleSelect(usize),
+    ToggleCompare,
+    CompareWipeStart,
+    CompareMoved(iced::Point),
+    FlipCompare,
+    CycleWipeAxis,
+    CycleCompareMode,
+    BlendAmountChanged(f32),
+    StartQuality,
+    ExportQuality,
+    CloseQuality,
+    // UI fade timer (only while an overlay is fading)
     UiFadeTick,
+    // Frame timer of animated images (only while one is playing)
+    AnimationTick(std::time::Instant),
+    // Trailing scope renders held back by the rate limit (only while one is waiting)
+    ScopeTick(std::time::Instant),
+    // Periodic stall detection and delivered-FPS sampling
+    StallCheck,
+    // Push notifications from loader and video worker threads
+    Worker(WorkerEvent),
     // Grid controls
     IncreaseColumns,
     DecreaseColumns,
+    NextPage,
+    PrevPage,
+    // Settings
+    ToggleFramerateMode,
+    CycleHiddenPlayback,
+    CycleDecoderPreference,
     // File loading
     BrowseFile,
     EventOccurred(Event),
     LoadInitialFiles(Vec<PathBuf>),
-    CheckLoadedMedia,
 }
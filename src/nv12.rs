@@ -0,0 +1,67 @@
+//! NV12 frames as they sit in appsink buffers.
+//!
+//! Decoders and videoconvert may pad rows and planes however they like. Frames are
+//! mapped with `gstreamer_video`, which reads the real strides and plane offsets from
+//! the buffer's `VideoMeta`, falling back to the caps' default layout without one.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_video as gst_video;
+
+/// Video info negotiated on an appsink pad, if it carries NV12.
+pub fn pad_video_info(pad: &gst::Pad) -> Option<gst_video::VideoInfo> {
+    let caps = pad.current_caps()?;
+    let info = gst_video::VideoInfo::from_caps(&caps).ok()?;
+    (info.format() == gst_video::VideoFormat::Nv12).then_some(info)
+}
+
+/// Luma and interleaved chroma planes of a mapped frame.
+pub struct Planes<T> {
+    pub luma: T,
+    pub chroma: T,
+    pub luma_stride: usize,
+    pub chroma_stride: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Run `f` with a frame's planes mapped for reading. None if the buffer doesn't hold
+/// a frame of `info`.
+pub fn with_planes<R>(
+    buffer: &gst::BufferRef,
+    info: &gst_video::VideoInfo,
+    f: impl FnOnce(Planes<&[u8]>) -> R,
+) -> Option<R> {
+    let frame = gst_video::VideoFrameRef::from_buffer_ref_readable(buffer, info).ok()?;
+    let strides = frame.plane_stride();
+    Some(f(Planes {
+        luma: frame.plane_data(0).ok()?,
+        chroma: frame.plane_data(1).ok()?,
+        luma_stride: strides[0].max(0) as usize,
+        chroma_stride: strides[1].max(0) as usize,
+        width: frame.width() as usize,
+        height: frame.height() as usize,
+    }))
+}
+
+/// Run `f` with a frame's planes mapped for writing. None if the buffer doesn't hold
+/// a frame of `info`.
+pub fn with_planes_mut<R>(
+    buffer: &mut gst::BufferRef,
+    info: &gst_video::VideoInfo,
+    f: impl FnOnce(Planes<&mut [u8]>) -> R,
+) -> Option<R> {
+    let mut frame = gst_video::VideoFrameRef::from_buffer_ref_writable(buffer, info).ok()?;
+    let strides = frame.plane_stride();
+    let (luma_stride, chroma_stride) = (strides[0].max(0) as usize, strides[1].max(0) as usize);
+    let (width, height) = (frame.width() as usize, frame.height() as usize);
+    let [luma, chroma, ..] = frame.planes_data_mut();
+    Some(f(Planes {
+        luma,
+        chroma,
+        luma_stride,
+        chroma_stride,
+        width,
+        height,
+    }))
+}
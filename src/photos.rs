@@ -0,0 +1,56 @@
+//! Photo decode sizing and texture memory budget.
+//!
+//! Photos are decoded no larger than the window needs, with full resolution loaded on
+//! demand for fullscreen viewing. When resident RGBA data exceeds the budget, photos
+//! that are off screen give up their pixels, least recently visible first, and are
+//! decoded again when they come back into view.
+
+use iced::Size;
+
+/// Total RGBA bytes kept resident across all photos.
+pub const MEMORY_BUDGET_BYTES: u64 = 512 * 1024 * 1024;
+/// Smallest decode bound, so photos loaded before the window opens aren't tiny.
+const MIN_DECODE_DIMENSION: u32 = 1920;
+/// Re-decode when the window needs this much more than the loaded size.
+const RELOAD_THRESHOLD: f32 = 1.25;
+
+/// Longest side a photo is decoded at for a window of `window` logical size.
+pub fn max_decode_dimension(window: Size, scale_factor: f32) -> u32 {
+    let longest = window.width.max(window.height) * scale_factor;
+    (longest.ceil() as u32).max(MIN_DECODE_DIMENSION)
+}
+
+/// Bytes of an RGBA image of the given size.
+pub fn rgba_bytes(size: (u32, u32)) -> u64 {
+    size.0 as u64 * size.1 as u64 * 4
+}
+
+/// Whether a photo decoded at `loaded` is too soft for `max_dimension`, given the
+/// original `full` size caps what a re-decode can gain.
+pub fn needs_larger_decode(loaded: (u32, u32), full: (u32, u32), max_dimension: u32) -> bool {
+    let loaded_longest = loaded.0.max(loaded.1);
+    let full_longest = full.0.max(full.1);
+    let wanted = max_dimension.min(full_longest);
+    wanted as f32 > loaded_longest as f32 * RELOAD_THRESHOLD
+}
+
+/// Pick photos to evict so `resident` fits the budget.
+///
+/// `candidates` are off-screen photos as `(id, bytes, last_visible)`; the ones seen
+/// longest ago go first.
+pub fn select_evictions<T: Ord + Copy>(
+    resident: u64,
+    mut candidates: Vec<(usize, u64, T)>,
+) -> Vec<usize> {
+    candidates.sort_by_key(|&(_, _, last_visible)| last_visible);
+    let mut total = resident;
+    let mut evicted = Vec::new();
+    for (id, bytes, _) in candidates {
+        if total <= MEMORY_BUDGET_BYTES {
+            break;
+        }
+        total = total.saturating_sub(bytes);
+        evicted.push(id);
+    }
+    evicted
+}
@@ -0,0 +1,616 @@
+//! Objective quality metrics between two videos.
+//!
+//! Both files are decoded to luma in lockstep with throwaway pipelines, B scaled to A's
+//! size, and each frame of A is scored against the frame of B showing at the same time:
+//! PSNR from the mean squared error, and SSIM averaged over overlapping 8x8 windows.
+//! Runs from the compare view on a worker thread, or headless as `cineplex quality`.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use iced::widget::image::Handle;
+use serde_json::{Value, json};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::events::{EventSender, WorkerEvent};
+
+/// PSNR reported for identical frames, which would otherwise be infinite.
+const MAX_PSNR: f64 = 100.0;
+/// SSIM window size and the step between windows.
+const SSIM_WINDOW: usize = 8;
+const SSIM_STEP: usize = 4;
+const SSIM_C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+const SSIM_C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+/// B frames starting this close after an A frame still count as showing with it.
+const PTS_TOLERANCE: f64 = 0.001;
+/// How long to wait for a decode pipeline to preroll.
+const PREROLL_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(10);
+/// Frames scored between progress updates.
+const PROGRESS_BATCH: usize = 10;
+/// Size of the rendered graph, and the value ranges it plots.
+const GRAPH_WIDTH: u32 = 800;
+const GRAPH_HEIGHT: u32 = 160;
+const GRAPH_PSNR_RANGE: (f64, f64) = (20.0, 60.0);
+const GRAPH_SSIM_RANGE: (f64, f64) = (0.8, 1.0);
+
+/// Identifies analysis runs so events from a cancelled run are ignored.
+static NEXT_RUN: AtomicU64 = AtomicU64::new(0);
+
+/// Two videos to compare. B starts `offset` seconds into its file when A starts at
+/// zero (negative offsets skip into A instead).
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub a: PathBuf,
+    pub b: PathBuf,
+    pub offset: f64,
+}
+
+/// Scores of one frame of A.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameMetrics {
+    pub frame: usize,
+    pub time: f64, // Position in A, seconds
+    pub psnr: f64, // dB, capped at MAX_PSNR
+    pub ssim: f64,
+}
+
+/// Averages and worst cases over a run.
+pub struct Summary {
+    pub frames: usize,
+    pub mean_psnr: f64,
+    pub min_psnr: f64,
+    pub mean_ssim: f64,
+    pub min_ssim: f64,
+}
+
+pub fn summarize(frames: &[FrameMetrics]) -> Option<Summary> {
+    if frames.is_empty() {
+        return None;
+    }
+    let count = frames.len() as f64;
+    Some(Summary {
+        frames: frames.len(),
+        mean_psnr: frames.iter().map(|f| f.psnr).sum::<f64>() / count,
+        min_psnr: frames.iter().map(|f| f.psnr).fold(f64::INFINITY, f64::min),
+        mean_ssim: frames.iter().map(|f| f.ssim).sum::<f64>() / count,
+        min_ssim: frames.iter().map(|f| f.ssim).fold(f64::INFINITY, f64::min),
+    })
+}
+
+/// A decoded luma plane, tightly packed.
+pub struct LumaFrame {
+    pub width: usize,
+    pub height: usize,
+    pub pts: f64,
+    pub data: Vec<u8>,
+}
+
+/// Throwaway pipeline decoding a file to 8-bit luma as fast as it can.
+pub struct LumaDecoder {
+    pipeline: gst::Pipeline,
+    sink: gst_app::AppSink,
+}
+
+impl LumaDecoder {
+    /// Open `path` scaled to `size` (or its own size), starting `start` seconds in.
+    pub fn open(
+        path: &Path,
+        size: Option<(usize, usize)>,
+        start: f64,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        gst::init()?;
+        let url = url::Url::from_file_path(path).map_err(|_| "Invalid video path")?;
+        let size_caps = match size {
+            Some((width, height)) => format!(",width={},height={}", width, height),
+            None => String::new(),
+        };
+        let pipeline = gst::parse::launch(&format!(
+            "uridecodebin uri=\"{}\" ! videoflip method=automatic ! videoconvert ! videoscale ! \
+             appsink name=luma sync=false max-buffers=4 \
+             caps=video/x-raw,format=GRAY8,pixel-aspect-ratio=1/1{}",
+            url.as_str(),
+            size_caps
+        ))?
+        .downcast::<gst::Pipeline>()
+        .map_err(|_| "Failed to cast to Pipeline")?;
+        let sink = pipeline
+            .by_name("luma")
+            .ok_or("Could not find luma appsink")?
+            .downcast::<gst_app::AppSink>()
+            .map_err(|_| "Failed to cast to AppSink")?;
+        // From here on, Drop shuts the pipeline down on errors
+        let decoder = LumaDecoder { pipeline, sink };
+
+        decoder.pipeline.set_state(gst::State::Paused)?;
+        decoder.pipeline.state(PREROLL_TIMEOUT).0?;
+        if start > 0.0 {
+            decoder.pipeline.seek_simple(
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::ClockTime::from_nseconds((start * 1e9) as u64),
+            )?;
+            decoder.pipeline.state(PREROLL_TIMEOUT).0?;
+        }
+        decoder.pipeline.set_state(gst::State::Playing)?;
+        Ok(decoder)
+    }
+
+    /// Duration of the file in seconds, if known.
+    pub fn duration(&self) -> Option<f64> {
+        self.pipeline
+            .query_duration::<gst::ClockTime>()
+            .map(|d| d.nseconds() as f64 / 1e9)
+    }
+
+    /// Jump to the keyframe at or before `position` seconds.
+    pub fn seek_keyframe(&self, position: f64) -> Result<(), Box<dyn std::error::Error>> {
+        self.pipeline.seek_simple(
+            gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
+            gst::ClockTime::from_nseconds((position * 1e9) as u64),
+        )?;
+        self.pipeline.state(PREROLL_TIMEOUT).0?;
+        Ok(())
+    }
+
+    /// Next frame, or None at the end of the stream.
+    pub fn next(&self) -> Result<Option<LumaFrame>, Box<dyn std::error::Error>> {
+        let Ok(sample) = self.sink.pull_sample() else {
+            // End of stream, unless the pipeline stopped on an error
+            let error = self
+                .pipeline
+                .bus()
+                .and_then(|bus| bus.pop_filtered(&[gst::MessageType::Error]));
+            return match error.as_ref().map(|msg| msg.view()) {
+                Some(gst::MessageView::Error(err)) => Err(err.error().to_string().into()),
+                _ => Ok(None),
+            };
+        };
+        let structure = sample
+            .caps()
+            .and_then(|caps| caps.structure(0))
+            .ok_or("Sample has no caps")?;
+        let width = structure.get::<i32>("width")?.max(0) as usize;
+        let height = structure.get::<i32>("height")?.max(0) as usize;
+        let buffer = sample.buffer().ok_or("Sample has no buffer")?;
+        let pts = buffer
+            .pts()
+            .map(|t| t.nseconds() as f64 / 1e9)
+            .unwrap_or(0.0);
+        let map = buffer.map_readable()?;
+
+        // GRAY8 rows are padded to 4 bytes
+        let stride = width.next_multiple_of(4);
+        if height == 0 || map.len() < stride * (height - 1) + width {
+            return Err("Frame buffer too small".into());
+        }
+        let mut data = Vec::with_capacity(width * height);
+        for row in map.as_slice().chunks(stride).take(height) {
+            data.extend_from_slice(&row[..width]);
+        }
+        Ok(Some(LumaFrame {
+            width,
+            height,
+            pts,
+            data,
+        }))
+    }
+}
+
+impl Drop for LumaDecoder {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gst::State::Null);
+    }
+}
+
+/// PSNR of two equally sized luma planes.
+fn psnr(a: &[u8], b: &[u8]) -> f64 {
+    let sum: u64 = a
+        .iter()
+        .zip(b)
+        .map(|(&x, &y)| {
+            let d = x as i64 - y as i64;
+            (d * d) as u64
+        })
+        .sum();
+    if sum == 0 {
+        return MAX_PSNR;
+    }
+    let mse = sum as f64 / a.len() as f64;
+    (10.0 * (255.0 * 255.0 / mse).log10()).min(MAX_PSNR)
+}
+
+/// Mean SSIM over overlapping windows of two equally sized luma planes.
+fn ssim(a: &[u8], b: &[u8], width: usize, height: usize) -> f64 {
+    if width < SSIM_WINDOW || height < SSIM_WINDOW {
+        return if a == b { 1.0 } else { 0.0 };
+    }
+    let n = (SSIM_WINDOW * SSIM_WINDOW) as f64;
+    let mut total = 0.0;
+    let mut windows = 0usize;
+    for y0 in (0..=height - SSIM_WINDOW).step_by(SSIM_STEP) {
+        for x0 in (0..=width - SSIM_WINDOW).step_by(SSIM_STEP) {
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0u64, 0u64, 0u64, 0u64, 0u64);
+            for y in y0..y0 + SSIM_WINDOW {
+                let row = y * width;
+                for i in row + x0..row + x0 + SSIM_WINDOW {
+                    let (pa, pb) = (a[i] as u64, b[i] as u64);
+                    sa += pa;
+                    sb += pb;
+                    saa += pa * pa;
+                    sbb += pb * pb;
+                    sab += pa * pb;
+                }
+            }
+            let (mean_a, mean_b) = (sa as f64 / n, sb as f64 / n);
+            let var_a = saa as f64 / n - mean_a * mean_a;
+            let var_b = sbb as f64 / n - mean_b * mean_b;
+            let cov = sab as f64 / n - mean_a * mean_b;
+            total += ((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * cov + SSIM_C2))
+                / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1) * (var_a + var_b + SSIM_C2));
+            windows += 1;
+        }
+    }
+    total / windows as f64
+}
+
+/// Score every frame of A against B. `on_progress` receives each batch of new scores
+/// with the fraction of A processed. Stops with an error once `cancel` is set.
+pub fn analyze(
+    job: &Job,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&[FrameMetrics], f64),
+) -> Result<Vec<FrameMetrics>, Box<dyn std::error::Error>> {
+    let (a_start, b_start) = if job.offset >= 0.0 {
+        (0.0, job.offset)
+    } else {
+        (-job.offset, 0.0)
+    };
+    let a = LumaDecoder::open(&job.a, None, a_start)?;
+    let duration = a.duration().unwrap_or(0.0) - a_start;
+    let first_a = a.next()?.ok_or("Video A has no frames")?;
+    let b = LumaDecoder::open(&job.b, Some((first_a.width, first_a.height)), b_start)?;
+    let mut b_current = b.next()?.ok_or("Video B has no frames")?;
+    let mut b_next = b.next()?;
+    let (a_origin, b_origin) = (first_a.pts, b_current.pts);
+
+    let mut frames: Vec<FrameMetrics> = Vec::new();
+    let mut reported = 0;
+    let mut a_frame = Some(first_a);
+    while let Some(fa) = a_frame {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".into());
+        }
+        let t = fa.pts - a_origin;
+
+        // Advance B to its last frame starting at or before this one
+        loop {
+            match b_next.take() {
+                Some(nb) if nb.pts - b_origin <= t + PTS_TOLERANCE => {
+                    b_current = nb;
+                    b_next = b.next()?;
+                }
+                other => {
+                    b_next = other;
+                    break;
+                }
+            }
+        }
+        // B ran out: stop instead of scoring against its last frame for the rest of A
+        if b_next.is_none() && t - (b_current.pts - b_origin) > 1.0 {
+            break;
+        }
+        if (fa.width, fa.height) != (b_current.width, b_current.height) {
+            return Err("Videos decoded to different frame sizes".into());
+        }
+
+        frames.push(FrameMetrics {
+            frame: frames.len(),
+            time: a_start + t,
+            psnr: psnr(&fa.data, &b_current.data),
+            ssim: ssim(&fa.data, &b_current.data, fa.width, fa.height),
+        });
+        if frames.len() - reported >= PROGRESS_BATCH {
+            let progress = if duration > 0.0 { t / duration } else { 0.0 };
+            on_progress(&frames[reported..], progress.clamp(0.0, 1.0));
+            reported = frames.len();
+        }
+        a_frame = a.next()?;
+    }
+    on_progress(&frames[reported..], 1.0);
+    Ok(frames)
+}
+
+/// Per-frame scores as CSV.
+pub fn to_csv(frames: &[FrameMetrics]) -> String {
+    let mut out = String::from("frame,seconds,psnr,ssim\n");
+    for f in frames {
+        let _ = writeln!(out, "{},{:.3},{:.4},{:.6}", f.frame, f.time, f.psnr, f.ssim);
+    }
+    out
+}
+
+/// Job, summary and per-frame scores as JSON.
+pub fn to_json(job: &Job, frames: &[FrameMetrics]) -> Value {
+    let summary = summarize(frames).map(|s| {
+        json!({
+            "frames": s.frames,
+            "mean_psnr": s.mean_psnr,
+            "min_psnr": s.min_psnr,
+            "mean_ssim": s.mean_ssim,
+            "min_ssim": s.min_ssim,
+        })
+    });
+    json!({
+        "a": job.a.display().to_string(),
+        "b": job.b.display().to_string(),
+        "offset": job.offset,
+        "summary": summary,
+        "frames": frames
+            .iter()
+            .map(|f| json!({"frame": f.frame, "seconds": f.time, "psnr": f.psnr, "ssim": f.ssim}))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Write `<base>.csv` and `<base>.json` next to each other.
+pub fn export(base: &Path, job: &Job, frames: &[FrameMetrics]) -> std::io::Result<()> {
+    std::fs::write(base.with_extension("csv"), to_csv(frames))?;
+    let json = serde_json::to_string_pretty(&to_json(job, frames)).unwrap_or_default();
+    std::fs::write(base.with_extension("json"), json)?;
+    Ok(())
+}
+
+/// Plot PSNR (green) and SSIM (blue) over the frames as an RGBA image. Each column
+/// shows the worst frame it covers, so short dips stay visible in long videos.
+pub fn render_graph(frames: &[FrameMetrics]) -> Handle {
+    let (width, height) = (GRAPH_WIDTH as usize, GRAPH_HEIGHT as usize);
+    let mut pixels = [20u8, 20, 20, 220].repeat(width * height);
+    let mut plot = |x: usize, from: usize, to: usize, color: [u8; 4]| {
+        for y in from.min(to)..=from.max(to) {
+            let i = (y * width + x) * 4;
+            pixels[i..i + 4].copy_from_slice(&color);
+        }
+    };
+    let row = |value: f64, (low, high): (f64, f64)| {
+        let t = ((value - low) / (high - low)).clamp(0.0, 1.0);
+        ((1.0 - t) * (height - 1) as f64).round() as usize
+    };
+
+    if !frames.is_empty() {
+        let mut previous: Option<(usize, usize)> = None;
+        for x in 0..width {
+            let start = x * frames.len() / width;
+            let end = ((x + 1) * frames.len() / width)
+                .max(start + 1)
+                .min(frames.len());
+            let Some(column) = frames.get(start..end) else {
+                break;
+            };
+            let psnr = column.iter().map(|f| f.psnr).fold(f64::INFINITY, f64::min);
+            let ssim = column.iter().map(|f| f.ssim).fold(f64::INFINITY, f64::min);
+            let (py, sy) = (row(psnr, GRAPH_PSNR_RANGE), row(ssim, GRAPH_SSIM_RANGE));
+            let (prev_py, prev_sy) = previous.unwrap_or((py, sy));
+            plot(x, prev_py, py, [80, 220, 100, 255]);
+            plot(x, prev_sy, sy, [90, 160, 255, 255]);
+            previous = Some((py, sy));
+        }
+    }
+    Handle::from_rgba(GRAPH_WIDTH, GRAPH_HEIGHT, pixels)
+}
+
+/// An analysis started from the compare view. Dropping it cancels the run.
+pub struct Analysis {
+    pub run: u64,
+    pub job: Job,
+    pub frames: Vec<FrameMetrics>,
+    pub progress: f64,
+    pub result: Option<Result<(), String>>, // Set once the run ends
+    pub graph: Handle,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Analysis {
+    /// Start analyzing on a worker thread. Scores arrive as
+    /// [`WorkerEvent::QualityFrames`], the outcome as [`WorkerEvent::QualityFinished`].
+    pub fn start(job: Job, events: EventSender) -> Self {
+        let run = NEXT_RUN.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_job = job.clone();
+        let thread_cancel = cancel.clone();
+        std::thread::Builder::new()
+            .name("quality".to_string())
+            .spawn(move || {
+                let result = analyze(&thread_job, &thread_cancel, |frames, progress| {
+                    let _ = events.unbounded_send(WorkerEvent::QualityFrames(
+                        run,
+                        frames.to_vec(),
+                        progress,
+                    ));
+                });
+                let result = result.map(|_| ()).map_err(|e| e.to_string());
+                let _ = events.unbounded_send(WorkerEvent::QualityFinished(run, result));
+            })
+            .expect("Failed to spawn quality thread");
+        log::info!(
+            "Quality analysis started: run={}, a={}, b={}",
+            run,
+            job.a.display(),
+            job.b.display()
+        );
+        Analysis {
+            run,
+            job,
+            frames: Vec::new(),
+            progress: 0.0,
+            result: None,
+            graph: render_graph(&[]),
+            cancel,
+        }
+    }
+
+    /// Add a batch of scores and redraw the graph.
+    pub fn push_frames(&mut self, frames: Vec<FrameMetrics>, progress: f64) {
+        self.frames.extend(frames);
+        self.progress = progress;
+        self.graph = render_graph(&self.frames);
+    }
+}
+
+impl Drop for Analysis {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+fn usage() -> i32 {
+    eprintln!("Usage: cineplex quality <a> <b> [--offset SECONDS] [--csv FILE] [--json FILE]");
+    2
+}
+
+/// `cineplex quality <a> <b> [--offset SECONDS] [--csv FILE] [--json FILE]`
+///
+/// Prints a summary to stderr and the per-frame CSV to stdout unless an output file is
+/// given. Returns the process exit code.
+pub fn run_cli(args: &[String]) -> i32 {
+    let mut paths = Vec::new();
+    let mut offset = 0.0;
+    let mut csv_path = None;
+    let mut json_path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--offset" => match iter.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(value) if value.is_finite() => offset = value,
+                _ => return usage(),
+            },
+            "--csv" => match iter.next() {
+                Some(path) => csv_path = Some(PathBuf::from(path)),
+                None => return usage(),
+            },
+            "--json" => match iter.next() {
+                Some(path) => json_path = Some(PathBuf::from(path)),
+                None => return usage(),
+            },
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let Ok([a, b]) = <[PathBuf; 2]>::try_from(paths) else {
+        return usage();
+    };
+    let (Ok(a), Ok(b)) = (a.canonicalize(), b.canonicalize()) else {
+        eprintln!("Both videos must exist");
+        return 2;
+    };
+
+    let job = Job { a, b, offset };
+    let cancel = AtomicBool::new(false);
+    let frames = match analyze(&job, &cancel, |_, progress| {
+        eprint!("\rAnalyzing... {:3.0}%", progress * 100.0);
+    }) {
+        Ok(frames) => frames,
+        Err(e) => {
+            eprintln!("\nQuality analysis failed: {}", e);
+            return 1;
+        }
+    };
+    eprintln!();
+    if let Some(s) = summarize(&frames) {
+        eprintln!(
+            "{} frames: PSNR mean {:.2} dB (min {:.2}), SSIM mean {:.4} (min {:.4})",
+            s.frames, s.mean_psnr, s.min_psnr, s.mean_ssim, s.min_ssim
+        );
+    }
+
+    if csv_path.is_none() && json_path.is_none() {
+        print!("{}", to_csv(&frames));
+        return 0;
+    }
+    let mut written = Ok(());
+    if let Some(path) = &csv_path {
+        written = written.and_then(|_| std::fs::write(path, to_csv(&frames)));
+    }
+    if let Some(path) = &json_path {
+        let json = serde_json::to_string_pretty(&to_json(&job, &frames)).unwrap_or_default();
+        written = written.and_then(|_| std::fs::write(path, json));
+    }
+    match written {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Failed to write results: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16x16 luma plane with a diagonal gradient.
+    fn gradient() -> Vec<u8> {
+        (0..16 * 16)
+            .map(|i| ((i % 16 + i / 16) * 8) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn psnr_of_identical_planes_is_capped() {
+        let a = gradient();
+        assert_eq!(psnr(&a, &a), MAX_PSNR);
+    }
+
+    #[test]
+    fn psnr_follows_mean_squared_error() {
+        let (a, b) = (vec![0u8; 100], vec![10u8; 100]);
+        let expected = 10.0 * (255.0f64 * 255.0 / 100.0).log10();
+        assert!((psnr(&a, &b) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ssim_is_one_for_identical_planes_and_drops_with_distortion() {
+        let a = gradient();
+        assert!((ssim(&a, &a, 16, 16) - 1.0).abs() < 1e-9);
+        let inverted: Vec<u8> = a.iter().map(|&p| 255 - p).collect();
+        assert!(ssim(&a, &inverted, 16, 16) < 0.5);
+        let flat = vec![128u8; 16 * 16];
+        let score = ssim(&a, &flat, 16, 16);
+        assert!(score > 0.0 && score < 1.0);
+    }
+
+    #[test]
+    fn ssim_of_planes_smaller_than_a_window_compares_exactly() {
+        assert_eq!(ssim(&[1, 2, 3, 4], &[1, 2, 3, 4], 2, 2), 1.0);
+        assert_eq!(ssim(&[1, 2, 3, 4], &[1, 2, 3, 5], 2, 2), 0.0);
+    }
+
+    #[test]
+    fn summary_has_means_and_worst_cases() {
+        let frames = [
+            FrameMetrics {
+                frame: 0,
+                time: 0.0,
+                psnr: 40.0,
+                ssim: 0.98,
+            },
+            FrameMetrics {
+                frame: 1,
+                time: 0.04,
+                psnr: 30.0,
+                ssim: 0.90,
+            },
+        ];
+        let summary = summarize(&frames).unwrap();
+        assert_eq!(summary.frames, 2);
+        assert_eq!(summary.mean_psnr, 35.0);
+        assert_eq!(summary.min_psnr, 30.0);
+        assert!((summary.mean_ssim - 0.94).abs() < 1e-9);
+        assert_eq!(summary.min_ssim, 0.90);
+        assert!(summarize(&[]).is_none());
+        assert_eq!(
+            to_csv(&frames[1..]),
+            "frame,seconds,psnr,ssim\n1,0.040,30.0000,0.900000\n"
+        );
+    }
+}
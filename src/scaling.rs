@@ -0,0 +1,86 @@
+//! Decode-resolution targeting for grid cells.
+//!
+//! Videos are scaled down in the pipeline to roughly the size they occupy on screen,
+//! so small cells in large grids don't pay for full-resolution conversion and upload.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use iced::Size;
+
+use crate::fit::FitMode;
+
+/// Height of the bottom controls bar, excluded from the grid area.
+const CONTROLS_BAR_HEIGHT: f32 = 40.0;
+/// Spacing between grid cells (matches `render_main_view`).
+const GRID_SPACING: f32 = 5.0;
+/// Only renegotiate when the target differs from the current size by more than this
+/// fraction, so live window resizing doesn't trigger a caps change on every event.
+const RESIZE_HYSTERESIS: f32 = 0.2;
+/// Targets this close to the source size decode at full resolution instead.
+const FULL_RES_THRESHOLD: f32 = 0.9;
+
+/// Logical size of one grid cell for `count` items in `columns` columns.
+pub fn grid_cell_size(window: Size, count: usize, columns: usize) -> Size {
+    let area = Size::new(window.width, (window.height - CONTROLS_BAR_HEIGHT).max(1.0));
+    if count <= 1 {
+        return area;
+    }
+    let columns = columns.clamp(1, count);
+    let rows = count.div_ceil(columns);
+    Size::new(
+        (area.width - GRID_SPACING * (columns - 1) as f32) / columns as f32,
+        (area.height - GRID_SPACING * (rows - 1) as f32) / rows as f32,
+    )
+}
+
+/// Decode size that shows `source` in `cell` (in physical pixels) at the given fit,
+/// or `None` for full resolution. Cover and fill scale to the larger axis ratio, and
+/// 1:1 always needs every source pixel.
+pub fn target_decode_size(
+    source: (u32, u32),
+    cell: Size,
+    scale_factor: f32,
+    fit_mode: FitMode,
+) -> Option<(u32, u32)> {
+    let (src_w, src_h) = source;
+    if src_w == 0 || src_h == 0 {
+        return None;
+    }
+    let width_ratio = cell.width * scale_factor / src_w as f32;
+    let height_ratio = cell.height * scale_factor / src_h as f32;
+    let fit = match fit_mode {
+        FitMode::Contain => width_ratio.min(height_ratio),
+        FitMode::Cover | FitMode::Fill => width_ratio.max(height_ratio),
+        FitMode::Native => return None,
+    };
+    if !fit.is_finite() || fit >= FULL_RES_THRESHOLD {
+        return None;
+    }
+    // NV12 needs even dimensions
+    let even = |v: f32| ((v.round() as u32).max(2) + 1) & !1;
+    Some((even(src_w as f32 * fit), even(src_h as f32 * fit)))
+}
+
+/// Whether moving from `current` to `target` is a large enough change to renegotiate.
+pub fn needs_renegotiation(current: Option<(u32, u32)>, target: Option<(u32, u32)>) -> bool {
+    match (current, target) {
+        (None, None) => false,
+        (Some((cur_w, _)), Some((new_w, _))) => {
+            let ratio = new_w as f32 / cur_w.max(1) as f32;
+            (ratio - 1.0).abs() > RESIZE_HYSTERESIS
+        }
+        _ => true,
+    }
+}
+
+/// Apply a decode size to the `videoscale` capsfilter (`None` restores full resolution).
+pub fn apply_decode_size(scale_filter: &gst::Element, size: Option<(u32, u32)>) {
+    let caps = match size {
+        Some((w, h)) => gst::Caps::builder("video/x-raw")
+            .field("width", w as i32)
+            .field("height", h as i32)
+            .build(),
+        None => gst::Caps::builder("video/x-raw").build(),
+    };
+    scale_filter.set_property("caps", &caps);
+}
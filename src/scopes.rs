@@ -0,0 +1,291 @@
+//! Histogram, waveform and vectorscope of a cell, computed on the CPU.
+//!
+//! Frames come from the same sources as blends: the frame tap of a video (after its
+//! color adjustments and LUT, so the scope shows what the cell shows) or a photo's
+//! RGBA pixels. A subsampled grid of pixels is read on a worker thread, which renders
+//! each scope to a small image and announces it like any other worker result. Videos
+//! request at most one scope every `SCOPE_INTERVAL`.
+
+use iced::widget::image::Handle;
+use std::time::{Duration, Instant};
+
+use crate::blend::Frame;
+use crate::events::{EventSender, WorkerEvent};
+use crate::worker::LatestWorker;
+
+/// Shortest time between scope updates of a playing video.
+pub const SCOPE_INTERVAL: Duration = Duration::from_millis(200);
+/// Sampled pixels along each axis of a frame, bounding the work per scope.
+const SAMPLE_COLUMNS: usize = 384;
+const SAMPLE_ROWS: usize = 216;
+/// Size of the histogram and waveform images; the vectorscope is square.
+const SCOPE_WIDTH: usize = 256;
+const SCOPE_HEIGHT: usize = 128;
+const VECTORSCOPE_SIZE: usize = 256;
+
+/// Which scope a cell shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    /// Luma and RGB histograms, overlaid.
+    Histogram,
+    /// Luma of each column of the frame, bottom to top.
+    Waveform,
+    /// Chroma plotted by hue angle and saturation.
+    Vectorscope,
+}
+
+impl Scope {
+    /// Next scope in the toggle cycle, ending with none.
+    pub fn cycle(current: Option<Scope>) -> Option<Scope> {
+        match current {
+            None => Some(Scope::Histogram),
+            Some(Scope::Histogram) => Some(Scope::Waveform),
+            Some(Scope::Waveform) => Some(Scope::Vectorscope),
+            Some(Scope::Vectorscope) => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Histogram => "histogram",
+            Scope::Waveform => "waveform",
+            Scope::Vectorscope => "vectorscope",
+        }
+    }
+
+    /// Size of the rendered image.
+    pub fn image_size(self) -> (usize, usize) {
+        match self {
+            Scope::Histogram | Scope::Waveform => (SCOPE_WIDTH, SCOPE_HEIGHT),
+            Scope::Vectorscope => (VECTORSCOPE_SIZE, VECTORSCOPE_SIZE),
+        }
+    }
+}
+
+/// Scope state of one cell.
+pub struct ScopeView {
+    pub scope: Scope,
+    pub image: Option<Handle>, // Latest rendering, kept until the next one arrives
+    pub pending: bool,         // A job is on the worker
+    pub stale: bool,           // The frame changed while the job ran
+    pub requested: Instant,    // When the last job was queued
+}
+
+impl ScopeView {
+    pub fn new(scope: Scope) -> Self {
+        ScopeView {
+            scope,
+            image: None,
+            pending: false,
+            stale: false,
+            requested: Instant::now()
+                .checked_sub(SCOPE_INTERVAL)
+                .unwrap_or_else(Instant::now),
+        }
+    }
+}
+
+/// Luma (BT.709) of an RGB pixel, 0..255.
+fn luma([r, g, b]: [f32; 3]) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Render `scope` of a frame, or None if its pixels can't be read.
+pub fn render(frame: &Frame, scope: Scope) -> Option<Handle> {
+    let (width, height) = frame.size();
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let (columns, rows) = (
+        SAMPLE_COLUMNS.min(width as usize),
+        SAMPLE_ROWS.min(height as usize),
+    );
+    let samples = frame.with_pixels(|pixels| {
+        let mut samples = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            let y = (row * height as usize + height as usize / 2) / rows;
+            for column in 0..columns {
+                let x = (column * width as usize + width as usize / 2) / columns;
+                let rgb = pixels.rgb(x.min(width as usize - 1), y.min(height as usize - 1));
+                samples.push((column, rgb.map(|c| c.clamp(0.0, 255.0))));
+            }
+        }
+        samples
+    })?;
+
+    let (out_w, out_h) = scope.image_size();
+    let pixels = match scope {
+        Scope::Histogram => histogram(&samples, out_w, out_h),
+        Scope::Waveform => waveform(&samples, columns, out_w, out_h),
+        Scope::Vectorscope => vectorscope(&samples, out_w),
+    };
+    Some(Handle::from_rgba(out_w as u32, out_h as u32, pixels))
+}
+
+/// Transparent RGBA canvas.
+fn canvas(width: usize, height: usize) -> Vec<u8> {
+    vec![0; width * height * 4]
+}
+
+/// Add `color` at `weight` (0..1) to a canvas pixel, saturating.
+fn add(pixels: &mut [u8], width: usize, x: usize, y: usize, color: [u8; 3], weight: f32) {
+    let i = (y * width + x) * 4;
+    for (c, &channel) in color.iter().enumerate() {
+        let value = pixels[i + c] as f32 + channel as f32 * weight;
+        pixels[i + c] = value.min(255.0) as u8;
+    }
+    pixels[i + 3] = pixels[i + 3].max((255.0 * weight.min(1.0)) as u8);
+}
+
+/// Brightness of a plotted point from its hit count, relative to `reference` hits.
+/// The square root keeps sparse traces visible next to dense ones.
+fn intensity(count: u32, reference: f32) -> f32 {
+    (count as f32 / reference.max(1.0)).sqrt().min(1.0)
+}
+
+fn histogram(samples: &[(usize, [f32; 3])], width: usize, height: usize) -> Vec<u8> {
+    // Bins for R, G, B and luma, one per output column
+    let mut bins = [[0u32; SCOPE_WIDTH]; 4];
+    let bin = |v: f32| ((v / 256.0 * width as f32) as usize).min(width - 1);
+    for &(_, rgb) in samples {
+        for (channel, value) in bins.iter_mut().zip(rgb) {
+            channel[bin(value)] += 1;
+        }
+        bins[3][bin(luma(rgb))] += 1;
+    }
+    // Clipped extremes would flatten everything else
+    let peak = bins
+        .iter()
+        .flat_map(|b| b[1..width - 1].iter())
+        .copied()
+        .max()
+        .unwrap_or(1)
+        .max(1) as f32;
+
+    let colors = [[255, 60, 60], [60, 255, 60], [60, 60, 255], [110, 110, 110]];
+    let mut pixels = canvas(width, height);
+    for x in 0..width {
+        for (channel, color) in colors.iter().enumerate() {
+            let bar = ((bins[channel][x] as f32 / peak).min(1.0) * height as f32) as usize;
+            for y in height - bar..height {
+                add(&mut pixels, width, x, y, *color, 0.6);
+            }
+        }
+    }
+    pixels
+}
+
+fn waveform(samples: &[(usize, [f32; 3])], columns: usize, width: usize, height: usize) -> Vec<u8> {
+    let mut counts = vec![0u32; width * height];
+    for &(column, rgb) in samples {
+        let x = (column * width / columns).min(width - 1);
+        let y = height - 1 - ((luma(rgb) / 256.0 * height as f32) as usize).min(height - 1);
+        counts[y * width + x] += 1;
+    }
+    // A column's samples spread over a handful of levels in a typical frame
+    let reference = samples.len() as f32 / width as f32 / 8.0;
+
+    let mut pixels = canvas(width, height);
+    // Graticule at 0, 25, 50, 75 and 100%
+    for level in 0..=4 {
+        let y = ((height - 1) * level / 4).min(height - 1);
+        for x in 0..width {
+            add(&mut pixels, width, x, y, [80, 80, 80], 0.5);
+        }
+    }
+    for (i, &count) in counts.iter().enumerate() {
+        if count > 0 {
+            add(
+                &mut pixels,
+                width,
+                i % width,
+                i / width,
+                [120, 255, 120],
+                intensity(count, reference),
+            );
+        }
+    }
+    pixels
+}
+
+fn vectorscope(samples: &[(usize, [f32; 3])], size: usize) -> Vec<u8> {
+    let center = size as f32 / 2.0;
+    let mut counts = vec![0u32; size * size];
+    for &(_, [r, g, b]) in samples {
+        // BT.709 color difference, about -128..128
+        let cb = -0.1146 * r - 0.3854 * g + 0.5 * b;
+        let cr = 0.5 * r - 0.4542 * g - 0.0458 * b;
+        let x = (center + cb).clamp(0.0, size as f32 - 1.0) as usize;
+        let y = (center - cr).clamp(0.0, size as f32 - 1.0) as usize;
+        counts[y * size + x] += 1;
+    }
+    let reference = samples.len() as f32 / 200.0;
+
+    let mut pixels = canvas(size, size);
+    // Graticule: outer circle, 75% circle and center cross
+    let steps = 720;
+    for radius in [center - 1.0, (center - 1.0) * 0.75] {
+        for step in 0..steps {
+            let angle = step as f32 / steps as f32 * std::f32::consts::TAU;
+            let x = (center + radius * angle.cos()) as usize;
+            let y = (center + radius * angle.sin()) as usize;
+            add(
+                &mut pixels,
+                size,
+                x.min(size - 1),
+                y.min(size - 1),
+                [80, 80, 80],
+                0.5,
+            );
+        }
+    }
+    for i in 0..size {
+        add(&mut pixels, size, i, size / 2, [60, 60, 60], 0.4);
+        add(&mut pixels, size, size / 2, i, [60, 60, 60], 0.4);
+    }
+    for (i, &count) in counts.iter().enumerate() {
+        if count > 0 {
+            add(
+                &mut pixels,
+                size,
+                i % size,
+                i / size,
+                [255, 255, 255],
+                intensity(count, reference),
+            );
+        }
+    }
+    pixels
+}
+
+/// A frame to render a scope of, for one cell.
+pub struct Job {
+    pub id: usize,
+    pub frame: Frame,
+    pub scope: Scope,
+}
+
+/// Background worker rendering scopes, skipping ahead to each cell's newest request.
+pub struct Worker {
+    worker: LatestWorker<usize, Job>,
+}
+
+impl Worker {
+    /// Spawn the worker. Each result is announced with [`WorkerEvent::ScopeReady`];
+    /// unreadable frames are announced without an image.
+    pub fn spawn(events: EventSender) -> Self {
+        let worker = LatestWorker::spawn("scopes", move |id: usize, job: Job| {
+            let image = render(&job.frame, job.scope);
+            if image.is_none() {
+                log::debug!("Scope skipped: id={}, frame could not be read", id);
+            }
+            let _ = events.unbounded_send(WorkerEvent::ScopeReady(id, image));
+        });
+        Worker { worker }
+    }
+
+    /// Queue a scope rendering.
+    pub fn request(&self, job: Job) {
+        self.worker.request(job.id, job);
+    }
+}
@@ -0,0 +1,236 @@
+//! Persistent user settings.
+//!
+//! Settings are stored as JSON in `$XDG_CONFIG_HOME/cineplex/settings.json`
+//! (falling back to `~/.config`). Missing or malformed fields keep their defaults.
+
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+use crate::caption::{CaptionPosition, CaptionSize};
+use crate::decoders::{DecoderFamily, DecoderPreferences};
+use crate::exposure::ExposureAids;
+use crate::fit::FitMode;
+
+/// Framerate delivered to the video sink.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FramerateMode {
+    /// Keep the stream's own rate (VFR content reporting 0 fps falls back to a fixed rate).
+    Native,
+    /// Resample every video to a fixed rate.
+    Fixed(u32),
+}
+
+/// What happens to videos hidden while another item is fullscreen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HiddenPlayback {
+    /// Keep decoding at full speed.
+    Play,
+    /// Pause, and resume at the position they would have reached.
+    Pause,
+    /// Keep playing but deliver only a few frames per second.
+    Throttle,
+}
+
+impl HiddenPlayback {
+    pub fn next(self) -> Self {
+        match self {
+            HiddenPlayback::Pause => HiddenPlayback::Throttle,
+            HiddenPlayback::Throttle => HiddenPlayback::Play,
+            HiddenPlayback::Play => HiddenPlayback::Pause,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HiddenPlayback::Play => "play",
+            HiddenPlayback::Pause => "pause",
+            HiddenPlayback::Throttle => "throttle",
+        }
+    }
+}
+
+/// User settings that persist across runs.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub framerate_mode: FramerateMode,
+    pub hidden_playback: HiddenPlayback,
+    pub decoders: DecoderPreferences,
+    /// Grid items per page; only the visible page's videos keep a pipeline.
+    pub page_size: usize,
+    /// Fit of cells without their own fit mode.
+    pub fit_mode: FitMode,
+    /// LUT of cells without their own LUT.
+    pub lut: Option<PathBuf>,
+    /// Zebras cover levels at or above this IRE.
+    pub zebra_ire: u8,
+    /// Corner and text size of cell captions.
+    pub caption_position: CaptionPosition,
+    pub caption_size: CaptionSize,
+    /// Show positions as source timecode instead of minutes and seconds.
+    pub timecode: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            framerate_mode: FramerateMode::Native,
+            hidden_playback: HiddenPlayback::Pause,
+            decoders: DecoderPreferences::default(),
+            page_size: 16,
+            fit_mode: FitMode::Contain,
+            lut: None,
+            zebra_ire: ExposureAids::default().zebra_ire,
+            caption_position: CaptionPosition::BottomLeft,
+            caption_size: CaptionSize::Small,
+            timecode: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from disk, falling back to defaults.
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Settings::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<Value>(&contents) {
+                Ok(value) => Settings::from_json(&value),
+                Err(e) => {
+                    log::warn!("Ignoring malformed settings {}: {}", path.display(), e);
+                    Settings::default()
+                }
+            },
+            Err(_) => Settings::default(),
+        }
+    }
+
+    /// Write settings to disk. Failures are logged, not fatal.
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let contents = serde_json::to_string_pretty(&self.to_json()).unwrap_or_default();
+        if let Err(e) = std::fs::write(&path, contents) {
+            log::warn!("Failed to save settings to {}: {}", path.display(), e);
+        }
+    }
+
+    fn from_json(value: &Value) -> Self {
+        let mut settings = Settings::default();
+        match value.get("framerate") {
+            Some(Value::String(s)) if s == "native" => {
+                settings.framerate_mode = FramerateMode::Native;
+            }
+            Some(Value::Number(n)) => {
+                if let Some(fps) = n.as_u64().filter(|&f| f > 0 && f <= 240) {
+                    settings.framerate_mode = FramerateMode::Fixed(fps as u32);
+                }
+            }
+            _ => {}
+        }
+        if let Some(policy) = value.get("hidden_playback").and_then(Value::as_str) {
+            settings.hidden_playback = match policy {
+                "play" => HiddenPlayback::Play,
+                "throttle" => HiddenPlayback::Throttle,
+                _ => HiddenPlayback::Pause,
+            };
+        }
+        if let Some(decoders) = value.get("decoders") {
+            let families = |key: &str| -> Vec<DecoderFamily> {
+                decoders
+                    .get(key)
+                    .and_then(Value::as_array)
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .filter_map(DecoderFamily::parse)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            settings.decoders = DecoderPreferences {
+                prefer: families("prefer"),
+                forbid: families("forbid"),
+            };
+        }
+        if let Some(size) = value
+            .get("page_size")
+            .and_then(Value::as_u64)
+            .filter(|&s| (1..=100).contains(&s))
+        {
+            settings.page_size = size as usize;
+        }
+        if let Some(fit) = value
+            .get("fit")
+            .and_then(Value::as_str)
+            .and_then(FitMode::parse)
+        {
+            settings.fit_mode = fit;
+        }
+        settings.lut = value.get("lut").and_then(Value::as_str).map(PathBuf::from);
+        if let Some(ire) = value
+            .get("zebra_ire")
+            .and_then(Value::as_u64)
+            .filter(|&ire| ire <= 109)
+        {
+            settings.zebra_ire = ire as u8;
+        }
+        if let Some(position) = value
+            .get("caption_position")
+            .and_then(Value::as_str)
+            .and_then(CaptionPosition::parse)
+        {
+            settings.caption_position = position;
+        }
+        if let Some(size) = value
+            .get("caption_size")
+            .and_then(Value::as_str)
+            .and_then(CaptionSize::parse)
+        {
+            settings.caption_size = size;
+        }
+        if let Some(timecode) = value.get("timecode").and_then(Value::as_bool) {
+            settings.timecode = timecode;
+        }
+        settings
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "framerate": match self.framerate_mode {
+                FramerateMode::Native => json!("native"),
+                FramerateMode::Fixed(fps) => json!(fps),
+            },
+            "hidden_playback": self.hidden_playback.as_str(),
+            "decoders": {
+                "prefer": self.decoders.prefer.iter().map(|f| f.as_str()).collect::<Vec<_>>(),
+                "forbid": self.decoders.forbid.iter().map(|f| f.as_str()).collect::<Vec<_>>(),
+            },
+            "page_size": self.page_size,
+            "fit": self.fit_mode.as_str(),
+            "lut": self.lut.as_ref().map(|p| p.to_string_lossy()),
+            "zebra_ire": self.zebra_ire,
+            "caption_position": self.caption_position.as_str(),
+            "caption_size": self.caption_size.as_str(),
+            "timecode": self.timecode,
+        })
+    }
+}
+
+/// Directory for cineplex configuration files.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("cineplex"))
+}
+
+fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("settings.json"))
+}
@@ -1,20 +1,180 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
 use iced::widget::image::Handle;
 use iced_video_player::Video;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
 use std::sync::mpsc;
 use std::time::Instant;
 use tempfile::TempDir;
 
+use crate::animation::{Animation, DecodedFrames};
+use crate::blend::{BlendMode, FrameTap};
+use crate::chapters::Chapter;
+use crate::color::ColorAdjust;
+use crate::exposure::{ExposureAids, ExposureStage};
+use crate::fit::FitMode;
+use crate::letterbox::Letterbox;
+use crate::lut::{Lut, LutStage};
+use crate::markers::Marker;
+use crate::scopes::ScopeView;
+use crate::subtitles::{self, Cue, Sidecar, SubtitleSource};
+use crate::timecode::Timebase;
+use crate::tracks::{self, TrackGroup, TrackKind};
+use crate::transform::{CropRect, Transform};
+use crate::zoom::ZoomView;
+
 /// Result of a background media loading operation.
 pub enum LoadResult {
     Video(VideoInstance),
     Photo(PhotoInstance),
-    Error(String),
+    /// A photo decoded again after eviction, a window resize, or for fullscreen.
+    PhotoImage {
+        id: usize,
+        image: Result<DecodedPhoto, String>,
+        full_res: bool,
+    },
+    Error(usize, String),
+}
+
+/// RGBA pixels of a photo, possibly downscaled from the original.
+pub struct DecodedPhoto {
+    pub handle: Handle,
+    pub size: (u32, u32),              // Size of `handle`
+    pub full_size: (u32, u32),         // Original size (after orientation and crop)
+    pub frames: Option<DecodedFrames>, // Every frame of an animated image, `handle` first
+}
+
+/// Elements of a video's GStreamer pipeline that are adjusted at runtime.
+pub struct PipelineHandles {
+    pub pipeline: gst::Pipeline,
+    /// Capsfilter after `videoscale`, used to decode at cell resolution.
+    pub scale_filter: Option<gst::Element>,
+    /// `videocrop` removing detected letterbox bars.
+    pub letterbox: Option<gst::Element>,
+    /// `videoflip` and `videocrop` applying the manual transform.
+    pub flip: Option<gst::Element>,
+    pub crop: Option<gst::Element>,
+    /// `videobalance` and `gamma` applying the color adjustments.
+    pub balance: Option<gst::Element>,
+    pub gamma: Option<gst::Element>,
+    /// LUT applied to frames by a pad probe on the appsink.
+    pub lut: Arc<LutStage>,
+    /// Zebras and false color drawn on frames by a later probe.
+    pub exposure: Arc<ExposureStage>,
+    /// Capsfilter after `videorate`, lowered to throttle hidden videos.
+    pub rate_filter: Option<gst::Element>,
+    /// Caps of `rate_filter` as loaded, restored when throttling ends.
+    pub rate_caps: Option<gst::Caps>,
+    /// Frames reaching the appsink, counted by a pad probe.
+    pub delivered_frames: Arc<AtomicU64>,
+    /// Latest decoded frame, held while a blend view compares this video.
+    pub frame_tap: Arc<FrameTap>,
+}
+
+/// Framerate delivered by hidden videos under the throttle policy.
+const THROTTLED_FPS: i32 = 2;
+
+impl PipelineHandles {
+    /// Crop letterbox bars (None shows the whole frame). Returns the size left of an
+    /// `upright_size` frame.
+    pub fn apply_letterbox(&self, crop: Option<CropRect>, upright_size: (u32, u32)) -> (u32, u32) {
+        let edges = crop.map(|c| c.edges(upright_size)).unwrap_or_default();
+        if let Some(element) = &self.letterbox {
+            set_crop_edges(element, edges);
+        }
+        let (left, top, right, bottom) = edges;
+        (upright_size.0 - left - right, upright_size.1 - top - bottom)
+    }
+
+    /// Apply a manual rotation, flip and crop. `size` is the frame size before them.
+    pub fn apply_transform(&self, transform: &Transform, size: (u32, u32)) {
+        if let Some(flip) = &self.flip {
+            flip.set_property_from_str("method", transform.videoflip_method());
+        }
+        if let Some(crop) = &self.crop {
+            let edges = transform
+                .crop
+                .map(|c| c.edges(transform.oriented_size(size)))
+                .unwrap_or_default();
+            set_crop_edges(crop, edges);
+        }
+    }
+
+    /// Apply color adjustments and a LUT (None for no LUT).
+    pub fn apply_color(&self, adjust: &ColorAdjust, lut: Option<Arc<Lut>>) {
+        if let Some(balance) = &self.balance {
+            balance.set_property("brightness", adjust.brightness as f64);
+            balance.set_property("contrast", adjust.contrast as f64);
+            balance.set_property("saturation", adjust.saturation as f64);
+        }
+        if let Some(gamma) = &self.gamma {
+            gamma.set_property("gamma", adjust.gamma as f64);
+        }
+        self.lut.set(lut);
+    }
+
+    /// Number of tracks of a kind.
+    pub fn track_count(&self, kind: TrackKind) -> i32 {
+        tracks::count(&self.pipeline, kind)
+    }
+
+    /// Track of a kind that is playing, -1 for none.
+    pub fn current_track(&self, kind: TrackKind) -> i32 {
+        tracks::current(&self.pipeline, kind)
+    }
+
+    /// Play another track of a kind (subtitle tracks go to the text sink).
+    pub fn select_track(&self, kind: TrackKind, index: i32) {
+        tracks::select(&self.pipeline, kind, index);
+    }
+
+    /// Every kind's tracks with their labels, for the track picker.
+    pub fn read_tracks(&self) -> Vec<TrackGroup> {
+        tracks::read_all(&self.pipeline)
+    }
+
+    /// Drop delivery to a few frames per second, or restore the loaded framerate.
+    /// Decoding continues; this saves conversion and upload for cells nobody sees.
+    pub fn set_throttled(&self, throttled: bool) {
+        let Some(filter) = &self.rate_filter else {
+            return;
+        };
+        let caps = if throttled {
+            gst::Caps::builder("video/x-raw")
+                .field("framerate", gst::Fraction::new(THROTTLED_FPS, 1))
+                .build()
+        } else {
+            self.rate_caps
+                .clone()
+                .unwrap_or_else(|| gst::Caps::builder("video/x-raw").build())
+        };
+        filter.set_property("caps", &caps);
+    }
+}
+
+/// Set the left, top, right and bottom pixels a `videocrop` removes.
+fn set_crop_edges(crop: &gst::Element, (left, top, right, bottom): (u32, u32, u32, u32)) {
+    crop.set_property("left", left as i32);
+    crop.set_property("top", top as i32);
+    crop.set_property("right", right as i32);
+    crop.set_property("bottom", bottom as i32);
+}
+
+/// How a video was suspended while hidden behind another fullscreen item.
+pub struct HiddenState {
+    pub resume_playing: bool, // Playing when hidden (paused videos stay paused)
+    pub position: f64,        // Position when hidden
+    pub since: Instant,
 }
 
 /// Represents a single video instance in the player.
 pub struct VideoInstance {
     pub id: usize,
     pub video: Video,
+    pub path: PathBuf,
+    pub filename: String,
     pub position: f64,
     pub duration: f64, // Cached duration to avoid blocking GStreamer queries during rendering
     pub dragging: bool,
@@ -22,15 +182,152 @@ pub struct VideoInstance {
     pub hovered: bool,
     pub fullscreen: bool,
     pub _temp_dir: Option<TempDir>,
-    pub native_fps: f64, // Native framerate of the video
+    pub native_fps: f64,            // Native framerate of the video
+    pub timebase: Timebase,         // Frame numbering of the source stream
+    pub start_timecode: u64,        // Timecode of the first frame, in frames
+    pub measured_fps: f64,          // Delivered framerate over the last sample window
+    pub fps_sample: (Instant, u64), // Start of the current sample window and frame count
+    pub pipeline: PipelineHandles,
+    pub source_size: (u32, u32), // Unscaled output size (after orientation and crop)
+    pub upright_size: (u32, u32), // Size after automatic orientation, before any crop
+    pub transform: Transform,
+    pub decode_size: Option<(u32, u32)>, // Current scaled decode size, None for full resolution
+    pub hidden: Option<HiddenState>,     // Set while hidden behind a fullscreen item
+    pub decoder: Option<String>,         // Autoplugged video decoder factory name
+    pub software_decode: bool,           // Hardware decoders skipped after one failed
+    pub show_info: bool,
+    pub view: ZoomView,
+    pub fit: Option<FitMode>, // None follows the global default
+    pub letterbox: Letterbox,
+    pub keep_bars: bool, // Detected bars are shown instead of cropped
+    pub color: ColorAdjust,
+    pub lut: Option<PathBuf>, // Own LUT, None follows the all-cells LUT
+    pub show_color: bool,     // Color panel open
+    pub scope: Option<ScopeView>,
+    pub aids: ExposureAids,
+    pub show_caption: bool,
+    pub subtitles: SubtitleSource,
+    pub sidecars: Vec<Sidecar>,
+    pub embedded_cues: Vec<Cue>, // Cues of the selected embedded track seen so far
+    pub subtitle_delay: f64,     // Seconds subtitles are shown late (early if negative)
+    pub sync_offset: f64,        // Seconds ahead of the pair's timeline when compared
+    pub tracks: Option<Vec<TrackGroup>>, // Track picker contents, while it is open
+    pub markers: Vec<Marker>,    // Sorted by position
+    pub chapters: Vec<Chapter>,  // From the container, sorted by start
     // UI fade tracking
     pub last_mouse_activity: Instant,
 }
 
+impl VideoInstance {
+    /// Bars currently cropped from the frame.
+    pub fn letterbox_crop(&self) -> Option<CropRect> {
+        match self.letterbox {
+            Letterbox::Bars(crop) if !self.keep_bars => Some(crop),
+            _ => None,
+        }
+    }
+
+    /// Push the letterbox crop and manual transform to the pipeline after either changed.
+    /// Decoding returns to full resolution, since the current decode size has the old
+    /// aspect ratio; the caller picks a new one.
+    pub fn apply_geometry(&mut self) {
+        if let Some(filter) = &self.pipeline.scale_filter {
+            crate::scaling::apply_decode_size(filter, None);
+        }
+        self.decode_size = None;
+        let letterboxed = self
+            .pipeline
+            .apply_letterbox(self.letterbox_crop(), self.upright_size);
+        self.pipeline.apply_transform(&self.transform, letterboxed);
+        self.source_size = self.transform.output_size(letterboxed);
+    }
+
+    /// Subtitle text to show at the current position.
+    pub fn subtitle_text(&self) -> Option<String> {
+        let cues = match self.subtitles {
+            SubtitleSource::Off => return None,
+            SubtitleSource::Sidecar(i) => &self.sidecars.get(i)?.cues,
+            SubtitleSource::Embedded(_) => &self.embedded_cues,
+        };
+        subtitles::text_at(cues, self.position - self.subtitle_delay)
+    }
+
+    /// Name of the subtitle source, for display.
+    pub fn subtitle_label(&self) -> String {
+        match self.subtitles {
+            SubtitleSource::Off => "off".to_string(),
+            SubtitleSource::Sidecar(i) => self
+                .sidecars
+                .get(i)
+                .map_or_else(|| "off".to_string(), |s| s.name.clone()),
+            SubtitleSource::Embedded(track) => format!("track {}", track + 1),
+        }
+    }
+
+    /// Source timecode of the frame shown at `secs`.
+    pub fn timecode_at(&self, secs: f64) -> String {
+        self.timebase
+            .format(self.start_timecode + self.timebase.frames(secs))
+    }
+
+    /// Position of a typed timecode in this video, or why it has none.
+    pub fn position_of_timecode(&self, label: &str) -> Result<f64, String> {
+        position_of_timecode(
+            &self.timebase,
+            self.start_timecode,
+            self.duration,
+            &self.filename,
+            label,
+        )
+    }
+}
+
+/// Position of a typed timecode in a video of `duration` seconds whose first frame is
+/// labelled `start_timecode`, or why it has none.
+pub fn position_of_timecode(
+    timebase: &Timebase,
+    start_timecode: u64,
+    duration: f64,
+    filename: &str,
+    label: &str,
+) -> Result<f64, String> {
+    let frames = timebase
+        .parse(label)
+        .ok_or_else(|| format!("{:?} is not HH:MM:SS:FF", label.trim()))?;
+    frames
+        .checked_sub(start_timecode)
+        .map(|f| timebase.seconds(f))
+        .filter(|&s| s <= duration)
+        .ok_or_else(|| {
+            format!(
+                "{} is outside {} ({} to {})",
+                timebase.format(frames),
+                filename,
+                timebase.format(start_timecode),
+                timebase.format(start_timecode + timebase.frames(duration))
+            )
+        })
+}
+
 /// Represents a single photo instance in the player.
 pub struct PhotoInstance {
     pub id: usize,
-    pub handle: Handle,
+    pub path: PathBuf,
+    pub handle: Option<Handle>, // Display-size pixels, None while evicted
+    pub size: (u32, u32),       // Size of `handle`
+    pub full_size: (u32, u32),  // Original size (after orientation and crop)
+    pub full_handle: Option<Handle>, // Full-resolution pixels, only while fullscreen
+    pub reload_pending: bool,
+    pub reload_error: Option<String>, // Last reload failed; no more reloads until it is cleared
+    pub last_visible: Instant,
+    pub view: ZoomView,
+    pub fit: Option<FitMode>, // None follows the global default
+    pub transform: Transform,
+    pub scope: Option<ScopeView>,
+    pub aids: ExposureAids,
+    pub aid_handle: Option<Handle>, // Pixels with the aids drawn, while any is on
+    pub animation: Option<Animation>, // Frames and playback of an animated image
+    pub show_caption: bool,
     pub hovered: bool,
     pub fullscreen: bool,
     pub filename: String,
@@ -38,10 +335,104 @@ pub struct PhotoInstance {
     pub last_mouse_activity: Instant,
 }
 
+impl PhotoInstance {
+    /// Decoded pixels the aids are drawn from: full resolution when held.
+    pub fn source_handle(&self) -> Option<&Handle> {
+        self.full_handle.as_ref().or(self.handle.as_ref())
+    }
+
+    /// Pixels to draw: with the exposure aids while any is on and ready.
+    pub fn display_handle(&self) -> Option<&Handle> {
+        self.aid_handle
+            .as_ref()
+            .filter(|_| self.aids.is_active())
+            .or(self.source_handle())
+    }
+
+    /// RGBA bytes currently held by this photo's handles.
+    pub fn resident_bytes(&self) -> u64 {
+        let display = if self.handle.is_some() {
+            crate::photos::rgba_bytes(self.size)
+        } else {
+            0
+        };
+        let full = if self.full_handle.is_some() {
+            crate::photos::rgba_bytes(self.full_size)
+        } else {
+            0
+        };
+        // `handle` is one of the animation's frames
+        let frames = self
+            .animation
+            .as_ref()
+            .map_or(0, |a| a.frames.len().saturating_sub(1) as u64);
+        display + full + frames * crate::photos::rgba_bytes(self.size)
+    }
+
+    /// Show the animation's frame at its current position.
+    pub fn show_current_frame(&mut self) {
+        if let Some(frame) = self.animation.as_ref().and_then(|a| a.current_frame()) {
+            self.handle = Some(frame.clone());
+        }
+    }
+}
+
+/// Playback state of a video paged out of the grid, restored when it is loaded again.
+pub struct ResumeState {
+    pub position: f64,
+    pub duration: f64,
+    pub timebase: Timebase,
+    pub start_timecode: u64,
+    pub looping: bool,
+    pub show_info: bool,
+    pub show_caption: bool,
+    pub subtitles: SubtitleSource,
+    pub subtitle_delay: f64,
+    pub sync_offset: f64,
+    pub audio_track: i32,
+    pub video_track: i32,
+    pub view: ZoomView,
+    pub markers: Vec<Marker>,
+    pub letterbox: Letterbox,
+}
+
+/// A media item without a loaded instance: not yet loaded, or a video on another page.
+pub struct PendingMedia {
+    pub id: usize,
+    pub path: PathBuf,
+    pub filename: String,
+    pub loading: bool,         // A background load is in flight
+    pub software_decode: bool, // Load with hardware video decoders skipped
+    pub error: Option<String>, // Last reload failed; retried once it is paged in again
+    pub resume: Option<ResumeState>,
+    pub thumbnail: Option<Handle>, // Cached poster frame or photo thumbnail
+}
+
+impl PendingMedia {
+    pub fn new(id: usize, path: PathBuf) -> Self {
+        let filename = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        PendingMedia {
+            id,
+            path,
+            filename,
+            loading: false,
+            software_decode: false,
+            error: None,
+            resume: None,
+            thumbnail: None,
+        }
+    }
+}
+
 /// Unified media item that can be either a video or a photo.
 pub enum MediaItem {
     Video(VideoInstance),
     Photo(PhotoInstance),
+    Pending(PendingMedia),
 }
 
 impl MediaItem {
@@ -49,6 +440,15 @@ impl MediaItem {
         match self {
             MediaItem::Video(v) => v.id,
             MediaItem::Photo(p) => p.id,
+            MediaItem::Pending(p) => p.id,
+        }
+    }
+
+    pub fn filename(&self) -> &str {
+        match self {
+            MediaItem::Video(v) => &v.filename,
+            MediaItem::Photo(p) => &p.filename,
+            MediaItem::Pending(p) => &p.filename,
         }
     }
 
@@ -56,8 +456,131 @@ impl MediaItem {
         match self {
             MediaItem::Video(v) => v.fullscreen,
             MediaItem::Photo(p) => p.fullscreen,
+            MediaItem::Pending(_) => false,
+        }
+    }
+
+    /// Zoom and pan of a loaded video or photo.
+    pub fn view_mut(&mut self) -> Option<&mut ZoomView> {
+        match self {
+            MediaItem::Video(v) => Some(&mut v.view),
+            MediaItem::Photo(p) => Some(&mut p.view),
+            MediaItem::Pending(_) => None,
         }
     }
+
+    /// Pixel size the item's zoom layout is computed against.
+    pub fn source_size(&self) -> Option<(u32, u32)> {
+        match self {
+            MediaItem::Video(v) => Some(v.source_size),
+            MediaItem::Photo(p) => Some(p.size),
+            MediaItem::Pending(_) => None,
+        }
+    }
+
+    pub fn is_video(&self) -> bool {
+        match self {
+            MediaItem::Video(_) => true,
+            MediaItem::Photo(_) => false,
+            MediaItem::Pending(p) => crate::loader::is_video_file(&p.path),
+        }
+    }
+}
+
+/// A marker note currently being typed in a video cell.
+pub struct MarkerEdit {
+    pub video_id: usize,
+    pub index: usize,
+    pub draft: String,
+}
+
+/// A timecode being typed to jump to.
+pub struct TimecodeEntry {
+    pub video_id: Option<usize>, // None jumps every video
+    pub draft: String,
+}
+
+/// Direction of the wipe line in compare mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WipeAxis {
+    Vertical,   // A on the left, B on the right
+    Horizontal, // A on top, B below
+}
+
+/// One side of a comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareSide {
+    A,
+    B,
+}
+
+/// What the compare view draws.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareMode {
+    Wipe,
+    Difference, // Amplified absolute difference of A and B
+    Onion,      // B laid over A at adjustable opacity
+}
+
+impl CompareMode {
+    pub fn next(self) -> Self {
+        match self {
+            CompareMode::Wipe => CompareMode::Difference,
+            CompareMode::Difference => CompareMode::Onion,
+            CompareMode::Onion => CompareMode::Wipe,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompareMode::Wipe => "wipe",
+            CompareMode::Difference => "difference",
+            CompareMode::Onion => "onion",
+        }
+    }
+}
+
+/// Two items superimposed with a wipe line between them, or blended.
+pub struct Compare {
+    pub a: usize,
+    pub b: usize,
+    pub wipe: f32, // Normalized position of the wipe line
+    pub axis: WipeAxis,
+    pub flicker: Option<CompareSide>, // Showing one side whole instead of the wipe
+    pub dragging: bool,               // Wipe line follows the cursor
+    pub mode: CompareMode,
+    pub gain: f32,               // Difference amplification
+    pub opacity: f32,            // Onion-skin opacity of B
+    pub blended: Option<Handle>, // Last difference or onion-skin image
+    pub blend_pending: bool,     // A blend is running on the worker
+    pub blend_generation: u64,   // Generation of the last blend requested for this pair
+    pub blend_stale: bool,       // Settings or frames changed while it ran
+}
+
+impl Compare {
+    /// Blend computed for the current mode, None while wiping.
+    pub fn blend_mode(&self) -> Option<BlendMode> {
+        match self.mode {
+            CompareMode::Wipe => None,
+            CompareMode::Difference => Some(BlendMode::Difference { gain: self.gain }),
+            CompareMode::Onion => Some(BlendMode::Onion {
+                opacity: self.opacity,
+            }),
+        }
+    }
+}
+
+/// A crop rectangle being drawn over a cell, in cell coordinates.
+pub struct CropDraw {
+    pub id: usize,
+    pub start: Option<iced::Point>, // Set once the drag starts
+    pub current: iced::Point,
+}
+
+/// A drag panning a zoomed cell.
+pub struct PanDrag {
+    pub id: usize,
+    pub last: iced::Point, // Previous cursor position within the cell
 }
 
 /// Application state containing all media and UI state.
@@ -65,37 +588,86 @@ pub struct App {
     pub media: Vec<MediaItem>,
     pub next_id: usize,
     pub grid_columns: usize,
+    pub page: usize, // Index of the visible grid page
     pub error: Option<String>,
     pub status: String,
     pub watchdog: crate::watchdog::Watchdog,
-    pub stall_check_counter: u32,
     // Drag-to-reorder state
     pub drag_source_id: Option<usize>,
     pub drag_target: Option<(usize, bool)>, // (target cell id, insert_before)
+    // Zoom and pan
+    pub cursor: Option<(usize, iced::Point)>, // Last cursor position within a cell
+    pub pan: Option<PanDrag>,
+    pub linked_zoom: bool, // Mirror zoom and pan to every cell
+    pub crop_draw: Option<CropDraw>,
+    // Compare mode
+    pub selection: Vec<usize>, // Up to two cells picked for comparison
+    pub compare: Option<Compare>,
+    pub quality: Option<crate::quality::Analysis>, // PSNR/SSIM of the compared pair
     pub window_width: f32,
+    pub window_height: f32,
+    pub scale_factor: f32,
     // Background media loading
     pub load_tx: mpsc::Sender<LoadResult>,
     pub load_rx: mpsc::Receiver<LoadResult>,
+    pub events: crate::events::EventChannel,
+    pub thumbnails: crate::thumbnails::Generator,
+    pub blender: crate::blend::Worker,
+    pub scopes: crate::scopes::Worker,
     pub loading_count: usize,
+    // Marker note editing
+    pub marker_edit: Option<MarkerEdit>,
+    pub timecode_entry: Option<TimecodeEntry>,
+    pub settings: crate::settings::Settings,
+    pub media_prefs: crate::media_prefs::PrefsStore,
+    pub luts: crate::lut::LutCache,
+    pub color_bypass: bool, // Color adjustments and LUTs off, for before/after
 }
 
 impl Default for App {
     fn default() -> Self {
         let (load_tx, load_rx) = mpsc::channel();
+        let events = crate::events::EventChannel::default();
+        let settings = crate::settings::Settings::load();
+        if let Err(e) = crate::decoders::apply(&settings.decoders) {
+            log::warn!("Failed to apply decoder preferences: {}", e);
+        }
+        let thumbnails = crate::thumbnails::Generator::spawn(events.tx.clone());
+        let blender = crate::blend::Worker::spawn(events.tx.clone());
+        let scopes = crate::scopes::Worker::spawn(events.tx.clone());
         App {
             media: Vec::new(),
             next_id: 0,
             grid_columns: 2, // Default to 2 columns
+            page: 0,
             error: None,
             status: "Drop media files here to load them".to_string(),
             watchdog: crate::watchdog::Watchdog::spawn(),
-            stall_check_counter: 0,
             drag_source_id: None,
             drag_target: None,
+            cursor: None,
+            pan: None,
+            linked_zoom: false,
+            crop_draw: None,
+            selection: Vec::new(),
+            compare: None,
+            quality: None,
             window_width: 800.0,
+            window_height: 600.0,
+            scale_factor: 1.0,
             load_tx,
             load_rx,
+            events,
+            thumbnails,
+            blender,
+            scopes,
             loading_count: 0,
+            marker_edit: None,
+            timecode_entry: None,
+            settings,
+            media_prefs: crate::media_prefs::PrefsStore::load(),
+            luts: crate::lut::LutCache::default(),
+            color_bypass: false,
         }
     }
 }
@@ -0,0 +1,170 @@
+//! Single frames decoded from a video with a throwaway pipeline: thumbnail posters,
+//! and stills exported with the cell's caption burned in.
+//!
+//! Captions are drawn by GStreamer's `textoverlay` onto the full-resolution frame, so
+//! an exported still carries the same lines as the on-screen caption without a font
+//! renderer of our own.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use image::{DynamicImage, RgbaImage};
+use std::path::{Path, PathBuf};
+
+use crate::caption::{CaptionPosition, CaptionSize};
+use crate::events::{EventSender, WorkerEvent};
+
+/// How long to wait for a frame pipeline to preroll.
+const FRAME_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(5);
+
+/// Where in a video to take a frame.
+#[derive(Clone, Copy, Debug)]
+pub enum FrameAt {
+    /// This fraction of the duration in, at the nearest keyframe.
+    Fraction(f64),
+    /// Exactly this many seconds in.
+    Seconds(f64),
+}
+
+/// Caption lines to draw on a frame, laid out like the on-screen caption.
+pub struct BurnIn {
+    pub lines: Vec<String>,
+    pub position: CaptionPosition,
+    pub size: CaptionSize,
+}
+
+/// Decode one frame of a video, upright and with square pixels, with `burn_in` drawn
+/// on it.
+pub fn decode_frame(
+    path: &Path,
+    at: FrameAt,
+    burn_in: Option<&BurnIn>,
+) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+    gst::init()?;
+    let url = url::Url::from_file_path(path).map_err(|_| "Invalid video path")?;
+    let overlay = if burn_in.is_some() {
+        "textoverlay name=caption ! videoconvert ! "
+    } else {
+        ""
+    };
+    let pipeline = gst::parse::launch(&format!(
+        "uridecodebin uri=\"{}\" ! videoflip method=automatic ! videoconvert ! videoscale ! \
+         {}appsink name=frame sync=false caps=video/x-raw,format=RGBA,pixel-aspect-ratio=1/1",
+        url.as_str(),
+        overlay
+    ))?
+    .downcast::<gst::Pipeline>()
+    .map_err(|_| "Failed to cast to Pipeline")?;
+
+    let result = (|| -> Result<DynamicImage, Box<dyn std::error::Error>> {
+        let appsink = pipeline
+            .by_name("frame")
+            .ok_or("Could not find frame appsink")?
+            .downcast::<gst_app::AppSink>()
+            .map_err(|_| "Failed to cast to AppSink")?;
+        if let Some(burn_in) = burn_in {
+            let caption = pipeline
+                .by_name("caption")
+                .ok_or("Could not find caption overlay")?;
+            configure_overlay(&caption, burn_in);
+        }
+
+        pipeline.set_state(gst::State::Paused)?;
+        pipeline.state(FRAME_TIMEOUT).0?;
+        let target = match at {
+            FrameAt::Fraction(fraction) => pipeline
+                .query_duration::<gst::ClockTime>()
+                .map(|d| (d.nseconds() as f64 * fraction, gst::SeekFlags::KEY_UNIT)),
+            FrameAt::Seconds(secs) => Some((secs * 1e9, gst::SeekFlags::ACCURATE)),
+        };
+        if let Some((nanos, flags)) = target {
+            let target = gst::ClockTime::from_nseconds(nanos.max(0.0) as u64);
+            pipeline.seek_simple(gst::SeekFlags::FLUSH | flags, target)?;
+            pipeline.state(FRAME_TIMEOUT).0?;
+        }
+
+        let sample = appsink
+            .try_pull_preroll(FRAME_TIMEOUT)
+            .ok_or("Timed out waiting for frame")?;
+        let structure = sample
+            .caps()
+            .and_then(|caps| caps.structure(0))
+            .ok_or("Frame sample has no caps")?;
+        let width = structure.get::<i32>("width")? as u32;
+        let height = structure.get::<i32>("height")? as u32;
+        let buffer = sample.buffer().ok_or("Frame sample has no buffer")?;
+        let map = buffer.map_readable()?;
+        // RGBA rows are already 4-byte aligned, so the stride is exactly width * 4
+        let pixels = map
+            .as_slice()
+            .get(..(width * height * 4) as usize)
+            .ok_or("Frame buffer too small")?
+            .to_vec();
+        let image = RgbaImage::from_raw(width, height, pixels).ok_or("Invalid frame")?;
+        Ok(DynamicImage::ImageRgba8(image))
+    })();
+
+    let _ = pipeline.set_state(gst::State::Null);
+    result
+}
+
+/// Set up `textoverlay` to draw the caption in its corner. Text is sized against a
+/// 640-pixel-wide frame and scaled with the video, the way a caption reads on a cell.
+fn configure_overlay(overlay: &gst::Element, burn_in: &BurnIn) {
+    // The overlay reads its text as Pango markup
+    let text = gst::glib::markup_escape_text(&burn_in.lines.join("\n"));
+    overlay.set_property("text", text.as_str());
+    overlay.set_property(
+        "font-desc",
+        format!("Sans {}", burn_in.size.text_size().round()),
+    );
+    overlay.set_property("shaded-background", true);
+    overlay.set_property("auto-resize", true);
+    overlay.set_property_from_str(
+        "valignment",
+        if burn_in.position.is_top() {
+            "top"
+        } else {
+            "bottom"
+        },
+    );
+    overlay.set_property_from_str(
+        "halignment",
+        if burn_in.position.is_left() {
+            "left"
+        } else {
+            "right"
+        },
+    );
+    overlay.set_property_from_str(
+        "line-alignment",
+        if burn_in.position.is_left() {
+            "left"
+        } else {
+            "right"
+        },
+    );
+}
+
+/// Export the frame `secs` into a video as a PNG still on a background thread. The
+/// outcome arrives as [`WorkerEvent::StillExported`].
+pub fn export_async(
+    path: PathBuf,
+    secs: f64,
+    burn_in: Option<BurnIn>,
+    out: PathBuf,
+    events: EventSender,
+) {
+    std::thread::Builder::new()
+        .name("still-export".to_string())
+        .spawn(move || {
+            let result = decode_frame(&path, FrameAt::Seconds(secs), burn_in.as_ref())
+                .and_then(|frame| {
+                    frame.save_with_format(&out, image::ImageFormat::Png)?;
+                    Ok(out)
+                })
+                .map_err(|e| e.to_string());
+            let _ = events.unbounded_send(WorkerEvent::StillExported(result));
+        })
+        .expect("Failed to spawn still export thread");
+}
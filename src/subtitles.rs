@@ -0,0 +1,321 @@
+//! Subtitles of a video: `.srt` / `.vtt` sidecar files and embedded text tracks.
+//!
+//! Sidecars next to the video (`movie.srt`, `movie.en.vtt`, ...) are parsed when it
+//! loads. Embedded text tracks go to a text appsink on the playbin instead of being
+//! burned into the frames; a probe forwards each cue as it is demuxed. Either way the
+//! cell looks up the cues at its own position, minus its subtitle delay, so subtitles
+//! stay with the cell through seeks, loops and framerate changes.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::path::{Path, PathBuf};
+
+use crate::events::{EventSender, WorkerEvent};
+
+/// Sidecar extensions, matched case-insensitively.
+const SIDECAR_EXTENSIONS: &[&str] = &["srt", "vtt"];
+/// Step of the per-cell subtitle delay, in seconds.
+pub const DELAY_STEP: f64 = 0.1;
+
+/// One subtitle, shown from `start` to `end` seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Cues of a sidecar file, sorted by start.
+pub struct Sidecar {
+    /// File name, for display.
+    pub name: String,
+    pub cues: Vec<Cue>,
+}
+
+/// Where a cell's subtitles come from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubtitleSource {
+    Off,
+    /// Index into the video's sidecars.
+    Sidecar(usize),
+    /// Embedded text track, as numbered by playbin's `current-text`.
+    Embedded(i32),
+}
+
+impl SubtitleSource {
+    /// Next source in the toggle cycle: each sidecar, each embedded track, then off.
+    pub fn next(self, sidecars: usize, embedded: i32) -> Self {
+        let first_embedded = || {
+            if embedded > 0 {
+                SubtitleSource::Embedded(0)
+            } else {
+                SubtitleSource::Off
+            }
+        };
+        match self {
+            SubtitleSource::Off if sidecars > 0 => SubtitleSource::Sidecar(0),
+            SubtitleSource::Off => first_embedded(),
+            SubtitleSource::Sidecar(i) if i + 1 < sidecars => SubtitleSource::Sidecar(i + 1),
+            SubtitleSource::Sidecar(_) => first_embedded(),
+            SubtitleSource::Embedded(i) if i + 1 < embedded => SubtitleSource::Embedded(i + 1),
+            SubtitleSource::Embedded(_) => SubtitleSource::Off,
+        }
+    }
+}
+
+/// Text of the cues showing at `secs`, one per line, or None between cues.
+pub fn text_at(cues: &[Cue], secs: f64) -> Option<String> {
+    // Cues are sorted by start; overlapping ones are shown together
+    let end = cues.partition_point(|c| c.start <= secs);
+    let lines: Vec<&str> = cues[..end]
+        .iter()
+        .filter(|c| secs < c.end)
+        .map(|c| c.text.as_str())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Add a cue to a sorted list, skipping it if an identical one is there already
+/// (embedded cues are delivered again after every seek and loop).
+pub fn insert_cue(cues: &mut Vec<Cue>, cue: Cue) {
+    let index = cues.partition_point(|c| c.start < cue.start);
+    if cues[index..]
+        .iter()
+        .take_while(|c| c.start == cue.start)
+        .any(|c| *c == cue)
+    {
+        return;
+    }
+    cues.insert(index, cue);
+}
+
+/// Parse an `HH:MM:SS,mmm` (SRT) or `[HH:]MM:SS.mmm` (WebVTT) timestamp.
+fn parse_timestamp(s: &str) -> Option<f64> {
+    let s = s.trim().replace(',', ".");
+    let mut secs = 0.0;
+    for field in s.split(':') {
+        secs = secs * 60.0 + field.parse::<f64>().ok().filter(|v| *v >= 0.0)?;
+    }
+    Some(secs)
+}
+
+/// Plain text of a cue: markup tags (`<i>`, `<c.yellow>`, `<v Name>`), SSA overrides
+/// (`{\an8}`) and the common entities removed.
+pub fn strip_markup(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut closing = None;
+    for ch in text.chars() {
+        match (closing, ch) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (None, _) => plain.push(ch),
+            (Some(close), _) if ch == close => closing = None,
+            (Some(_), _) => {}
+        }
+    }
+    plain
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Parse SRT or WebVTT: blocks separated by blank lines (or lines of only
+/// whitespace), each with a `start --> end` line followed by the text. Blocks without
+/// one (headers, notes, styles) are skipped, as are identifiers and cue settings.
+pub fn parse(contents: &str) -> Vec<Cue> {
+    let lines: Vec<&str> = contents.trim_start_matches('\u{feff}').lines().collect();
+    let mut cues = Vec::new();
+    for block in lines.split(|l| l.trim().is_empty()) {
+        let mut lines = block.iter().copied().skip_while(|l| !l.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, rest)) = timing.split_once("-->") else {
+            continue;
+        };
+        let end = rest.split_whitespace().next().unwrap_or_default();
+        let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            continue;
+        };
+        let text = strip_markup(&lines.collect::<Vec<_>>().join("\n"));
+        if end > start && !text.is_empty() {
+            cues.push(Cue { start, end, text });
+        }
+    }
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+/// Sidecar files of a video: same directory, names starting with the video's stem
+/// and ending in a subtitle extension, sorted by name.
+fn find_sidecars(video: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (video.parent(), video.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            let extension = p
+                .extension()
+                .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            name.starts_with(&*stem)
+                && name[stem.len()..].starts_with('.')
+                && SIDECAR_EXTENSIONS.contains(&extension.as_str())
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// Load every sidecar of a video. Unreadable files and files without cues are
+/// logged and skipped.
+pub fn load_sidecars(video: &Path) -> Vec<Sidecar> {
+    find_sidecars(video)
+        .into_iter()
+        .filter_map(|path| {
+            // Not every subtitle file is UTF-8; keep what can be read
+            let bytes = std::fs::read(&path)
+                .map_err(|e| log::warn!("Failed to read {}: {}", path.display(), e))
+                .ok()?;
+            let cues = parse(&String::from_utf8_lossy(&bytes));
+            if cues.is_empty() {
+                log::warn!("No subtitles found in {}", path.display());
+                return None;
+            }
+            log::info!("Loaded {} subtitles from {}", cues.len(), path.display());
+            Some(Sidecar {
+                name: path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                cues,
+            })
+        })
+        .collect()
+}
+
+/// Forward the text buffers reaching a video's text appsink as
+/// [`WorkerEvent::SubtitleCue`], timed in stream time.
+pub fn forward_cues(sink_pad: &gst::Pad, video_id: usize, events: EventSender) {
+    sink_pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+        let Some(gst::PadProbeData::Buffer(ref buffer)) = info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        // Image-based tracks (DVD, PGS) arrive as other caps and are not shown
+        let is_text = pad
+            .current_caps()
+            .and_then(|c| c.structure(0).map(|s| s.has_name("text/x-raw")))
+            .unwrap_or(false);
+        let segment = pad
+            .sticky_event::<gst::event::Segment>(0)
+            .and_then(|e| e.segment().downcast_ref::<gst::ClockTime>().cloned());
+        let (Some(pts), Some(segment), true) = (buffer.pts(), segment, is_text) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Some(start) = segment.to_stream_time(pts) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Ok(map) = buffer.map_readable() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let text = strip_markup(&String::from_utf8_lossy(map.as_slice()));
+        if !text.is_empty() {
+            let start = start.seconds_f64();
+            // Cues without a duration stay up for a few seconds
+            let duration = buffer.duration().map_or(4.0, |d| d.seconds_f64());
+            let cue = Cue {
+                start,
+                end: start + duration,
+                text,
+            };
+            let _ = events.unbounded_send(WorkerEvent::SubtitleCue(video_id, cue));
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start: f64, end: f64, text: &str) -> Cue {
+        Cue {
+            start,
+            end,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn timestamps_in_srt_and_webvtt_forms() {
+        assert_eq!(parse_timestamp("00:01:02,500"), Some(62.5));
+        assert_eq!(parse_timestamp("01:00:00.250"), Some(3600.25));
+        assert_eq!(parse_timestamp(" 01:02.5 "), Some(62.5));
+        assert_eq!(parse_timestamp("00:00:-1,000"), None);
+        assert_eq!(parse_timestamp("00:aa:01,000"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn markup_and_entities_are_stripped() {
+        assert_eq!(strip_markup("<i>Hello</i> <b>there</b>"), "Hello there");
+        assert_eq!(strip_markup("<v Roger>Yes <c.yellow>sir</c>"), "Yes sir");
+        assert_eq!(strip_markup("{\\an8}Top line"), "Top line");
+        assert_eq!(strip_markup("a &lt;b&gt; &amp;&nbsp;c"), "a <b> & c");
+        assert_eq!(strip_markup("  <i></i>  "), "");
+    }
+
+    #[test]
+    fn srt_blocks_split_on_blank_and_whitespace_lines() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\n<i>First</i>\r\n\r\n\
+                   2\n00:00:03,000 --> 00:00:04,000\nSecond\nline two\n  \t\n\
+                   3\n00:00:05,000 --> 00:00:06,000\nThird\n";
+        assert_eq!(
+            parse(srt),
+            vec![
+                cue(1.0, 2.5, "First"),
+                cue(3.0, 4.0, "Second\nline two"),
+                cue(5.0, 6.0, "Third"),
+            ]
+        );
+    }
+
+    #[test]
+    fn webvtt_headers_settings_and_empty_cues_are_skipped() {
+        let vtt = "WEBVTT\n\nNOTE a comment\n\n\
+                   intro\n00:05.000 --> 00:06.000 align:start position:10%\nLater\n\n\
+                   00:01.000 --> 00:02.000\nEarlier\n\n\
+                   00:03.000 --> 00:02.000\nBackwards\n\n\
+                   00:07.000 --> 00:08.000\n<i></i>\n";
+        assert_eq!(
+            parse(vtt),
+            vec![cue(1.0, 2.0, "Earlier"), cue(5.0, 6.0, "Later")]
+        );
+    }
+
+    #[test]
+    fn text_at_shows_overlapping_cues_together() {
+        let cues = vec![
+            cue(1.0, 3.0, "one"),
+            cue(2.0, 4.0, "two"),
+            cue(5.0, 6.0, "three"),
+        ];
+        assert_eq!(text_at(&cues, 0.5), None);
+        assert_eq!(text_at(&cues, 1.0).as_deref(), Some("one"));
+        assert_eq!(text_at(&cues, 2.5).as_deref(), Some("one\ntwo"));
+        assert_eq!(text_at(&cues, 3.0).as_deref(), Some("two"));
+        assert_eq!(text_at(&cues, 4.5), None);
+        assert_eq!(text_at(&cues, 6.0), None);
+        assert_eq!(text_at(&[], 1.0), None);
+    }
+}
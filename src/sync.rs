@@ -36,3 +36,23 @@ pub fn synchronized_set_paused(video_id: usize, video: &iced_video_player::Video
 
     gst_logger::log_pause_toggle_complete(video_id, paused, start);
 }
+
+/// Position a video would have reached after playing for `elapsed` seconds from `position`.
+/// Looping videos wrap around; others stop at the end.
+pub fn expected_position(position: f64, elapsed: f64, duration: f64, looping: bool) -> f64 {
+    let target = position + elapsed.max(0.0);
+    if duration <= 0.0 || !target.is_finite() {
+        position
+    } else if looping {
+        target % duration
+    } else {
+        target.min(duration)
+    }
+}
+
+/// Pause or resume several videos together.
+pub fn synchronized_group_set_paused(videos: &[(usize, &iced_video_player::Video)], paused: bool) {
+    for (id, video) in videos {
+        synchronized_set_paused(*id, video, paused);
+    }
+}
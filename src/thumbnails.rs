@@ -0,0 +1,190 @@
+//! Persistent thumbnail cache.
+//!
+//! Poster frames for videos and downscaled copies of photos are stored as PNG files in
+//! `$XDG_CACHE_HOME/cineplex/thumbnails` (falling back to `~/.cache`), keyed by path,
+//! file size and modification time so edited files get fresh thumbnails. They stand in
+//! for items while pipelines spin up and for cells whose video was paged out.
+
+use image::DynamicImage;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::events::{EventSender, WorkerEvent};
+use crate::loader;
+use crate::stills::{self, FrameAt};
+use crate::worker::LatestWorker;
+
+/// Longest side of a cached thumbnail.
+const MAX_DIMENSION: u32 = 320;
+/// Total size of the cache directory before the oldest thumbnails are pruned.
+const CACHE_LIMIT_BYTES: u64 = 256 * 1024 * 1024;
+/// Poster frames are taken this far into the video, past black leaders and fades.
+const POSTER_POSITION: f64 = 0.1;
+
+/// Directory holding cached thumbnails.
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    Some(base.join("cineplex").join("thumbnails"))
+}
+
+/// Cache file name for a media file: FNV-1a over its path, size and mtime.
+fn cache_key(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let path_bytes = path.as_os_str().as_encoded_bytes();
+    let size_bytes = metadata.len().to_le_bytes();
+    let mtime_bytes = mtime.to_le_bytes();
+    for byte in path_bytes.iter().chain(&size_bytes).chain(&mtime_bytes) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Some(format!("{:016x}.png", hash))
+}
+
+/// Cached thumbnail for a media file, if one exists for its current contents.
+fn lookup(path: &Path) -> Option<PathBuf> {
+    let file = cache_dir()?.join(cache_key(path)?);
+    file.is_file().then_some(file)
+}
+
+/// Downscale and write a thumbnail, then prune the cache if it grew past its limit.
+fn store(path: &Path, image: &DynamicImage) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = cache_dir().ok_or("No cache directory")?;
+    let key = cache_key(path).ok_or("Cannot stat media file")?;
+    std::fs::create_dir_all(&dir)?;
+
+    let file = dir.join(key);
+    image.thumbnail(MAX_DIMENSION, MAX_DIMENSION).save(&file)?;
+    prune(&dir);
+    Ok(file)
+}
+
+/// Delete the least recently written thumbnails until the cache is well under its limit.
+fn prune(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            Some((e.path(), metadata.len(), modified))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= CACHE_LIMIT_BYTES {
+        return;
+    }
+
+    files.sort_by_key(|&(_, _, modified)| modified);
+    let target = CACHE_LIMIT_BYTES / 4 * 3;
+    let mut removed = 0;
+    for (file, size, _) in files {
+        if total <= target {
+            break;
+        }
+        if std::fs::remove_file(&file).is_ok() {
+            total = total.saturating_sub(size);
+            removed += 1;
+        }
+    }
+    log::info!(
+        "Thumbnail cache pruned: removed={}, remaining_bytes={}",
+        removed,
+        total
+    );
+}
+
+/// Outcome of [`clear`]: files that could not be removed don't stop the rest.
+#[derive(Default)]
+pub struct Cleared {
+    pub removed: usize,
+    pub failed: Vec<(PathBuf, std::io::Error)>,
+}
+
+/// Remove every cached thumbnail. Fails only if the cache directory can't be read.
+pub fn clear() -> std::io::Result<Cleared> {
+    let mut cleared = Cleared::default();
+    let Some(dir) = cache_dir() else {
+        return Ok(cleared);
+    };
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(cleared),
+        Err(e) => return Err(e),
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        match std::fs::remove_file(&path) {
+            Ok(()) => cleared.removed += 1,
+            Err(e) => cleared.failed.push((path, e)),
+        }
+    }
+    Ok(cleared)
+}
+
+/// Headless `--clear-thumbnails`: empty the cache and report what was left behind.
+/// Returns the process exit code.
+pub fn run_clear_cli() -> i32 {
+    match clear() {
+        Ok(cleared) => {
+            println!("Removed {} cached thumbnails", cleared.removed);
+            for (path, e) in &cleared.failed {
+                eprintln!("Failed to remove {}: {}", path.display(), e);
+            }
+            if cleared.failed.is_empty() { 0 } else { 1 }
+        }
+        Err(e) => {
+            eprintln!("Failed to clear thumbnail cache: {}", e);
+            1
+        }
+    }
+}
+
+/// Background worker generating missing thumbnails one at a time.
+pub struct Generator {
+    worker: LatestWorker<usize, PathBuf>,
+}
+
+impl Generator {
+    /// Spawn the worker. Each thumbnail, found in the cache or freshly generated, is
+    /// announced with [`WorkerEvent::ThumbnailReady`] for the item that requested it,
+    /// so the UI thread never stats or hashes media files itself.
+    pub fn spawn(events: EventSender) -> Self {
+        let worker = LatestWorker::spawn("thumbnails", move |id: usize, path: PathBuf| {
+            if let Some(file) = lookup(&path) {
+                let _ = events.unbounded_send(WorkerEvent::ThumbnailReady(id, file));
+                return;
+            }
+            let image = if loader::is_video_file(&path) {
+                stills::decode_frame(&path, FrameAt::Fraction(POSTER_POSITION), None)
+            } else {
+                loader::open_oriented(&path)
+            };
+            match image.and_then(|image| store(&path, &image)) {
+                Ok(file) => {
+                    log::debug!("Thumbnail cached: id={}, file={}", id, file.display());
+                    let _ = events.unbounded_send(WorkerEvent::ThumbnailReady(id, file));
+                }
+                Err(e) => log::warn!("Thumbnail failed: path={}, error={}", path.display(), e),
+            }
+        });
+        Generator { worker }
+    }
+
+    /// Queue a thumbnail for `path`, looked up in the cache or generated.
+    pub fn request(&self, id: usize, path: PathBuf) {
+        self.worker.request(id, path);
+    }
+}
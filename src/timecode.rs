@@ -0,0 +1,169 @@
+//! SMPTE timecode formatting helpers.
+
+/// Nominal integer frame rate used for timecode counting (e.g. 29.97 -> 30).
+pub fn timecode_rate(fps: f64) -> u64 {
+    if fps.is_finite() && fps >= 1.0 {
+        fps.round() as u64
+    } else {
+        30
+    }
+}
+
+/// Format a frame count as non-drop-frame `HH:MM:SS:FF`.
+pub fn frames_to_timecode(frames: u64, rate: u64) -> String {
+    let rate = rate.max(1);
+    let ff = frames % rate;
+    let total_secs = frames / rate;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60,
+        ff
+    )
+}
+
+/// How a stream's frames are numbered: its real framerate, the nominal rate timecode
+/// counts at, and whether labels are dropped to keep timecode on the clock (29.97 and
+/// 59.94 fps, as cameras and editors count them).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timebase {
+    pub fps: f64,
+    pub rate: u64,
+    pub drop_frame: bool,
+}
+
+impl Timebase {
+    pub fn new(fps: f64) -> Self {
+        let rate = timecode_rate(fps);
+        let fps = if fps.is_finite() && fps >= 1.0 {
+            fps
+        } else {
+            rate as f64
+        };
+        Timebase {
+            fps,
+            rate,
+            drop_frame: (rate == 30 || rate == 60) && (fps * 1.001 - rate as f64).abs() < 0.01,
+        }
+    }
+
+    /// Labels skipped at the start of each minute, except every tenth, in drop-frame.
+    fn dropped_per_minute(&self) -> u64 {
+        if self.drop_frame { self.rate / 15 } else { 0 }
+    }
+
+    /// Index of the frame shown at `secs`.
+    pub fn frames(&self, secs: f64) -> u64 {
+        if !secs.is_finite() || secs <= 0.0 {
+            return 0;
+        }
+        (secs * self.fps).round() as u64
+    }
+
+    /// Start time of frame `frames`, in seconds.
+    pub fn seconds(&self, frames: u64) -> f64 {
+        frames as f64 / self.fps
+    }
+
+    /// Label of frame `frames`: `HH:MM:SS:FF`, or `HH:MM:SS;FF` in drop-frame.
+    pub fn format(&self, frames: u64) -> String {
+        let drop = self.dropped_per_minute();
+        if drop == 0 {
+            return frames_to_timecode(frames, self.rate);
+        }
+        let per_minute = self.rate * 60 - drop;
+        let per_ten_minutes = self.rate * 600 - drop * 9;
+        let (tens, rest) = (frames / per_ten_minutes, frames % per_ten_minutes);
+        let skipped = drop * 9 * tens + drop * (rest.saturating_sub(drop) / per_minute);
+        let label = frames_to_timecode(frames + skipped, self.rate);
+        match label.rsplit_once(':') {
+            Some((hms, ff)) => format!("{};{}", hms, ff),
+            None => label,
+        }
+    }
+
+    /// Frame of a typed or tagged label (`HH:MM:SS:FF`, `;` or `.` also accepted before
+    /// the frames), or None if it isn't one.
+    pub fn parse(&self, label: &str) -> Option<u64> {
+        let fields: Vec<u64> = label
+            .trim()
+            .split([':', ';', '.'])
+            .map(|f| f.parse().ok())
+            .collect::<Option<_>>()?;
+        let &[hh, mm, ss, ff] = fields.as_slice() else {
+            return None;
+        };
+        if mm >= 60 || ss >= 60 || ff >= self.rate {
+            return None;
+        }
+        let minutes = hh * 60 + mm;
+        let nominal = (minutes * 60 + ss) * self.rate + ff;
+        let skipped = self.dropped_per_minute() * (minutes - minutes / 10);
+        Some(nominal.saturating_sub(skipped))
+    }
+
+    /// Frame of a start timecode found in a container tag: the whole value of a tag
+    /// named like a timecode, or a `timecode=` entry of any tag (how extended comments
+    /// carry unknown keys).
+    pub fn parse_tag(&self, name: &str, value: &str) -> Option<u64> {
+        let named = name.to_ascii_lowercase().contains("timecode");
+        named.then(|| self.parse(value)).flatten().or_else(|| {
+            let (key, label) = value.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("timecode")
+                .then(|| self.parse(label))?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NTSC: f64 = 30000.0 / 1001.0;
+
+    #[test]
+    fn drop_frame_only_at_ntsc_rates() {
+        assert!(Timebase::new(NTSC).drop_frame);
+        assert!(Timebase::new(60000.0 / 1001.0).drop_frame);
+        assert!(!Timebase::new(30.0).drop_frame);
+        assert!(!Timebase::new(24000.0 / 1001.0).drop_frame);
+        assert_eq!(Timebase::new(24000.0 / 1001.0).rate, 24);
+    }
+
+    #[test]
+    fn drop_frame_skips_labels_except_every_tenth_minute() {
+        let tb = Timebase::new(NTSC);
+        assert_eq!(tb.format(0), "00:00:00;00");
+        assert_eq!(tb.format(1799), "00:00:59;29");
+        assert_eq!(tb.format(1800), "00:01:00;02");
+        assert_eq!(tb.format(17982), "00:10:00;00");
+        assert_eq!(tb.format(107892), "01:00:00;00");
+    }
+
+    #[test]
+    fn drop_frame_labels_round_trip() {
+        let tb = Timebase::new(NTSC);
+        for frames in (0..120_000).step_by(7) {
+            assert_eq!(tb.parse(&tb.format(frames)), Some(frames), "{}", frames);
+        }
+    }
+
+    #[test]
+    fn non_drop_frame_counts_nominal_frames() {
+        let tb = Timebase::new(25.0);
+        assert_eq!(tb.format(25 * 3600 + 24), "01:00:00:24");
+        assert_eq!(tb.parse("01:00:00:24"), Some(25 * 3600 + 24));
+        assert_eq!(tb.parse("00:00:00:25"), None);
+        assert_eq!(tb.parse("1:2:3"), None);
+    }
+
+    #[test]
+    fn start_timecode_from_tags() {
+        let tb = Timebase::new(30.0);
+        assert_eq!(tb.parse_tag("timecode", "01:00:00:00"), Some(108000));
+        assert_eq!(tb.parse_tag("comment", "timecode=00:00:01:00"), Some(30));
+        assert_eq!(tb.parse_tag("comment", "00:00:01:00"), None);
+    }
+}
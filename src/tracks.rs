@@ -0,0 +1,98 @@
+//! Video, audio and subtitle tracks of multi-track files, as playbin exposes them.
+//!
+//! playbin numbers the streams of each kind (`n-video`, `n-audio`, `n-text`), plays one
+//! of each (`current-video`, ...) and keeps the tags of every stream, from which the
+//! picker labels each track with its language, codec and title.
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+/// Kind of stream a track carries.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Text,
+}
+
+impl TrackKind {
+    pub const ALL: [TrackKind; 3] = [TrackKind::Video, TrackKind::Audio, TrackKind::Text];
+
+    /// Name used by playbin's properties and signals.
+    fn as_str(self) -> &'static str {
+        match self {
+            TrackKind::Video => "video",
+            TrackKind::Audio => "audio",
+            TrackKind::Text => "text",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            TrackKind::Video => "Video",
+            TrackKind::Audio => "Audio",
+            TrackKind::Text => "Subtitles",
+        }
+    }
+}
+
+/// Tracks of one kind, and the one playing (-1 for none).
+pub struct TrackGroup {
+    pub kind: TrackKind,
+    pub labels: Vec<String>,
+    pub current: i32,
+}
+
+/// Number of tracks of a kind.
+pub fn count(pipeline: &gst::Pipeline, kind: TrackKind) -> i32 {
+    pipeline.property(&format!("n-{}", kind.as_str()))
+}
+
+/// Track of a kind that is playing, -1 for none.
+pub fn current(pipeline: &gst::Pipeline, kind: TrackKind) -> i32 {
+    pipeline.property(&format!("current-{}", kind.as_str()))
+}
+
+/// Play another track of a kind.
+pub fn select(pipeline: &gst::Pipeline, kind: TrackKind, index: i32) {
+    pipeline.set_property(&format!("current-{}", kind.as_str()), index);
+}
+
+/// Label of a track from its tags: number, language, codec and title, as known.
+fn label(pipeline: &gst::Pipeline, kind: TrackKind, index: i32) -> String {
+    let mut parts = vec![format!("{}", index + 1)];
+    let signal = format!("get-{}-tags", kind.as_str());
+    if let Some(tags) = pipeline.emit_by_name::<Option<gst::TagList>>(&signal, &[&index]) {
+        let language = tags
+            .get::<gst::tags::LanguageName>()
+            .map(|v| v.get().to_string())
+            .or_else(|| {
+                tags.get::<gst::tags::LanguageCode>()
+                    .map(|v| v.get().to_string())
+            });
+        let codec = match kind {
+            TrackKind::Video => tags.get::<gst::tags::VideoCodec>(),
+            TrackKind::Audio => tags.get::<gst::tags::AudioCodec>(),
+            TrackKind::Text => tags.get::<gst::tags::SubtitleCodec>(),
+        }
+        .map(|v| v.get().to_string());
+        let title = tags.get::<gst::tags::Title>().map(|v| v.get().to_string());
+        parts.extend([language, codec, title].into_iter().flatten());
+    }
+    parts.join(" · ")
+}
+
+/// Every kind's tracks, skipping kinds the file has none of.
+pub fn read_all(pipeline: &gst::Pipeline) -> Vec<TrackGroup> {
+    TrackKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let n = count(pipeline, kind);
+            (n > 0).then(|| TrackGroup {
+                kind,
+                labels: (0..n).map(|i| label(pipeline, kind, i)).collect(),
+                current: current(pipeline, kind),
+            })
+        })
+        .collect()
+}
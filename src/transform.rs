@@ -0,0 +1,170 @@
+//! Manual rotation, flip and crop of a media file.
+//!
+//! Applied on top of the automatic orientation (`videoflip method=automatic` for
+//! videos, EXIF for photos) inside the pipeline or decoder, so the grid, zoom, blends
+//! and decode sizing all see the corrected frame. Orientation is stored as a horizontal
+//! mirror followed by clockwise quarter turns, which covers every combination of
+//! 90° rotations and flips. The crop is normalized to the oriented frame.
+
+use iced::Point;
+use image::DynamicImage;
+
+/// Smallest crop side, as a fraction of the frame, so a stray click doesn't crop to nothing.
+const MIN_CROP_FRACTION: f32 = 0.02;
+
+/// Region of a frame to keep, in normalized (0..1) coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CropRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl CropRect {
+    /// Rectangle spanned by two normalized points, clamped to the frame.
+    /// None if it is too small to be meant as a crop.
+    pub fn from_points(a: Point, b: Point) -> Option<Self> {
+        let (x0, x1) = (a.x.min(b.x).clamp(0.0, 1.0), a.x.max(b.x).clamp(0.0, 1.0));
+        let (y0, y1) = (a.y.min(b.y).clamp(0.0, 1.0), a.y.max(b.y).clamp(0.0, 1.0));
+        if x1 - x0 < MIN_CROP_FRACTION || y1 - y0 < MIN_CROP_FRACTION {
+            return None;
+        }
+        Some(CropRect {
+            x: x0,
+            y: y0,
+            width: x1 - x0,
+            height: y1 - y0,
+        })
+    }
+
+    /// This rectangle, drawn on a frame already cropped to `outer`, in uncropped coordinates.
+    pub fn within(self, outer: Option<CropRect>) -> CropRect {
+        let Some(outer) = outer else {
+            return self;
+        };
+        CropRect {
+            x: outer.x + self.x * outer.width,
+            y: outer.y + self.y * outer.height,
+            width: self.width * outer.width,
+            height: self.height * outer.height,
+        }
+    }
+
+    /// Pixels removed from the left, top, right and bottom of a `size` frame.
+    /// Rounded to even counts, which every subsampled format can crop at.
+    pub fn edges(&self, size: (u32, u32)) -> (u32, u32, u32, u32) {
+        let even = |v: f32| (v.round().max(0.0) as u32) & !1;
+        let (w, h) = (size.0 as f32, size.1 as f32);
+        let left = even(self.x * w).min(size.0);
+        let top = even(self.y * h).min(size.1);
+        let right = even((1.0 - self.x - self.width) * w).min(size.0 - left);
+        let bottom = even((1.0 - self.y - self.height) * h).min(size.1 - top);
+        (left, top, right, bottom)
+    }
+
+    fn rotated_clockwise(self) -> Self {
+        CropRect {
+            x: 1.0 - self.y - self.height,
+            y: self.x,
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    fn flipped_horizontal(self) -> Self {
+        CropRect {
+            x: 1.0 - self.x - self.width,
+            ..self
+        }
+    }
+
+    fn flipped_vertical(self) -> Self {
+        CropRect {
+            y: 1.0 - self.y - self.height,
+            ..self
+        }
+    }
+}
+
+/// Rotation, flip and crop applied to a file's frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Transform {
+    pub quarter_turns: u8, // Clockwise, 0..4
+    pub mirrored: bool,    // Flipped horizontally before turning
+    pub crop: Option<CropRect>,
+}
+
+impl Transform {
+    /// Turn the picture a quarter clockwise. The crop turns with it, keeping the same region.
+    pub fn rotate_clockwise(&mut self) {
+        self.quarter_turns = (self.quarter_turns + 1) % 4;
+        self.crop = self.crop.map(CropRect::rotated_clockwise);
+    }
+
+    /// Mirror the picture as currently shown left to right.
+    pub fn flip_horizontal(&mut self) {
+        self.quarter_turns = (4 - self.quarter_turns) % 4;
+        self.mirrored = !self.mirrored;
+        self.crop = self.crop.map(CropRect::flipped_horizontal);
+    }
+
+    /// Mirror the picture as currently shown top to bottom.
+    pub fn flip_vertical(&mut self) {
+        self.quarter_turns = (6 - self.quarter_turns) % 4;
+        self.mirrored = !self.mirrored;
+        self.crop = self.crop.map(CropRect::flipped_vertical);
+    }
+
+    /// `videoflip` method performing the orientation.
+    pub fn videoflip_method(&self) -> &'static str {
+        match (self.mirrored, self.quarter_turns) {
+            (false, 1) => "clockwise",
+            (false, 2) => "rotate-180",
+            (false, 3) => "counterclockwise",
+            (true, 0) => "horizontal-flip",
+            (true, 1) => "upper-right-diagonal",
+            (true, 2) => "vertical-flip",
+            (true, 3) => "upper-left-diagonal",
+            _ => "none",
+        }
+    }
+
+    /// Size of a `size` frame after orientation, before the crop.
+    pub fn oriented_size(&self, size: (u32, u32)) -> (u32, u32) {
+        if self.quarter_turns % 2 == 1 {
+            (size.1, size.0)
+        } else {
+            size
+        }
+    }
+
+    /// Size of a `size` frame after orientation and crop.
+    pub fn output_size(&self, size: (u32, u32)) -> (u32, u32) {
+        let oriented = self.oriented_size(size);
+        match self.crop {
+            Some(crop) => {
+                let (left, top, right, bottom) = crop.edges(oriented);
+                (oriented.0 - left - right, oriented.1 - top - bottom)
+            }
+            None => oriented,
+        }
+    }
+
+    /// Orient and crop a decoded photo.
+    pub fn apply_to_image(&self, img: DynamicImage) -> DynamicImage {
+        let img = if self.mirrored { img.fliph() } else { img };
+        let img = match self.quarter_turns {
+            1 => img.rotate90(),
+            2 => img.rotate180(),
+            3 => img.rotate270(),
+            _ => img,
+        };
+        let Some(crop) = self.crop else {
+            return img;
+        };
+        let (left, top, right, bottom) = crop.edges((img.width(), img.height()));
+        let (width, height) = (img.width() - left - right, img.height() - top - bottom);
+        img.crop_imm(left, top, width.max(1), height.max(1))
+    }
+}
@@ -1,19 +1,50 @@
+use iced::widget::image::Handle;
 use iced::widget::text::Shaping;
 use iced::widget::{
-    button, center, column, container, image, mouse_area, row, slider, stack, text,
+    Row, button, center, column, container, image, mouse_area, pin, responsive, row, slider, stack,
+    text, text_input,
 };
 use iced::{Color, Element, Length, Theme, alignment};
 use iced_video_player::VideoPlayer;
 use std::time::Instant;
 
+use crate::caption;
+use crate::chapters::{self, Chapter};
+use crate::color::ColorAdjust;
+use crate::decoders::DecoderFamily;
+use crate::exposure::ExposureAids;
+use crate::fit::{FitMode, native_layout};
+use crate::letterbox::Letterbox;
+use crate::markers::Marker;
 use crate::message::Message;
-use crate::state::{App, MediaItem, PhotoInstance, VideoInstance};
+use crate::quality::{self, Analysis};
+use crate::scopes::ScopeView;
+use crate::settings::FramerateMode;
+use crate::state::{
+    App, Compare, CompareMode, CompareSide, CropDraw, MarkerEdit, MediaItem, PendingMedia,
+    PhotoInstance, TimecodeEntry, VideoInstance, WipeAxis,
+};
+use crate::subtitles::SubtitleSource;
+use crate::tracks::{TrackGroup, TrackKind};
+use crate::viewport::viewport;
+use crate::zoom::ZoomView;
+
+/// Widget ID of the marker note input, focused when a marker is dropped.
+pub const MARKER_NOTE_INPUT_ID: &str = "marker-note";
+/// Widget ID of the timecode input, focused when a timecode jump is started.
+pub const TIMECODE_INPUT_ID: &str = "timecode-entry";
+
+/// Border and label color of cells selected for comparison.
+const SELECTION_COLOR: Color = Color::from_rgb(1.0, 0.75, 0.2);
 
 /// Duration before UI starts fading (in seconds).
 const FADE_DELAY_SECS: f64 = 3.0;
 /// Duration of the fade animation (in seconds).
 const FADE_DURATION_SECS: f64 = 0.5;
 
+/// Chapters listed in the info panel at once, so long lists don't run off the cell.
+const MAX_LISTED_CHAPTERS: usize = 12;
+
 /// Compute UI opacity based on time since last mouse activity.
 /// Returns 1.0 for fully visible, 0.0 for fully hidden.
 /// Quantized to steps of 0.1 to prevent continuous layout invalidation.
@@ -33,18 +64,790 @@ fn compute_ui_opacity(last_mouse_activity: Instant) -> f32 {
     (raw_opacity * 10.0).round() / 10.0
 }
 
+/// Where an overlay is in its fade-out. Ordered so the most urgent phase sorts first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FadePhase {
+    Fading,
+    Visible,
+    Hidden,
+}
+
+/// Fade phase for an overlay given the time of the last mouse activity.
+pub fn fade_phase(last_mouse_activity: Instant) -> FadePhase {
+    let elapsed = last_mouse_activity.elapsed().as_secs_f64();
+    if elapsed < FADE_DELAY_SECS {
+        FadePhase::Visible
+    } else if elapsed < FADE_DELAY_SECS + FADE_DURATION_SECS {
+        FadePhase::Fading
+    } else {
+        FadePhase::Hidden
+    }
+}
+
+/// Build a strip of tick marks at marker positions, aligned with the seek slider.
+fn build_marker_ticks<'a>(markers: &[Marker], duration: f64, opacity: f32) -> Element<'a, Message> {
+    let tick_color = Color::from_rgba(1.0, 0.6, 0.1, opacity);
+    let mut ticks = row![].width(Length::Fill).height(Length::Fixed(6.0));
+    let mut last = 0.0;
+
+    // Spacers use FillPortion in thousandths of the duration
+    for marker in markers {
+        let frac = (marker.position / duration).clamp(0.0, 1.0);
+        let gap = ((frac - last) * 1000.0).round() as u16;
+        if gap > 0 {
+            ticks = ticks.push(container("").width(Length::FillPortion(gap)));
+        }
+        ticks = ticks.push(
+            container("")
+                .width(Length::Fixed(2.0))
+                .height(Length::Fill)
+                .style(move |_theme: &Theme| container::Style {
+                    background: Some(tick_color.into()),
+                    ..Default::default()
+                }),
+        );
+        last = frac;
+    }
+    let rest = ((1.0 - last) * 1000.0).round() as u16;
+    if rest > 0 {
+        ticks = ticks.push(container("").width(Length::FillPortion(rest)));
+    }
+
+    // Inset by the slider handle radius so ticks line up with the handle center
+    container(ticks).padding([0, 7]).width(Length::Fill).into()
+}
+
+/// Build the note input shown while a freshly dropped marker is being annotated.
+fn build_marker_note_input<'a>(edit: &'a MarkerEdit) -> Element<'a, Message> {
+    text_input("Marker note (Enter to save, Esc to skip)", &edit.draft)
+        .id(MARKER_NOTE_INPUT_ID)
+        .on_input(Message::MarkerNoteChanged)
+        .on_submit(Message::CommitMarkerNote)
+        .size(12)
+        .padding(5)
+        .into()
+}
+
+/// Build the input a timecode to jump to is typed in.
+fn build_timecode_input<'a>(entry: &'a TimecodeEntry) -> Element<'a, Message> {
+    let placeholder = if entry.video_id.is_some() {
+        "Go to HH:MM:SS:FF (Enter to jump, Esc to cancel)"
+    } else {
+        "Go to HH:MM:SS:FF in every video (Enter to jump, Esc to cancel)"
+    };
+    text_input(placeholder, &entry.draft)
+        .id(TIMECODE_INPUT_ID)
+        .on_input(Message::TimecodeEntryChanged)
+        .on_submit(Message::CommitTimecodeEntry)
+        .size(12)
+        .padding(5)
+        .into()
+}
+
+/// Position of a video as minutes and seconds, or as its source timecode.
+fn position_label(vid: &VideoInstance, timecode: bool) -> String {
+    time_label(vid, vid.position, timecode)
+}
+
+/// A time `secs` into a video as minutes and seconds, or as its source timecode.
+fn time_label(vid: &VideoInstance, secs: f64, timecode: bool) -> String {
+    if timecode {
+        vid.timecode_at(secs)
+    } else {
+        format!("{}:{:02}", secs as u64 / 60, secs as u64 % 60)
+    }
+}
+
+/// Position label that starts a timecode jump in its video when clicked.
+fn build_position_button<'a>(
+    vid: &VideoInstance,
+    timecode: bool,
+    text_color: Color,
+) -> Element<'a, Message> {
+    button(
+        text(position_label(vid, timecode))
+            .size(12)
+            .color(text_color),
+    )
+    .on_press(Message::OpenTimecodeEntry(Some(vid.id)))
+    .padding(8)
+    .width(Length::Shrink)
+    .height(Length::Shrink)
+    .into()
+}
+
 /// Format FPS for display.
 fn get_fps_display(fps: f64) -> String {
     format!("{:.1} FPS", fps)
 }
 
-/// Wrap a media cell with drag visual feedback (dim source, insertion bar on target).
+/// FPS label showing the delivered rate while playing (the native rate when paused).
+/// Green when delivery keeps up with the stream, yellow when frames are being lost.
+fn build_fps_text<'a>(vid: &VideoInstance, opacity: f32) -> iced::widget::Text<'a> {
+    let delivering = !vid.video.paused() && vid.measured_fps > 0.0;
+    let fps = if delivering {
+        vid.measured_fps
+    } else {
+        vid.native_fps
+    };
+    let keeping_up = !delivering || vid.measured_fps >= vid.native_fps * 0.9;
+    let fps_color = if keeping_up {
+        Color::from_rgba(0.0, 1.0, 0.0, opacity)
+    } else {
+        Color::from_rgba(1.0, 0.85, 0.0, opacity)
+    };
+    text(get_fps_display(fps))
+        .size(14)
+        .shaping(Shaping::Basic)
+        .color(fps_color)
+}
+
+/// Build a strip of chapter segments aligned with the seek slider, the playing chapter
+/// brighter than the rest.
+fn build_chapter_segments<'a>(
+    chapters: &[Chapter],
+    duration: f64,
+    position: f64,
+    opacity: f32,
+) -> Element<'a, Message> {
+    let current = chapters::index_at(chapters, position);
+    let mut segments = row![]
+        .spacing(2)
+        .width(Length::Fill)
+        .height(Length::Fixed(4.0));
+
+    // Same thousandths-of-the-duration portions as the marker ticks
+    let portion =
+        |from: f64, to: f64| (((to - from) / duration).clamp(0.0, 1.0) * 1000.0).round() as u16;
+    if let Some(first) = chapters.first() {
+        let lead = portion(0.0, first.start);
+        if lead > 0 {
+            segments = segments.push(container("").width(Length::FillPortion(lead)));
+        }
+    }
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end = chapters.get(i + 1).map_or(duration, |c| c.start);
+        let width = portion(chapter.start, end);
+        if width == 0 {
+            continue;
+        }
+        let alpha = if current == Some(i) { 0.9 } else { 0.4 };
+        let color = Color::from_rgba(0.4, 0.75, 1.0, alpha * opacity);
+        segments = segments.push(
+            container("")
+                .width(Length::FillPortion(width))
+                .height(Length::Fill)
+                .style(move |_theme: &Theme| container::Style {
+                    background: Some(color.into()),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    // Inset by the slider handle radius, like the marker ticks
+    container(segments)
+        .padding([0, 7])
+        .width(Length::Fill)
+        .into()
+}
+
+/// Technical details for a video, shown in the top-left corner while toggled on.
+fn build_video_info_panel<'a>(vid: &'a VideoInstance) -> Element<'a, Message> {
+    let (source_w, source_h) = vid.source_size;
+    let decode = match vid.decode_size {
+        Some((w, h)) => format!("{}x{}", w, h),
+        None => "full resolution".to_string(),
+    };
+    let decoder = match &vid.decoder {
+        Some(name) => format!("{} ({})", name, DecoderFamily::of(name).as_str()),
+        None => "unknown".to_string(),
+    };
+    let line = |s: String| text(s).size(12).color(Color::WHITE);
+
+    let panel = container(
+        column![
+            text(&vid.filename).size(13).color(Color::WHITE),
+            line(format!("Source: {}x{}", source_w, source_h)),
+            line(format!("Decode: {}", decode)),
+            line(format!(
+                "FPS: {:.2} native, {:.1} delivered",
+                vid.native_fps, vid.measured_fps
+            )),
+            line(format!(
+                "Duration: {}:{:02}",
+                vid.duration as u64 / 60,
+                vid.duration as u64 % 60
+            )),
+            line(format!("Decoder: {}", decoder)),
+            build_chapter_list(vid, app.settings.timecode),
+        ]
+        .spacing(2),
+    )
+    .padding(8)
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.7).into()),
+        ..Default::default()
+    });
+
+    // Sit below the overlay's top bar
+    container(panel)
+        .padding(iced::Padding {
+            top: 50.0,
+            left: 10.0,
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Chapters of a video in the info panel, each seeking to its start when clicked.
+/// Long lists show the chapters around the current one.
+fn build_chapter_list<'a>(vid: &'a VideoInstance, timecode: bool) -> Element<'a, Message> {
+    if vid.chapters.is_empty() {
+        return column![].into();
+    }
+    let current = chapters::index_at(&vid.chapters, vid.position);
+    let count = vid.chapters.len();
+    let first = current
+        .unwrap_or(0)
+        .saturating_sub(MAX_LISTED_CHAPTERS / 2)
+        .min(count.saturating_sub(MAX_LISTED_CHAPTERS));
+    let last = (first + MAX_LISTED_CHAPTERS).min(count);
+    let more = |n: usize, which: &str| text(format!("  {} {} ...", n, which)).size(12);
+
+    let mut list = column![text("Chapters:").size(12).color(Color::WHITE)];
+    if first > 0 {
+        list = list.push(more(first, "earlier").color(Color::WHITE));
+    }
+    for (i, chapter) in vid.chapters.iter().enumerate().take(last).skip(first) {
+        let color = if current == Some(i) {
+            SELECTION_COLOR
+        } else {
+            Color::WHITE
+        };
+        let label = format!(
+            "{}  {}",
+            time_label(vid, chapter.start, timecode),
+            chapter.title
+        );
+        list = list.push(
+            button(text(label).size(12).color(color))
+                .on_press(Message::JumpToChapter(vid.id, i))
+                .padding([1, 4]),
+        );
+    }
+    if last < count {
+        list = list.push(more(count - last, "later").color(Color::WHITE));
+    }
+    list.into()
+}
+
+/// Color adjustment sliders and LUT controls of a video, below the top bar on the right.
+fn build_color_panel<'a>(app: &'a App, vid: &'a VideoInstance) -> Element<'a, Message> {
+    let id = vid.id;
+    let adjust = vid.color;
+    let label = |s: String| text(s).size(12).color(Color::WHITE);
+    let control = |name: &'static str,
+                   range: std::ops::RangeInclusive<f32>,
+                   value: f32,
+                   set: fn(ColorAdjust, f32) -> ColorAdjust| {
+        row![
+            label(format!("{} {:.2}", name, value)).width(Length::Fixed(110.0)),
+            slider(range, value, move |v| Message::AdjustColor(
+                id,
+                set(adjust, v)
+            ))
+            .step(0.01)
+            .on_release(Message::CommitColor(id))
+            .width(Length::Fixed(140.0)),
+        ]
+        .spacing(5)
+        .align_y(alignment::Vertical::Center)
+    };
+    let file_name = |path: &std::path::Path| {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    };
+    let lut = match (&vid.lut, &app.settings.lut) {
+        (Some(own), _) => file_name(own),
+        (None, Some(all)) => format!("{} (all cells)", file_name(all)),
+        (None, None) => "none".to_string(),
+    };
+    let small_button =
+        |s: &'static str, message: Message| button(text(s).size(12)).on_press(message).padding(4);
+
+    let panel = container(
+        column![
+            row![
+                label(if app.color_bypass {
+                    "Color (bypassed)".to_string()
+                } else {
+                    "Color".to_string()
+                }),
+                container("").width(Length::Fill),
+                small_button("reset", Message::ResetColor(id)),
+            ]
+            .width(Length::Fixed(255.0))
+            .align_y(alignment::Vertical::Center),
+            control(
+                "Brightness",
+                ColorAdjust::BRIGHTNESS_RANGE,
+                adjust.brightness,
+                |a, v| ColorAdjust { brightness: v, ..a },
+            ),
+            control(
+                "Contrast",
+                ColorAdjust::CONTRAST_RANGE,
+                adjust.contrast,
+                |a, v| ColorAdjust { contrast: v, ..a },
+            ),
+            control(
+                "Saturation",
+                ColorAdjust::SATURATION_RANGE,
+                adjust.saturation,
+                |a, v| ColorAdjust { saturation: v, ..a },
+            ),
+            control("Gamma", ColorAdjust::GAMMA_RANGE, adjust.gamma, |a, v| {
+                ColorAdjust { gamma: v, ..a }
+            },),
+            label(format!("LUT: {}", lut)),
+            row![
+                small_button("load", Message::LoadLut(Some(id))),
+                small_button("load for all", Message::LoadLut(None)),
+                small_button("clear", Message::ClearLut(id)),
+            ]
+            .spacing(5),
+        ]
+        .spacing(4),
+    )
+    .padding(8)
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.7).into()),
+        ..Default::default()
+    });
+
+    // Sit below the overlay's top bar
+    container(panel)
+        .padding(iced::Padding {
+            top: 50.0,
+            right: 10.0,
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(alignment::Horizontal::Right)
+        .into()
+}
+
+/// Media drawn at the size and offset `layout` gives for the cell (zoom and pan, or
+/// 1:1 pixels), clipped to the cell. `content` builds the media at that size.
+fn placed_content<'a>(
+    layout: impl Fn(iced::Size) -> (iced::Size, iced::Point) + 'a,
+    content: impl Fn(iced::Size) -> Element<'a, Message> + 'a,
+) -> Element<'a, Message> {
+    responsive(move |cell| {
+        let (size, offset) = layout(cell);
+        viewport(content(size), size, offset).into()
+    })
+    .into()
+}
+
+/// Fit mode button cycling the cell's fit.
+fn build_fit_button<'a>(id: usize, fit: FitMode, text_color: Color) -> Element<'a, Message> {
+    button(text(fit.as_str()).size(12).color(text_color))
+        .on_press(Message::CycleFit(id))
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// Letterbox state of a video; toggles between cropping and showing detected bars.
+fn build_letterbox_button<'a>(vid: &VideoInstance, text_color: Color) -> Element<'a, Message> {
+    let label = match vid.letterbox {
+        Letterbox::Unknown => "bars: ...",
+        Letterbox::NoBars => "bars: none",
+        Letterbox::Bars(_) if vid.keep_bars => "bars: show",
+        Letterbox::Bars(_) => "bars: crop",
+    };
+    button(text(label).size(12).color(text_color))
+        .on_press_maybe(
+            matches!(vid.letterbox, Letterbox::Bars(_)).then_some(Message::ToggleLetterbox(vid.id)),
+        )
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// Opens and closes a video's color panel.
+fn build_color_button<'a>(id: usize, text_color: Color) -> Element<'a, Message> {
+    button(text("color").size(12).color(text_color))
+        .on_press(Message::ToggleColorPanel(id))
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// Fit mode an item is drawn with: its own, or the global default.
+fn effective_fit(app: &App, item_fit: Option<FitMode>) -> FitMode {
+    item_fit.unwrap_or(app.settings.fit_mode)
+}
+
+/// Crop rectangle being dragged over a cell, or a hint until the drag starts.
+fn build_crop_overlay<'a>(draw: &CropDraw) -> Element<'a, Message> {
+    let Some(start) = draw.start else {
+        return center(text("Drag to crop").size(14).color(SELECTION_COLOR)).into();
+    };
+    let rect = container("")
+        .width(Length::Fixed((draw.current.x - start.x).abs()))
+        .height(Length::Fixed((draw.current.y - start.y).abs()))
+        .style(|_theme: &Theme| container::Style {
+            border: iced::Border {
+                color: SELECTION_COLOR,
+                width: 2.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        });
+    pin(rect)
+        .x(start.x.min(draw.current.x))
+        .y(start.y.min(draw.current.y))
+        .into()
+}
+
+/// Crop overlay for a cell, if a crop is being drawn over it.
+fn crop_overlay_for<'a>(app: &App, id: usize) -> Option<Element<'a, Message>> {
+    app.crop_draw
+        .as_ref()
+        .filter(|d| d.id == id)
+        .map(build_crop_overlay)
+}
+
+/// A cell's latest scope, in the bottom-right corner above the overlay controls.
+fn scope_overlay<'a>(view: Option<&ScopeView>) -> Option<Element<'a, Message>> {
+    let view = view?;
+    let handle = view.image.clone()?;
+    // Shown 128 logical pixels high; the vectorscope's extra resolution sharpens it
+    let (width, height) = view.scope.image_size();
+    let panel = container(
+        image(handle)
+            .width(Length::Fixed(width as f32 * 128.0 / height.max(1) as f32))
+            .height(Length::Fixed(128.0)),
+    )
+    .padding(4)
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.6).into()),
+        ..Default::default()
+    });
+    Some(
+        container(panel)
+            .padding(iced::Padding {
+                bottom: 70.0,
+                right: 10.0,
+                ..Default::default()
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(alignment::Horizontal::Right)
+            .align_y(alignment::Vertical::Bottom)
+            .into(),
+    )
+}
+
+/// Cycles a cell's scope, labelled with the one shown.
+fn build_scope_button<'a>(
+    id: usize,
+    view: Option<&ScopeView>,
+    text_color: Color,
+) -> Element<'a, Message> {
+    button(
+        text(view.map_or("scope", |v| v.scope.as_str()))
+            .size(12)
+            .color(text_color),
+    )
+    .on_press(Message::CycleScope(id))
+    .padding(8)
+    .width(Length::Shrink)
+    .height(Length::Shrink)
+    .into()
+}
+
+/// Toggles a cell's zebras and false color, highlighted while on.
+fn build_aid_buttons<'a>(id: usize, aids: ExposureAids, text_color: Color) -> Element<'a, Message> {
+    let label = |name: &'static str, on: bool| {
+        text(name)
+            .size(12)
+            .color(if on { SELECTION_COLOR } else { text_color })
+    };
+    row![
+        button(label("zebra", aids.zebras))
+            .on_press(Message::ToggleZebras(id))
+            .padding(8)
+            .width(Length::Shrink)
+            .height(Length::Shrink),
+        button(label("false", aids.false_color))
+            .on_press(Message::ToggleFalseColor(id))
+            .padding(8)
+            .width(Length::Shrink)
+            .height(Length::Shrink),
+    ]
+    .spacing(5)
+    .into()
+}
+
+/// Track picker of a video: its video and audio tracks, and every subtitle source,
+/// with the ones playing highlighted. Sits below the overlay's top bar, centered.
+fn build_track_panel<'a>(vid: &VideoInstance, groups: &[TrackGroup]) -> Element<'a, Message> {
+    let id = vid.id;
+    let entry = |label: String, selected: bool, message: Message| {
+        button(
+            text(label)
+                .size(12)
+                .shaping(Shaping::Advanced)
+                .color(if selected {
+                    SELECTION_COLOR
+                } else {
+                    Color::WHITE
+                }),
+        )
+        .on_press(message)
+        .padding(4)
+    };
+    let heading = |s: &'static str| text(s).size(13).color(Color::WHITE);
+
+    let mut list = column![].spacing(3);
+    for group in groups.iter().filter(|g| g.kind != TrackKind::Text) {
+        list = list.push(heading(group.kind.title()));
+        for (index, label) in (0..).zip(&group.labels) {
+            list = list.push(entry(
+                label.clone(),
+                index == group.current,
+                Message::SelectTrack(id, group.kind, index),
+            ));
+        }
+    }
+
+    // Subtitles choose between sidecars and embedded tracks, as the subs button does
+    let embedded = groups
+        .iter()
+        .find(|g| g.kind == TrackKind::Text)
+        .map_or(&[][..], |g| &g.labels[..]);
+    if !vid.sidecars.is_empty() || !embedded.is_empty() {
+        list = list.push(heading(TrackKind::Text.title()));
+        let mut sources = vec![("off".to_string(), SubtitleSource::Off)];
+        for (index, sidecar) in vid.sidecars.iter().enumerate() {
+            sources.push((sidecar.name.clone(), SubtitleSource::Sidecar(index)));
+        }
+        for (index, label) in (0..).zip(embedded) {
+            sources.push((label.clone(), SubtitleSource::Embedded(index)));
+        }
+        for (label, source) in sources {
+            list = list.push(entry(
+                label,
+                source == vid.subtitles,
+                Message::SetSubtitles(id, source),
+            ));
+        }
+    }
+
+    let panel = container(list)
+        .padding(8)
+        .style(|_theme: &Theme| container::Style {
+            background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.7).into()),
+            ..Default::default()
+        });
+    container(panel)
+        .padding(iced::Padding {
+            top: 50.0,
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(alignment::Horizontal::Center)
+        .into()
+}
+
+/// Opens and closes a video's track picker.
+fn build_tracks_button<'a>(id: usize, text_color: Color) -> Element<'a, Message> {
+    button(text("tracks").size(12).color(text_color))
+        .on_press(Message::ToggleTracks(id))
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// Cycles a video's subtitle source, labelled with the one shown.
+fn build_subtitle_button<'a>(vid: &VideoInstance, text_color: Color) -> Element<'a, Message> {
+    let label = match vid.subtitles {
+        SubtitleSource::Off => "subs".to_string(),
+        _ => format!("subs: {}", vid.subtitle_label()),
+    };
+    button(text(label).size(12).color(text_color))
+        .on_press(Message::CycleSubtitles(vid.id))
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// A video's current subtitle, centered above the overlay controls.
+fn subtitle_overlay<'a>(vid: &VideoInstance) -> Option<Element<'a, Message>> {
+    let line = vid.subtitle_text()?;
+    let panel = container(
+        text(line)
+            .size(16)
+            .color(Color::WHITE)
+            .shaping(Shaping::Advanced)
+            .align_x(alignment::Horizontal::Center),
+    )
+    .padding([4, 8])
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.6).into()),
+        ..Default::default()
+    });
+    Some(
+        container(panel)
+            .padding(iced::Padding {
+                bottom: 70.0,
+                ..Default::default()
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(alignment::Horizontal::Center)
+            .align_y(alignment::Vertical::Bottom)
+            .into(),
+    )
+}
+
+/// Shows and hides a cell's caption, highlighted while shown.
+fn build_caption_button<'a>(id: usize, shown: bool, text_color: Color) -> Element<'a, Message> {
+    button(
+        text("caption")
+            .size(12)
+            .color(if shown { SELECTION_COLOR } else { text_color }),
+    )
+    .on_press(Message::ToggleCaption(id))
+    .padding(8)
+    .width(Length::Shrink)
+    .height(Length::Shrink)
+    .into()
+}
+
+/// Exports the frame a video is showing, with its caption if shown.
+fn build_still_button<'a>(id: usize, text_color: Color) -> Element<'a, Message> {
+    button(text("still").size(12).color(text_color))
+        .on_press(Message::ExportStill(id))
+        .padding(8)
+        .width(Length::Shrink)
+        .height(Length::Shrink)
+        .into()
+}
+
+/// A cell's caption, in the corner and at the size the settings give.
+fn caption_overlay<'a>(app: &App, lines: Vec<String>) -> Element<'a, Message> {
+    let position = app.settings.caption_position;
+    let size = app.settings.caption_size.text_size();
+    let panel = container(
+        column(lines.into_iter().map(|line| {
+            text(line)
+                .size(size)
+                .color(Color::WHITE)
+                .shaping(Shaping::Advanced)
+                .into()
+        }))
+        .spacing(1),
+    )
+    .padding(6)
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.6).into()),
+        ..Default::default()
+    });
+    container(panel)
+        .padding(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(if position.is_left() {
+            alignment::Horizontal::Left
+        } else {
+            alignment::Horizontal::Right
+        })
+        .align_y(if position.is_top() {
+            alignment::Vertical::Top
+        } else {
+            alignment::Vertical::Bottom
+        })
+        .into()
+}
+
+/// Caption of a video at its current position.
+fn video_caption<'a>(app: &App, vid: &VideoInstance) -> Element<'a, Message> {
+    caption_overlay(app, caption::video_lines(vid))
+}
+
+/// Zoom level button that resets to fit; empty while not zoomed.
+fn build_zoom_button<'a>(id: usize, view: ZoomView, text_color: Color) -> Element<'a, Message> {
+    if !view.is_zoomed() {
+        return row![].into();
+    }
+    button(
+        text(format!("{:.1}x", view.zoom))
+            .size(12)
+            .color(text_color),
+    )
+    .on_press(Message::ResetZoom(id))
+    .padding(8)
+    .width(Length::Shrink)
+    .height(Length::Shrink)
+    .into()
+}
+
+/// Video player for a cell at its fit mode, or zoomed and panned when the view is
+/// zoomed (zoom always starts from the contained size).
+fn video_view<'a>(vid: &'a VideoInstance, fit: FitMode, scale_factor: f32) -> Element<'a, Message> {
+    let (view, source) = (vid.view, vid.source_size);
+    let sized = move |size: iced::Size| -> Element<'a, Message> {
+        VideoPlayer::new(&vid.video)
+            .content_fit(iced::ContentFit::Fill)
+            .width(Length::Fixed(size.width))
+            .height(Length::Fixed(size.height))
+            .into()
+    };
+    if view.is_zoomed() {
+        placed_content(move |cell| view.layout(cell, source), sized)
+    } else if fit == FitMode::Native {
+        placed_content(move |cell| native_layout(cell, source, scale_factor), sized)
+    } else {
+        VideoPlayer::new(&vid.video)
+            .content_fit(fit.content_fit())
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+/// Compare label of a cell in the selection: A for the first pick, B for the second.
+fn selection_label(app: &App, id: usize) -> Option<&'static str> {
+    match app.selection.iter().position(|&s| s == id)? {
+        0 => Some("A"),
+        _ => Some("B"),
+    }
+}
+
+/// Wrap a media cell with drag visual feedback (dim source, insertion bar on target)
+/// and the compare selection outline.
 fn wrap_drag_cell<'a>(
     cell: Element<'a, Message>,
     is_drag_source: bool,
     drag_target_info: Option<(usize, bool)>,
+    selection: Option<&'static str>,
 ) -> Element<'a, Message> {
-    if !is_drag_source && drag_target_info.is_none() {
+    if !is_drag_source && drag_target_info.is_none() && selection.is_none() {
         return container(cell)
             .width(Length::Fill)
             .height(Length::Fill)
@@ -89,6 +892,32 @@ fn wrap_drag_cell<'a>(
         layers = layers.push(container(bar_row).width(Length::Fill).height(Length::Fill));
     }
 
+    // Outline and A/B badge for cells selected for comparison
+    if let Some(label) = selection {
+        let badge = container(text(label).size(14).color(Color::BLACK))
+            .padding([2, 8])
+            .style(|_theme: &Theme| container::Style {
+                background: Some(SELECTION_COLOR.into()),
+                ..Default::default()
+            });
+        layers = layers.push(
+            container(badge)
+                .padding(8)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(alignment::Horizontal::Right)
+                .align_y(alignment::Vertical::Center)
+                .style(|_theme: &Theme| container::Style {
+                    border: iced::Border {
+                        color: SELECTION_COLOR,
+                        width: 3.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }),
+        );
+    }
+
     container(layers)
         .width(Length::Fill)
         .height(Length::Fill)
@@ -100,15 +929,62 @@ pub fn create_media_cell<'a>(app: &'a App, item: &'a MediaItem) -> Element<'a, M
     match item {
         MediaItem::Video(vid) => create_video_cell(app, vid),
         MediaItem::Photo(photo) => create_photo_cell(app, photo),
+        MediaItem::Pending(pending) => create_pending_cell(app, pending),
     }
 }
 
+/// Create a cell for an item whose video or photo is still loading,
+/// showing its cached thumbnail when there is one.
+fn create_pending_cell<'a>(app: &'a App, pending: &'a PendingMedia) -> Element<'a, Message> {
+    let label = center(
+        column![
+            text(&pending.filename).size(14),
+            match &pending.error {
+                Some(e) => text(format!("Failed to load: {}", e))
+                    .size(12)
+                    .color(Color::from_rgb(1.0, 0.5, 0.5)),
+                None => text("Loading...")
+                    .size(12)
+                    .color(Color::from_rgb(0.6, 0.8, 1.0)),
+            },
+        ]
+        .spacing(5)
+        .align_x(alignment::Horizontal::Center),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    let content: Element<'a, Message> = match &pending.thumbnail {
+        Some(thumbnail) => stack![
+            image(thumbnail)
+                .content_fit(iced::ContentFit::Contain)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            label,
+        ]
+        .into(),
+        None => label.into(),
+    };
+
+    let is_drag_source = app.drag_source_id == Some(pending.id);
+    let drag_target_info = app.drag_target.filter(|(tid, _)| *tid == pending.id);
+
+    let cell = mouse_area(content)
+        .on_press(Message::DragStart(pending.id))
+        .on_right_press(Message::ToggleSelect(pending.id))
+        .on_move(move |point| Message::MouseMoved(pending.id, point));
+
+    let selection = selection_label(app, pending.id);
+    wrap_drag_cell(cell.into(), is_drag_source, drag_target_info, selection)
+}
+
 /// Create a video cell with player and overlay controls.
 pub fn create_video_cell<'a>(app: &'a App, vid: &'a VideoInstance) -> Element<'a, Message> {
+    let fit = effective_fit(app, vid.fit);
     let video_player = container(
-        VideoPlayer::new(&vid.video).on_end_of_stream(Message::EndOfStream(vid.id)),
-        // Removed on_new_frame to prevent layout invalidation warnings
-        // FPS is now tracked via UiFadeTick subscription instead
+        video_view(vid, fit, app.scale_factor),
+        // Removed on_new_frame to prevent layout invalidation warnings.
+        // FPS and end of stream are pushed from appsink probes instead (see loader)
     )
     .width(Length::Fill)
     .height(Length::Fill)
@@ -117,11 +993,51 @@ pub fn create_video_cell<'a>(app: &'a App, vid: &'a VideoInstance) -> Element<'a
 
     let mut stack_content = stack![video_player];
 
-    // Add overlay controls when hovered, with fade based on mouse activity
-    if vid.hovered {
-        let opacity = compute_ui_opacity(vid.last_mouse_activity);
+    if vid.show_info {
+        stack_content = stack_content.push(build_video_info_panel(vid));
+    }
+    if vid.show_color {
+        stack_content = stack_content.push(build_color_panel(app, vid));
+    }
+    if let Some(groups) = &vid.tracks {
+        stack_content = stack_content.push(build_track_panel(vid, groups));
+    }
+    if let Some(scope) = scope_overlay(vid.scope.as_ref()) {
+        stack_content = stack_content.push(scope);
+    }
+    if let Some(subtitle) = subtitle_overlay(vid) {
+        stack_content = stack_content.push(subtitle);
+    }
+    if vid.show_caption {
+        stack_content = stack_content.push(video_caption(app, vid));
+    }
+    if let Some(crop) = crop_overlay_for(app, vid.id) {
+        stack_content = stack_content.push(crop);
+    }
+
+    // Add overlay controls when hovered, with fade based on mouse activity.
+    // Keep them fully visible while a marker note or timecode is being typed.
+    let marker_edit = app.marker_edit.as_ref().filter(|e| e.video_id == vid.id);
+    let timecode_entry = app
+        .timecode_entry
+        .as_ref()
+        .filter(|e| e.video_id == Some(vid.id));
+    let typing = marker_edit.is_some() || timecode_entry.is_some();
+    if vid.hovered || typing {
+        let opacity = if typing {
+            1.0
+        } else {
+            compute_ui_opacity(vid.last_mouse_activity)
+        };
         if opacity > 0.0 {
-            let overlay = build_video_overlay(vid, opacity);
+            let overlay = build_video_overlay(
+                vid,
+                fit,
+                opacity,
+                marker_edit,
+                timecode_entry,
+                app.settings.timecode,
+            );
             stack_content = stack_content.push(overlay);
         }
     }
@@ -134,26 +1050,96 @@ pub fn create_video_cell<'a>(app: &'a App, vid: &'a VideoInstance) -> Element<'a
         .on_enter(Message::MediaHoverChanged(vid.id, true))
         .on_exit(Message::MediaHoverChanged(vid.id, false))
         .on_press(Message::DragStart(vid.id))
-        .on_move(move |point| Message::MouseMoved(vid.id, point));
+        .on_right_press(Message::ToggleSelect(vid.id))
+        .on_move(move |point| Message::MouseMoved(vid.id, point))
+        .on_scroll(move |delta| Message::ZoomScroll(vid.id, delta));
 
-    wrap_drag_cell(cell.into(), is_drag_source, drag_target_info)
+    let selection = selection_label(app, vid.id);
+    wrap_drag_cell(cell.into(), is_drag_source, drag_target_info, selection)
+}
+
+/// Photo pixels, or a placeholder while an evicted photo is decoded again.
+/// Zoomed photos are drawn at their zoom and pan instead of fitted, and 1:1 photos
+/// at their original pixel size.
+fn photo_image<'a>(
+    photo: &PhotoInstance,
+    handle: Option<&Handle>,
+    size: Length,
+    fit: FitMode,
+    scale_factor: f32,
+) -> Element<'a, Message> {
+    let Some(handle) = handle.cloned() else {
+        return match &photo.reload_error {
+            Some(e) => text(format!("Failed to load: {}", e))
+                .size(14)
+                .color(Color::from_rgb(1.0, 0.5, 0.5))
+                .into(),
+            None => text("Loading...").size(14).into(),
+        };
+    };
+    let (view, display_size, full_size) = (photo.view, photo.size, photo.full_size);
+    let sized = |handle: Handle| {
+        move |size: iced::Size| -> Element<'a, Message> {
+            image(handle.clone())
+                .content_fit(iced::ContentFit::Fill)
+                .width(Length::Fixed(size.width))
+                .height(Length::Fixed(size.height))
+                .into()
+        }
+    };
+    match fit {
+        _ if view.is_zoomed() => {
+            placed_content(move |cell| view.layout(cell, display_size), sized(handle))
+        }
+        FitMode::Native => placed_content(
+            move |cell| native_layout(cell, full_size, scale_factor),
+            sized(handle),
+        ),
+        FitMode::Contain => image(handle)
+            .content_fit(iced::ContentFit::Contain)
+            .width(size)
+            .height(size)
+            .into(),
+        FitMode::Cover | FitMode::Fill => image(handle)
+            .content_fit(fit.content_fit())
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+    }
 }
 
 /// Create a photo cell with image and overlay controls.
 pub fn create_photo_cell<'a>(app: &'a App, photo: &'a PhotoInstance) -> Element<'a, Message> {
-    let photo_view = container(image(&photo.handle).content_fit(iced::ContentFit::Contain))
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .center_x(Length::Fill)
-        .center_y(Length::Fill);
+    // Full resolution is only held while zoomed or shown 1:1
+    let fit = effective_fit(app, photo.fit);
+    let photo_view = container(photo_image(
+        photo,
+        photo.display_handle(),
+        Length::Shrink,
+        fit,
+        app.scale_factor,
+    ))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x(Length::Fill)
+    .center_y(Length::Fill);
 
     let mut stack_content = stack![photo_view];
+    if let Some(scope) = scope_overlay(photo.scope.as_ref()) {
+        stack_content = stack_content.push(scope);
+    }
+    if photo.show_caption {
+        stack_content = stack_content.push(caption_overlay(app, caption::photo_lines(photo)));
+    }
+    if let Some(crop) = crop_overlay_for(app, photo.id) {
+        stack_content = stack_content.push(crop);
+    }
 
     // Add overlay controls when hovered, with fade based on mouse activity
     if photo.hovered {
         let opacity = compute_ui_opacity(photo.last_mouse_activity);
         if opacity > 0.0 {
-            let overlay = build_photo_overlay(photo, opacity);
+            let overlay = build_photo_overlay(photo, fit, opacity);
             stack_content = stack_content.push(overlay);
         }
     }
@@ -165,13 +1151,70 @@ pub fn create_photo_cell<'a>(app: &'a App, photo: &'a PhotoInstance) -> Element<
         .on_enter(Message::MediaHoverChanged(photo.id, true))
         .on_exit(Message::MediaHoverChanged(photo.id, false))
         .on_press(Message::DragStart(photo.id))
-        .on_move(move |point| Message::MouseMoved(photo.id, point));
+        .on_right_press(Message::ToggleSelect(photo.id))
+        .on_move(move |point| Message::MouseMoved(photo.id, point))
+        .on_scroll(move |delta| Message::ZoomScroll(photo.id, delta));
+
+    let selection = selection_label(app, photo.id);
+    wrap_drag_cell(cell.into(), is_drag_source, drag_target_info, selection)
+}
 
-    wrap_drag_cell(cell.into(), is_drag_source, drag_target_info)
+/// Bottom bar content of a photo: its buttons, and for an animated photo a seek slider
+/// above them and play, loop and position controls like a video's.
+fn photo_controls<'a>(
+    photo: &PhotoInstance,
+    buttons: Row<'a, Message>,
+    text_color: Color,
+) -> Element<'a, Message> {
+    let Some(animation) = &photo.animation else {
+        return container(buttons).padding(10).into();
+    };
+    let id = photo.id;
+    let small_button = |label: &'static str, message: Message| {
+        button(text(label).size(12).color(text_color))
+            .on_press(message)
+            .padding(8)
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+    };
+    column![
+        slider(0.0..=animation.duration(), animation.position, move |pos| {
+            Message::Seek(id, pos)
+        })
+        .step(0.01)
+        .on_release(Message::SeekRelease(id)),
+        row![
+            small_button(
+                if animation.paused { ">" } else { "||" },
+                Message::TogglePause(id)
+            ),
+            small_button(
+                if animation.looping { "↻" } else { "→" },
+                Message::ToggleLoop(id)
+            ),
+            buttons,
+            text(format!(
+                "{:.1}s / {:.1}s",
+                animation.position,
+                animation.duration()
+            ))
+            .size(12)
+            .color(text_color),
+        ]
+        .spacing(5)
+        .align_y(alignment::Vertical::Center),
+    ]
+    .spacing(5)
+    .padding(10)
+    .into()
 }
 
 /// Build the overlay controls for a photo.
-fn build_photo_overlay<'a>(photo: &'a PhotoInstance, opacity: f32) -> Element<'a, Message> {
+fn build_photo_overlay<'a>(
+    photo: &'a PhotoInstance,
+    fit: FitMode,
+    opacity: f32,
+) -> Element<'a, Message> {
     let bg_alpha = 0.7 * opacity;
     let text_color = Color::from_rgba(1.0, 1.0, 1.0, opacity);
 
@@ -193,7 +1236,8 @@ fn build_photo_overlay<'a>(photo: &'a PhotoInstance, opacity: f32) -> Element<'a
     })
     .width(Length::Fill);
 
-    let bottom_bar = container(
+    let bottom_bar = container(photo_controls(
+        photo,
         row![
             button(
                 text(if photo.fullscreen { "V" } else { "F" })
@@ -204,11 +1248,16 @@ fn build_photo_overlay<'a>(photo: &'a PhotoInstance, opacity: f32) -> Element<'a
             .padding(8)
             .width(Length::Shrink)
             .height(Length::Shrink),
+            build_zoom_button(photo.id, photo.view, text_color),
+            build_fit_button(photo.id, fit, text_color),
+            build_scope_button(photo.id, photo.scope.as_ref(), text_color),
+            build_aid_buttons(photo.id, photo.aids, text_color),
+            build_caption_button(photo.id, photo.show_caption, text_color),
         ]
         .spacing(5)
-        .align_y(alignment::Vertical::Center)
-        .padding(10),
-    )
+        .align_y(alignment::Vertical::Center),
+        text_color,
+    ))
     .style(move |_theme: &Theme| container::Style {
         background: Some(Color::from_rgba(0.0, 0.0, 0.0, bg_alpha).into()),
         ..Default::default()
@@ -228,21 +1277,20 @@ fn build_photo_overlay<'a>(photo: &'a PhotoInstance, opacity: f32) -> Element<'a
 }
 
 /// Build the overlay controls for a video.
-fn build_video_overlay<'a>(vid: &'a VideoInstance, opacity: f32) -> Element<'a, Message> {
+fn build_video_overlay<'a>(
+    vid: &'a VideoInstance,
+    fit: FitMode,
+    opacity: f32,
+    marker_edit: Option<&'a MarkerEdit>,
+    timecode_entry: Option<&'a TimecodeEntry>,
+    show_timecode: bool,
+) -> Element<'a, Message> {
     let bg_alpha = 0.7 * opacity;
     let text_color = Color::from_rgba(1.0, 1.0, 1.0, opacity);
 
     let top_bar = container(
         row![
-            {
-                // Display native FPS instead of calculated to avoid excessive state updates
-                let fps_text = get_fps_display(vid.native_fps);
-                let fps_color = Color::from_rgba(0.0, 1.0, 0.0, opacity); // Green for native FPS
-                text(fps_text)
-                    .size(14)
-                    .shaping(Shaping::Basic)
-                    .color(fps_color)
-            },
+            build_fps_text(vid, opacity),
             container("").width(Length::Fill),
             button(text("X").size(20).color(text_color))
                 .on_press(Message::RemoveMedia(vid.id))
@@ -258,66 +1306,112 @@ fn build_video_overlay<'a>(vid: &'a VideoInstance, opacity: f32) -> Element<'a,
     })
     .width(Length::Fill);
 
+    let mut bottom_column = column![];
+    if let Some(edit) = marker_edit {
+        bottom_column = bottom_column.push(build_marker_note_input(edit));
+    }
+    if let Some(entry) = timecode_entry {
+        bottom_column = bottom_column.push(build_timecode_input(entry));
+    }
+    if !vid.chapters.is_empty() {
+        bottom_column = bottom_column.push(build_chapter_segments(
+            &vid.chapters,
+            vid.duration,
+            vid.position,
+            opacity,
+        ));
+    }
+    if !vid.markers.is_empty() {
+        bottom_column = bottom_column.push(build_marker_ticks(&vid.markers, vid.duration, opacity));
+    }
+
     let bottom_bar = container(
-        column![
+        bottom_column
             // Seek slider (use cached duration to avoid blocking GStreamer queries)
-            slider(0.0..=vid.duration, vid.position, move |pos| {
-                Message::Seek(vid.id, pos)
-            })
-            .step(0.1)
-            .on_release(Message::SeekRelease(vid.id)),
-            // Control buttons (use cached state to avoid blocking GStreamer queries)
-            row![
-                button(
-                    text(if vid.video.paused() { ">" } else { "||" })
-                        .size(12)
-                        .color(text_color)
-                )
-                .on_press(Message::TogglePause(vid.id))
-                .padding(8)
-                .width(Length::Shrink)
-                .height(Length::Shrink),
-                button(
-                    text(if vid.video.looping() { "↻" } else { "→" })
-                        .size(12)
-                        .color(text_color)
-                )
-                .on_press(Message::ToggleLoop(vid.id))
-                .padding(8)
-                .width(Length::Shrink)
-                .height(Length::Shrink),
-                button(
-                    text(if vid.video.muted() { "M" } else { "~" })
-                        .size(12)
-                        .color(text_color)
-                )
-                .on_press(Message::ToggleMute(vid.id))
-                .padding(8)
-                .width(Length::Shrink)
-                .height(Length::Shrink),
-                button(
-                    text(if vid.fullscreen { "V" } else { "F" })
-                        .size(12)
-                        .color(text_color)
-                )
-                .on_press(Message::ToggleFullscreen(vid.id))
-                .padding(8)
-                .width(Length::Shrink)
-                .height(Length::Shrink),
-                text(format!(
-                    "{}:{:02}",
-                    vid.position as u64 / 60,
-                    vid.position as u64 % 60
-                ))
-                .size(12)
-                .color(text_color)
-            ]
+            .push(
+                slider(0.0..=vid.duration, vid.position, move |pos| {
+                    Message::Seek(vid.id, pos)
+                })
+                .step(0.1)
+                .on_release(Message::SeekRelease(vid.id)),
+            )
+            // Control buttons (use cached state to avoid blocking GStreamer queries)
+            .push(
+                row![
+                    button(
+                        text(if vid.video.paused() { ">" } else { "||" })
+                            .size(12)
+                            .color(text_color)
+                    )
+                    .on_press(Message::TogglePause(vid.id))
+                    .padding(8)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+                    button(
+                        text(if vid.video.looping() { "↻" } else { "→" })
+                            .size(12)
+                            .color(text_color)
+                    )
+                    .on_press(Message::ToggleLoop(vid.id))
+                    .padding(8)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+                    button(
+                        text(if vid.video.muted() { "M" } else { "~" })
+                            .size(12)
+                            .color(text_color)
+                    )
+                    .on_press(Message::ToggleMute(vid.id))
+                    .padding(8)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+                    button(
+                        text(if vid.fullscreen { "V" } else { "F" })
+                            .size(12)
+                            .color(text_color)
+                    )
+                    .on_press(Message::ToggleFullscreen(vid.id))
+                    .padding(8)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+                    button(text("i").size(12).color(text_color))
+                        .on_press(Message::ToggleInfo(vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                    build_zoom_button(vid.id, vid.view, text_color),
+                    build_fit_button(vid.id, fit, text_color),
+                    build_letterbox_button(vid, text_color),
+                    build_color_button(vid.id, text_color),
+                    build_scope_button(vid.id, vid.scope.as_ref(), text_color),
+                    build_aid_buttons(vid.id, vid.aids, text_color),
+                    build_caption_button(vid.id, vid.show_caption, text_color),
+                    build_still_button(vid.id, text_color),
+                    build_subtitle_button(vid, text_color),
+                    build_tracks_button(vid.id, text_color),
+                    button(text("◆").size(12).color(text_color))
+                        .on_press(Message::AddMarker(vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                    button(text("<◆").size(12).color(text_color))
+                        .on_press(Message::PrevMarker(vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                    button(text("◆>").size(12).color(text_color))
+                        .on_press(Message::NextMarker(vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                    build_position_button(vid, show_timecode, text_color),
+                ]
+                .spacing(5)
+                .align_y(alignment::Vertical::Center)
+                .width(Length::Shrink),
+            )
             .spacing(5)
-            .align_y(alignment::Vertical::Center)
-            .width(Length::Shrink)
-        ]
-        .spacing(5)
-        .padding(10),
+            .padding(10),
     )
     .style(move |_theme: &Theme| container::Style {
         background: Some(Color::from_rgba(0.0, 0.0, 0.0, bg_alpha).into()),
@@ -383,20 +1477,26 @@ pub fn render_main_view(app: &App) -> Element<'_, Message> {
         return center(col).width(Length::Fill).height(Length::Fill).into();
     }
 
+    // Compare mode
+    if let Some(compare) = &app.compare {
+        return render_compare_view(app, compare);
+    }
+
     // Fullscreen mode
     if let Some(fullscreen_item) = app.media.iter().find(|m| m.is_fullscreen()) {
         return render_fullscreen_view(app, fullscreen_item);
     }
 
-    // Grid mode - create media cells
-    let grid: Element<'_, Message> = if app.media.len() == 1 {
+    // Grid mode - create cells for the visible page only
+    let page = &app.media[app.page_range()];
+    let grid: Element<'_, Message> = if page.len() == 1 {
         // Single item: full screen
-        create_media_cell(app, &app.media[0])
+        create_media_cell(app, &page[0])
     } else {
         // Multiple items: use custom column count
         let mut rows: Vec<Element<'_, Message>> = Vec::new();
 
-        for chunk in app.media.chunks(app.grid_columns) {
+        for chunk in page.chunks(app.grid_columns) {
             let row_content: Vec<Element<'_, Message>> = chunk
                 .iter()
                 .map(|item| create_media_cell(app, item))
@@ -432,46 +1532,70 @@ fn render_fullscreen_view<'a>(app: &'a App, item: &'a MediaItem) -> Element<'a,
     match item {
         MediaItem::Video(vid) => render_fullscreen_video(app, vid),
         MediaItem::Photo(photo) => render_fullscreen_photo(app, photo),
+        MediaItem::Pending(pending) => create_pending_cell(app, pending),
     }
 }
 
 /// Render the fullscreen view for a single video.
 fn render_fullscreen_video<'a>(
-    _app: &'a App,
+    app: &'a App,
     fullscreen_vid: &'a VideoInstance,
 ) -> Element<'a, Message> {
+    let fit = effective_fit(app, fullscreen_vid.fit);
     let video_player = container(
-        VideoPlayer::new(&fullscreen_vid.video)
-            .on_end_of_stream(Message::EndOfStream(fullscreen_vid.id))
-            // Removed on_new_frame to prevent layout invalidation warnings
-            // FPS is now tracked via UiFadeTick subscription instead
-            .content_fit(iced::ContentFit::Contain)
-            .width(Length::Fill)
-            .height(Length::Fill),
+        video_view(fullscreen_vid, fit, app.scale_factor),
+        // Removed on_new_frame to prevent layout invalidation warnings.
+        // FPS and end of stream are pushed from appsink probes instead (see loader)
     )
     .width(Length::Fill)
     .height(Length::Fill)
     .center_x(Length::Fill)
     .center_y(Length::Fill);
 
-    let opacity = compute_ui_opacity(fullscreen_vid.last_mouse_activity);
+    let marker_edit = app
+        .marker_edit
+        .as_ref()
+        .filter(|e| e.video_id == fullscreen_vid.id);
+    let timecode_entry = app
+        .timecode_entry
+        .as_ref()
+        .filter(|e| e.video_id == Some(fullscreen_vid.id));
+    let opacity = if marker_edit.is_some() || timecode_entry.is_some() {
+        1.0
+    } else {
+        compute_ui_opacity(fullscreen_vid.last_mouse_activity)
+    };
     let mut fullscreen_stack = stack![video_player];
 
+    if fullscreen_vid.show_info {
+        fullscreen_stack = fullscreen_stack.push(build_video_info_panel(fullscreen_vid));
+    }
+    if fullscreen_vid.show_color {
+        fullscreen_stack = fullscreen_stack.push(build_color_panel(app, fullscreen_vid));
+    }
+    if let Some(groups) = &fullscreen_vid.tracks {
+        fullscreen_stack = fullscreen_stack.push(build_track_panel(fullscreen_vid, groups));
+    }
+    if let Some(scope) = scope_overlay(fullscreen_vid.scope.as_ref()) {
+        fullscreen_stack = fullscreen_stack.push(scope);
+    }
+    if let Some(subtitle) = subtitle_overlay(fullscreen_vid) {
+        fullscreen_stack = fullscreen_stack.push(subtitle);
+    }
+    if fullscreen_vid.show_caption {
+        fullscreen_stack = fullscreen_stack.push(video_caption(app, fullscreen_vid));
+    }
+    if let Some(crop) = crop_overlay_for(app, fullscreen_vid.id) {
+        fullscreen_stack = fullscreen_stack.push(crop);
+    }
+
     if opacity > 0.0 {
         let bg_alpha = 0.7 * opacity;
         let text_color = Color::from_rgba(1.0, 1.0, 1.0, opacity);
 
         let top_bar = container(
             row![
-                {
-                    // Display native FPS instead of calculated to avoid excessive state updates
-                    let fps_text = get_fps_display(fullscreen_vid.native_fps);
-                    let fps_color = Color::from_rgba(0.0, 1.0, 0.0, opacity); // Green for native FPS
-                    text(fps_text)
-                        .size(14)
-                        .shaping(Shaping::Basic)
-                        .color(fps_color)
-                },
+                build_fps_text(fullscreen_vid, opacity),
                 container("").width(Length::Fill),
                 button(text("X").size(20).color(text_color))
                     .on_press(Message::ToggleFullscreen(fullscreen_vid.id))
@@ -487,68 +1611,134 @@ fn render_fullscreen_video<'a>(
         })
         .width(Length::Fill);
 
+        let mut bottom_column = column![];
+        if let Some(edit) = marker_edit {
+            bottom_column = bottom_column.push(build_marker_note_input(edit));
+        }
+        if let Some(entry) = timecode_entry {
+            bottom_column = bottom_column.push(build_timecode_input(entry));
+        }
+        if !fullscreen_vid.chapters.is_empty() {
+            bottom_column = bottom_column.push(build_chapter_segments(
+                &fullscreen_vid.chapters,
+                fullscreen_vid.duration,
+                fullscreen_vid.position,
+                opacity,
+            ));
+        }
+        if !fullscreen_vid.markers.is_empty() {
+            bottom_column = bottom_column.push(build_marker_ticks(
+                &fullscreen_vid.markers,
+                fullscreen_vid.duration,
+                opacity,
+            ));
+        }
+
         let bottom_bar = container(
-            column![
+            bottom_column
                 // Seek slider (use cached duration to avoid blocking GStreamer queries)
-                slider(
-                    0.0..=fullscreen_vid.duration,
-                    fullscreen_vid.position,
-                    move |pos| Message::Seek(fullscreen_vid.id, pos)
+                .push(
+                    slider(
+                        0.0..=fullscreen_vid.duration,
+                        fullscreen_vid.position,
+                        move |pos| Message::Seek(fullscreen_vid.id, pos),
+                    )
+                    .step(0.1)
+                    .on_release(Message::SeekRelease(fullscreen_vid.id)),
                 )
-                .step(0.1)
-                .on_release(Message::SeekRelease(fullscreen_vid.id)),
                 // Control buttons (use cached state to avoid blocking GStreamer queries)
-                row![
-                    button(
-                        text(if fullscreen_vid.video.paused() { ">" } else { "||" })
+                .push(
+                    row![
+                        button(
+                            text(if fullscreen_vid.video.paused() {
+                                ">"
+                            } else {
+                                "||"
+                            })
                             .size(12)
                             .color(text_color)
-                    )
-                    .on_press(Message::TogglePause(fullscreen_vid.id))
-                    .padding(8)
-                    .width(Length::Shrink)
-                    .height(Length::Shrink),
-                    button(
-                        text(if fullscreen_vid.video.looping() {
-                            "↻"
-                        } else {
-                            "→"
-                        })
-                        .size(12)
-                        .color(text_color)
-                    )
-                    .on_press(Message::ToggleLoop(fullscreen_vid.id))
-                    .padding(8)
-                    .width(Length::Shrink)
-                    .height(Length::Shrink),
-                    button(
-                        text(if fullscreen_vid.video.muted() { "M" } else { "~" })
+                        )
+                        .on_press(Message::TogglePause(fullscreen_vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                        button(
+                            text(if fullscreen_vid.video.looping() {
+                                "↻"
+                            } else {
+                                "→"
+                            })
                             .size(12)
                             .color(text_color)
-                    )
-                    .on_press(Message::ToggleMute(fullscreen_vid.id))
-                    .padding(8)
-                    .width(Length::Shrink)
-                    .height(Length::Shrink),
-                    button(text("V").size(12).color(text_color))
-                        .on_press(Message::ToggleFullscreen(fullscreen_vid.id))
+                        )
+                        .on_press(Message::ToggleLoop(fullscreen_vid.id))
                         .padding(8)
                         .width(Length::Shrink)
                         .height(Length::Shrink),
-                    text(format!(
-                        "{}:{:02}",
-                        fullscreen_vid.position as u64 / 60,
-                        fullscreen_vid.position as u64 % 60
-                    ))
-                    .size(12)
-                    .color(text_color)
-                ]
+                        button(
+                            text(if fullscreen_vid.video.muted() {
+                                "M"
+                            } else {
+                                "~"
+                            })
+                            .size(12)
+                            .color(text_color)
+                        )
+                        .on_press(Message::ToggleMute(fullscreen_vid.id))
+                        .padding(8)
+                        .width(Length::Shrink)
+                        .height(Length::Shrink),
+                        button(text("V").size(12).color(text_color))
+                            .on_press(Message::ToggleFullscreen(fullscreen_vid.id))
+                            .padding(8)
+                            .width(Length::Shrink)
+                            .height(Length::Shrink),
+                        button(text("i").size(12).color(text_color))
+                            .on_press(Message::ToggleInfo(fullscreen_vid.id))
+                            .padding(8)
+                            .width(Length::Shrink)
+                            .height(Length::Shrink),
+                        build_zoom_button(fullscreen_vid.id, fullscreen_vid.view, text_color),
+                        build_fit_button(fullscreen_vid.id, fit, text_color),
+                        build_letterbox_button(fullscreen_vid, text_color),
+                        build_color_button(fullscreen_vid.id, text_color),
+                        build_scope_button(
+                            fullscreen_vid.id,
+                            fullscreen_vid.scope.as_ref(),
+                            text_color
+                        ),
+                        build_aid_buttons(fullscreen_vid.id, fullscreen_vid.aids, text_color),
+                        build_caption_button(
+                            fullscreen_vid.id,
+                            fullscreen_vid.show_caption,
+                            text_color
+                        ),
+                        build_still_button(fullscreen_vid.id, text_color),
+                        build_subtitle_button(fullscreen_vid, text_color),
+                        build_tracks_button(fullscreen_vid.id, text_color),
+                        button(text("◆").size(12).color(text_color))
+                            .on_press(Message::AddMarker(fullscreen_vid.id))
+                            .padding(8)
+                            .width(Length::Shrink)
+                            .height(Length::Shrink),
+                        button(text("<◆").size(12).color(text_color))
+                            .on_press(Message::PrevMarker(fullscreen_vid.id))
+                            .padding(8)
+                            .width(Length::Shrink)
+                            .height(Length::Shrink),
+                        button(text("◆>").size(12).color(text_color))
+                            .on_press(Message::NextMarker(fullscreen_vid.id))
+                            .padding(8)
+                            .width(Length::Shrink)
+                            .height(Length::Shrink),
+                        build_position_button(fullscreen_vid, app.settings.timecode, text_color),
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center)
+                    .width(Length::Shrink),
+                )
                 .spacing(5)
-                .align_y(alignment::Vertical::Center)
-                .width(Length::Shrink)
-            ]
-            .spacing(5)
-            .padding(10),
+                .padding(10),
         )
         .style(move |_theme: &Theme| container::Style {
             background: Some(Color::from_rgba(0.0, 0.0, 0.0, bg_alpha).into()),
@@ -571,18 +1761,23 @@ fn render_fullscreen_video<'a>(
     mouse_area(fullscreen_stack)
         .on_enter(Message::MediaHoverChanged(fullscreen_vid.id, true))
         .on_exit(Message::MediaHoverChanged(fullscreen_vid.id, false))
+        .on_press(Message::DragStart(fullscreen_vid.id)) // Pans while zoomed
         .on_move(move |point| Message::MouseMoved(fullscreen_vid.id, point))
+        .on_scroll(move |delta| Message::ZoomScroll(fullscreen_vid.id, delta))
         .into()
 }
 
 /// Render the fullscreen view for a single photo.
-fn render_fullscreen_photo<'a>(_app: &'a App, photo: &'a PhotoInstance) -> Element<'a, Message> {
-    let photo_view = container(
-        image(&photo.handle)
-            .content_fit(iced::ContentFit::Contain)
-            .width(Length::Fill)
-            .height(Length::Fill),
-    )
+fn render_fullscreen_photo<'a>(app: &'a App, photo: &'a PhotoInstance) -> Element<'a, Message> {
+    // Full resolution once loaded, the display-size decode until then
+    let fit = effective_fit(app, photo.fit);
+    let photo_view = container(photo_image(
+        photo,
+        photo.display_handle(),
+        Length::Fill,
+        fit,
+        app.scale_factor,
+    ))
     .width(Length::Fill)
     .height(Length::Fill)
     .center_x(Length::Fill)
@@ -590,6 +1785,15 @@ fn render_fullscreen_photo<'a>(_app: &'a App, photo: &'a PhotoInstance) -> Eleme
 
     let opacity = compute_ui_opacity(photo.last_mouse_activity);
     let mut fullscreen_stack = stack![photo_view];
+    if let Some(scope) = scope_overlay(photo.scope.as_ref()) {
+        fullscreen_stack = fullscreen_stack.push(scope);
+    }
+    if photo.show_caption {
+        fullscreen_stack = fullscreen_stack.push(caption_overlay(app, caption::photo_lines(photo)));
+    }
+    if let Some(crop) = crop_overlay_for(app, photo.id) {
+        fullscreen_stack = fullscreen_stack.push(crop);
+    }
 
     if opacity > 0.0 {
         let bg_alpha = 0.7 * opacity;
@@ -613,18 +1817,24 @@ fn render_fullscreen_photo<'a>(_app: &'a App, photo: &'a PhotoInstance) -> Eleme
         })
         .width(Length::Fill);
 
-        let bottom_bar = container(
+        let bottom_bar = container(photo_controls(
+            photo,
             row![
                 button(text("V").size(12).color(text_color))
                     .on_press(Message::ToggleFullscreen(photo.id))
                     .padding(8)
                     .width(Length::Shrink)
                     .height(Length::Shrink),
+                build_zoom_button(photo.id, photo.view, text_color),
+                build_fit_button(photo.id, fit, text_color),
+                build_scope_button(photo.id, photo.scope.as_ref(), text_color),
+                build_aid_buttons(photo.id, photo.aids, text_color),
+                build_caption_button(photo.id, photo.show_caption, text_color),
             ]
             .spacing(5)
-            .align_y(alignment::Vertical::Center)
-            .padding(10),
-        )
+            .align_y(alignment::Vertical::Center),
+            text_color,
+        ))
         .style(move |_theme: &Theme| container::Style {
             background: Some(Color::from_rgba(0.0, 0.0, 0.0, bg_alpha).into()),
             ..Default::default()
@@ -646,22 +1856,321 @@ fn render_fullscreen_photo<'a>(_app: &'a App, photo: &'a PhotoInstance) -> Eleme
     mouse_area(fullscreen_stack)
         .on_enter(Message::MediaHoverChanged(photo.id, true))
         .on_exit(Message::MediaHoverChanged(photo.id, false))
+        .on_press(Message::DragStart(photo.id)) // Pans while zoomed
         .on_move(move |point| Message::MouseMoved(photo.id, point))
+        .on_scroll(move |delta| Message::ZoomScroll(photo.id, delta))
+        .into()
+}
+
+/// One side of a comparison, contained in the window whatever the cell fit so both
+/// sides line up.
+fn compare_layer<'a>(item: &'a MediaItem) -> Element<'a, Message> {
+    // The scale factor only matters at 1:1
+    let content = match item {
+        MediaItem::Video(vid) => video_view(vid, FitMode::Contain, 1.0),
+        MediaItem::Photo(photo) => photo_image(
+            photo,
+            photo.display_handle(),
+            Length::Fill,
+            FitMode::Contain,
+            1.0,
+        ),
+        MediaItem::Pending(_) => text("Loading...").size(14).into(),
+    };
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
         .into()
 }
 
+/// A and B superimposed: A before the wipe line, B after it.
+fn compare_wipe<'a>(
+    a: &'a MediaItem,
+    b: &'a MediaItem,
+    wipe: f32,
+    axis: WipeAxis,
+) -> Element<'a, Message> {
+    let line_color = Color::from_rgba(1.0, 1.0, 1.0, 0.8);
+    responsive(move |size| {
+        let line = || {
+            container("").style(move |_theme: &Theme| container::Style {
+                background: Some(line_color.into()),
+                ..Default::default()
+            })
+        };
+        // B is laid out at the full window size and shifted back by the split, so the
+        // visible part lines up with A underneath
+        let full_b = container(compare_layer(b))
+            .width(Length::Fixed(size.width))
+            .height(Length::Fixed(size.height));
+        let (b_side, wipe_line): (Element<'a, Message>, Element<'a, Message>) = match axis {
+            WipeAxis::Vertical => {
+                let split = size.width * wipe;
+                let clipped = container(pin(full_b).x(-split))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .clip(true);
+                (
+                    row![container("").width(Length::Fixed(split)), clipped]
+                        .height(Length::Fill)
+                        .into(),
+                    row![
+                        container("").width(Length::Fixed((split - 1.0).max(0.0))),
+                        line().width(Length::Fixed(2.0)).height(Length::Fill),
+                    ]
+                    .height(Length::Fill)
+                    .into(),
+                )
+            }
+            WipeAxis::Horizontal => {
+                let split = size.height * wipe;
+                let clipped = container(pin(full_b).y(-split))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .clip(true);
+                (
+                    column![container("").height(Length::Fixed(split)), clipped]
+                        .width(Length::Fill)
+                        .into(),
+                    column![
+                        container("").height(Length::Fixed((split - 1.0).max(0.0))),
+                        line().width(Length::Fill).height(Length::Fixed(2.0)),
+                    ]
+                    .width(Length::Fill)
+                    .into(),
+                )
+            }
+        };
+        stack![compare_layer(a), b_side, wipe_line].into()
+    })
+    .into()
+}
+
+/// PSNR/SSIM results for the compared pair: summary, per-frame graph and export.
+fn build_quality_panel<'a>(analysis: &'a Analysis) -> Element<'a, Message> {
+    let status = match &analysis.result {
+        None => format!("Analyzing... {:.0}%", analysis.progress * 100.0),
+        Some(Ok(())) => "Done".to_string(),
+        Some(Err(e)) => format!("Failed: {}", e),
+    };
+    let summary = match quality::summarize(&analysis.frames) {
+        Some(s) => format!(
+            "{} frames  PSNR {:.2} dB (min {:.2})  SSIM {:.4} (min {:.4})",
+            s.frames, s.mean_psnr, s.min_psnr, s.mean_ssim, s.min_ssim
+        ),
+        None => "No frames scored yet".to_string(),
+    };
+    let finished = matches!(analysis.result, Some(Ok(())));
+
+    container(
+        column![
+            row![
+                text(summary).size(12).color(Color::WHITE),
+                container("").width(Length::Fill),
+                text(status).size(12).color(SELECTION_COLOR),
+                button(text("[Export]").size(12))
+                    .on_press_maybe(finished.then_some(Message::ExportQuality))
+                    .padding(5),
+                button(text("X").size(12))
+                    .on_press(Message::CloseQuality)
+                    .padding(5),
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center),
+            image(&analysis.graph)
+                .content_fit(iced::ContentFit::Fill)
+                .width(Length::Fill)
+                .height(Length::Fixed(120.0)),
+            text("PSNR 20-60 dB (green), SSIM 0.80-1.00 (blue)")
+                .size(10)
+                .color(Color::from_rgb(0.7, 0.7, 0.7)),
+        ]
+        .spacing(5)
+        .padding(10),
+    )
+    .style(|_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.7).into()),
+        ..Default::default()
+    })
+    .width(Length::Fill)
+    .into()
+}
+
+/// Render the compare view: two items split by a draggable wipe line, their difference
+/// or onion skin, or one of them whole while flickering between A and B.
+fn render_compare_view<'a>(app: &'a App, compare: &'a Compare) -> Element<'a, Message> {
+    let find = |id: usize| app.media.iter().find(|m| m.id() == id);
+    let (Some(a), Some(b)) = (find(compare.a), find(compare.b)) else {
+        return center(text("Compared media is no longer loaded")).into();
+    };
+
+    let media = match (compare.flicker, compare.mode, &compare.blended) {
+        (Some(CompareSide::A), _, _) => compare_layer(a),
+        (Some(CompareSide::B), _, _) => compare_layer(b),
+        (None, CompareMode::Wipe, _) => compare_wipe(a, b, compare.wipe, compare.axis),
+        (None, _, Some(blended)) => image(blended)
+            .content_fit(iced::ContentFit::Contain)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+        (None, _, None) => center(text("Blending...").size(14)).into(),
+    };
+
+    let bar_style = |_theme: &Theme| container::Style {
+        background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.7).into()),
+        ..Default::default()
+    };
+    let showing = match (compare.flicker, compare.mode) {
+        (Some(CompareSide::A), _) => "Showing A".to_string(),
+        (Some(CompareSide::B), _) => "Showing B".to_string(),
+        (None, CompareMode::Wipe) => "Drag to move the wipe".to_string(),
+        (None, CompareMode::Difference) => format!("Difference x{:.0}", compare.gain),
+        (None, CompareMode::Onion) => format!("B at {:.0}%", compare.opacity * 100.0),
+    };
+
+    let mut controls = row![
+        text(format!("A: {}  |  B: {}", a.filename(), b.filename()))
+            .size(14)
+            .color(Color::WHITE),
+        container("").width(Length::Fill),
+        text(showing).size(12).color(SELECTION_COLOR),
+    ]
+    .spacing(10)
+    .align_y(alignment::Vertical::Center)
+    .padding(10);
+
+    // Wipe direction, or the amount of the active blend
+    controls = match compare.mode {
+        CompareMode::Wipe => controls.push(
+            button(
+                text(match compare.axis {
+                    WipeAxis::Vertical => "[Wipe |]",
+                    WipeAxis::Horizontal => "[Wipe -]",
+                })
+                .size(12),
+            )
+            .on_press(Message::CycleWipeAxis)
+            .padding(5),
+        ),
+        CompareMode::Difference => controls.push(
+            slider(1.0..=32.0, compare.gain, Message::BlendAmountChanged)
+                .step(1.0)
+                .width(Length::Fixed(150.0)),
+        ),
+        CompareMode::Onion => controls.push(
+            slider(0.0..=1.0, compare.opacity, Message::BlendAmountChanged)
+                .step(0.05)
+                .width(Length::Fixed(150.0)),
+        ),
+    };
+
+    let top_bar = container(
+        controls
+            .push(
+                button(text(format!("[View: {}]", compare.mode.as_str())).size(12))
+                    .on_press(Message::CycleCompareMode)
+                    .padding(5),
+            )
+            .push({
+                let both_videos = a.is_video() && b.is_video();
+                button(text("[Analyze]").size(12))
+                    .on_press_maybe(both_videos.then_some(Message::StartQuality))
+                    .padding(5)
+            })
+            .push(
+                button(text("[A/B]").size(12))
+                    .on_press(Message::FlipCompare)
+                    .padding(5),
+            )
+            .push(
+                button(text("X").size(20).color(Color::WHITE))
+                    .on_press(Message::ToggleCompare)
+                    .padding(5)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+            ),
+    )
+    .style(bar_style)
+    .width(Length::Fill);
+
+    let mut overlay = column![top_bar, container("").height(Length::Fill)];
+    if let Some(analysis) = &app.quality {
+        overlay = overlay.push(build_quality_panel(analysis));
+    }
+
+    // Shared timeline, driven by the first video of the pair
+    let timeline = [a, b].into_iter().find_map(|m| match m {
+        MediaItem::Video(vid) => Some(vid),
+        _ => None,
+    });
+    if let Some(vid) = timeline {
+        let bottom_bar = container(
+            column![
+                slider(0.0..=vid.duration, vid.position, move |pos| {
+                    Message::Seek(vid.id, pos)
+                })
+                .step(0.1)
+                .on_release(Message::SeekRelease(vid.id)),
+                row![
+                    button(
+                        text(if vid.video.paused() { ">" } else { "||" })
+                            .size(12)
+                            .color(Color::WHITE)
+                    )
+                    .on_press(Message::TogglePause(vid.id))
+                    .padding(8)
+                    .width(Length::Shrink)
+                    .height(Length::Shrink),
+                    text(position_label(vid, app.settings.timecode))
+                        .size(12)
+                        .color(Color::WHITE),
+                ]
+                .spacing(5)
+                .align_y(alignment::Vertical::Center),
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .style(bar_style)
+        .width(Length::Fill);
+        overlay = overlay.push(bottom_bar);
+    }
+
+    mouse_area(stack![
+        media,
+        container(overlay).width(Length::Fill).height(Length::Fill)
+    ])
+    .on_press(Message::CompareWipeStart)
+    .on_move(Message::CompareMoved)
+    .into()
+}
+
+/// Page navigation, shown only when the grid spans more than one page.
+fn render_page_controls<'a>(app: &'a App) -> Element<'a, Message> {
+    let pages = app.page_count();
+    if pages <= 1 {
+        return row![].into();
+    }
+    row![
+        button(text("<<").size(16))
+            .on_press(Message::PrevPage)
+            .padding(5),
+        text(format!("Page {}/{}", app.page + 1, pages)).size(14),
+        button(text(">>").size(16))
+            .on_press(Message::NextPage)
+            .padding(5),
+    ]
+    .spacing(10)
+    .align_y(alignment::Vertical::Center)
+    .into()
+}
+
 /// Render the bottom control bar.
 fn render_controls_bar<'a>(app: &'a App) -> Element<'a, Message> {
-    let video_count = app
-        .media
-        .iter()
-        .filter(|m| matches!(m, MediaItem::Video(_)))
-        .count();
-    let photo_count = app
-        .media
-        .iter()
-        .filter(|m| matches!(m, MediaItem::Photo(_)))
-        .count();
+    let video_count = app.media.iter().filter(|m| m.is_video()).count();
+    let photo_count = app.media.len() - video_count;
 
     let count_text = match (video_count, photo_count) {
         (0, 0) => "No media".to_string(),
@@ -676,6 +2185,17 @@ fn render_controls_bar<'a>(app: &'a App) -> Element<'a, Message> {
         ),
     };
 
+    // Jump every video to a timecode, typed in place of the button
+    let goto: Element<'a, Message> = match app.timecode_entry.as_ref() {
+        Some(entry) if entry.video_id.is_none() => container(build_timecode_input(entry))
+            .width(Length::Fixed(360.0))
+            .into(),
+        _ => button(text("[Go to TC]").size(14))
+            .on_press(Message::OpenTimecodeEntry(None))
+            .padding(5)
+            .into(),
+    };
+
     container(
         row![
             button(text("<").size(16))
@@ -685,10 +2205,83 @@ fn render_controls_bar<'a>(app: &'a App) -> Element<'a, Message> {
             button(text(">").size(16))
                 .on_press(Message::IncreaseColumns)
                 .padding(5),
+            render_page_controls(app),
             container("").width(Length::Fill),
+            goto,
+            button(
+                text(if app.settings.timecode {
+                    "[Time: timecode]"
+                } else {
+                    "[Time: m:ss]"
+                })
+                .size(14)
+            )
+            .on_press(Message::ToggleTimecode)
+            .padding(5),
+            button(text(format!("[Fit: {}]", app.settings.fit_mode.as_str())).size(14))
+                .on_press(Message::CycleDefaultFit)
+                .padding(5),
+            button(
+                text(if app.color_bypass {
+                    "[Color: before]"
+                } else {
+                    "[Color: after]"
+                })
+                .size(14)
+            )
+            .on_press(Message::ToggleColorBypass)
+            .padding(5),
+            button(text(format!("[Zebra: {} IRE]", app.settings.zebra_ire)).size(14))
+                .on_press(Message::CycleZebraLevel)
+                .padding(5),
+            button(
+                text(format!(
+                    "[Caption: {}]",
+                    app.settings.caption_position.as_str()
+                ))
+                .size(14)
+            )
+            .on_press(Message::CycleCaptionPosition)
+            .padding(5),
+            button(
+                text(format!(
+                    "[Caption size: {}]",
+                    app.settings.caption_size.as_str()
+                ))
+                .size(14)
+            )
+            .on_press(Message::CycleCaptionSize)
+            .padding(5),
+            button(text(format!("[Compare {}/2]", app.selection.len())).size(14))
+                .on_press(Message::ToggleCompare)
+                .padding(5),
             button(text("[Browse]").size(14))
                 .on_press(Message::BrowseFile)
                 .padding(5),
+            button(text("[Export Markers]").size(14))
+                .on_press(Message::ExportMarkers)
+                .padding(5),
+            button(
+                text(match app.settings.framerate_mode {
+                    FramerateMode::Native => "[Native FPS]".to_string(),
+                    FramerateMode::Fixed(fps) => format!("[{} FPS]", fps),
+                })
+                .size(14)
+            )
+            .on_press(Message::ToggleFramerateMode)
+            .padding(5),
+            button(
+                text(format!(
+                    "[Hidden: {}]",
+                    app.settings.hidden_playback.as_str()
+                ))
+                .size(14)
+            )
+            .on_press(Message::CycleHiddenPlayback)
+            .padding(5),
+            button(text(format!("[Decoders: {}]", app.settings.decoders.label())).size(14))
+                .on_press(Message::CycleDecoderPreference)
+                .padding(5),
             text(count_text).size(12),
             {
                 if app.loading_count > 0 {
@@ -0,0 +1,145 @@
+//! Widget showing its content at a fixed size and offset, clipped to its bounds.
+//!
+//! Stock layouts clamp children to the space their parent has left, which shrinks
+//! zoomed media instead of letting it overflow the cell. [`Viewport`] lays its content
+//! out at the size it asks for, moves it by the offset and clips what falls outside.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{Tree, Widget};
+use iced::advanced::{Clipboard, Renderer as _, Shell};
+use iced::{Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
+
+pub struct Viewport<'a, Message> {
+    content: Element<'a, Message>,
+    size: Size,
+    offset: Point,
+}
+
+/// Show `content` at `size`, with its top-left corner at `offset` from the
+/// viewport's own (which fills the space it is given).
+pub fn viewport<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    size: Size,
+    offset: Point,
+) -> Viewport<'a, Message> {
+    Viewport {
+        content: content.into(),
+        size,
+        offset,
+    }
+}
+
+impl<Message> Widget<Message, Theme, Renderer> for Viewport<'_, Message> {
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fill, Length::Fill)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let content = self
+            .content
+            .as_widget_mut()
+            .layout(
+                &mut tree.children[0],
+                renderer,
+                &layout::Limits::new(Size::ZERO, self.size),
+            )
+            .move_to(self.offset);
+        layout::Node::with_children(limits.max(), vec![content])
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let Some(clip) = layout.bounds().intersection(viewport) else {
+            return;
+        };
+        let Some(content_layout) = layout.children().next() else {
+            return;
+        };
+        renderer.with_layer(clip, |renderer| {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                content_layout,
+                cursor,
+                &clip,
+            );
+        });
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        // Forwarded so the video player keeps receiving redraw events
+        let Some(content_layout) = layout.children().next() else {
+            return;
+        };
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            event,
+            content_layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let Some(content_layout) = layout.children().next() else {
+            return mouse::Interaction::None;
+        };
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            content_layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message: 'a> From<Viewport<'a, Message>> for Element<'a, Message> {
+    fn from(viewport: Viewport<'a, Message>) -> Self {
+        Element::new(viewport)
+    }
+}
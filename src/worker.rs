@@ -0,0 +1,116 @@
+//! Background threads that only need the newest job for each key.
+//!
+//! Scopes, blends and thumbnails are all requested faster than they can be produced
+//! while media plays or the grid pages. A job queued for a key that already has one
+//! waiting replaces it, so the thread always works on current frames and never drains
+//! a backlog of stale ones.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Jobs waiting for the thread, in the order their keys were first queued.
+struct Queue<K, J> {
+    jobs: Vec<(K, J)>,
+    closed: bool, // The worker was dropped; the thread exits once idle
+}
+
+struct Shared<K, J> {
+    queue: Mutex<Queue<K, J>>,
+    ready: Condvar,
+}
+
+/// A named thread running the newest queued job of each key.
+pub struct LatestWorker<K, J> {
+    shared: Arc<Shared<K, J>>,
+}
+
+impl<K: PartialEq + Send + 'static, J: Send + 'static> LatestWorker<K, J> {
+    /// Spawn the thread, which calls `run` for each job it takes.
+    pub fn spawn(name: &str, mut run: impl FnMut(K, J) + Send + 'static) -> Self {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                jobs: Vec::new(),
+                closed: false,
+            }),
+            ready: Condvar::new(),
+        });
+        let thread_shared = shared.clone();
+        std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                loop {
+                    let Ok(mut queue) = thread_shared.queue.lock() else {
+                        return;
+                    };
+                    while queue.jobs.is_empty() && !queue.closed {
+                        let Ok(woken) = thread_shared.ready.wait(queue) else {
+                            return;
+                        };
+                        queue = woken;
+                    }
+                    if queue.jobs.is_empty() {
+                        return;
+                    }
+                    // Jobs queued while these run replace each other until the next pass
+                    let jobs = std::mem::take(&mut queue.jobs);
+                    drop(queue);
+                    for (key, job) in jobs {
+                        run(key, job);
+                    }
+                }
+            })
+            .unwrap_or_else(|e| panic!("Failed to spawn {} thread: {}", name, e));
+        LatestWorker { shared }
+    }
+
+    /// Queue `job`, replacing any job still waiting for the same key.
+    pub fn request(&self, key: K, job: J) {
+        let Ok(mut queue) = self.shared.queue.lock() else {
+            return;
+        };
+        match queue.jobs.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, waiting)) => *waiting = job,
+            None => queue.jobs.push((key, job)),
+        }
+        self.shared.ready.notify_one();
+    }
+}
+
+impl<K, J> Drop for LatestWorker<K, J> {
+    fn drop(&mut self) {
+        if let Ok(mut queue) = self.shared.queue.lock() {
+            queue.closed = true;
+        }
+        self.shared.ready.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn queued_jobs_are_replaced_by_newer_ones_of_the_same_key() {
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let worker = LatestWorker::spawn("worker-test", move |key: usize, job: u32| {
+            if job == 0 {
+                // Hold the thread so the next jobs queue up behind this one
+                let _ = started_tx.send(());
+                let _ = release_rx.recv();
+            }
+            let _ = done_tx.send((key, job));
+        });
+
+        worker.request(0, 0);
+        started_rx.recv().unwrap();
+        worker.request(1, 1);
+        worker.request(2, 2);
+        worker.request(1, 3);
+        release_tx.send(()).unwrap();
+
+        let done: Vec<(usize, u32)> = (0..3).map(|_| done_rx.recv().unwrap()).collect();
+        assert_eq!(done, vec![(0, 0), (1, 3), (2, 2)]);
+    }
+}
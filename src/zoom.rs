@@ -0,0 +1,185 @@
+//! Zoom and pan for media cells.
+//!
+//! A [`ZoomView`] stores a zoom factor over the contain-fitted size and the
+//! normalized point of the media shown at the cell center, so the same view can
+//! be mirrored to cells of different sizes and aspect ratios.
+
+use iced::{Point, Size, Vector};
+
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 16.0;
+/// Zoom factor per wheel line.
+const ZOOM_STEP: f32 = 1.15;
+/// Pixels of smooth scrolling treated as one wheel line.
+const PIXELS_PER_LINE: f32 = 50.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZoomView {
+    pub zoom: f32,
+    pub center: Point, // Normalized (0..1) media coordinates at the cell center
+}
+
+impl Default for ZoomView {
+    fn default() -> Self {
+        ZoomView {
+            zoom: MIN_ZOOM,
+            center: Point::new(0.5, 0.5),
+        }
+    }
+}
+
+impl ZoomView {
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > MIN_ZOOM
+    }
+
+    /// Size and top-left offset of the media within a `cell` for a `source` of the given
+    /// pixel size. Offsets keep the media covering the cell wherever it is larger than it.
+    pub fn layout(&self, cell: Size, source: (u32, u32)) -> (Size, Point) {
+        let (src_w, src_h) = (source.0.max(1) as f32, source.1.max(1) as f32);
+        let fit = (cell.width / src_w).min(cell.height / src_h);
+        let size = Size::new(src_w * fit * self.zoom, src_h * fit * self.zoom);
+        let axis = |cell: f32, size: f32, center: f32| {
+            if size <= cell {
+                (cell - size) / 2.0
+            } else {
+                (cell / 2.0 - center * size).clamp(cell - size, 0.0)
+            }
+        };
+        let offset = Point::new(
+            axis(cell.width, size.width, self.center.x),
+            axis(cell.height, size.height, self.center.y),
+        );
+        (size, offset)
+    }
+
+    /// Zoom by `lines` wheel steps, keeping the media point under `cursor` in place.
+    pub fn zoom_at(&mut self, lines: f32, cursor: Point, cell: Size, source: (u32, u32)) {
+        let (size, offset) = self.layout(cell, source);
+        let anchor = Point::new(
+            (cursor.x - offset.x) / size.width,
+            (cursor.y - offset.y) / size.height,
+        );
+        let zoom = (self.zoom * ZOOM_STEP.powf(lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+        let new_size = Size::new(
+            size.width / self.zoom * zoom,
+            size.height / self.zoom * zoom,
+        );
+        let new_offset = Point::new(
+            cursor.x - anchor.x * new_size.width,
+            cursor.y - anchor.y * new_size.height,
+        );
+        self.zoom = zoom;
+        self.center = Point::new(
+            (cell.width / 2.0 - new_offset.x) / new_size.width,
+            (cell.height / 2.0 - new_offset.y) / new_size.height,
+        );
+        self.clamp_center(cell, source);
+    }
+
+    /// Move the media by `delta` cell pixels.
+    pub fn pan_by(&mut self, delta: Vector, cell: Size, source: (u32, u32)) {
+        let (size, _) = self.layout(cell, source);
+        self.center.x -= delta.x / size.width;
+        self.center.y -= delta.y / size.height;
+        self.clamp_center(cell, source);
+    }
+
+    /// Keep the center where the visible region stays within the media.
+    fn clamp_center(&mut self, cell: Size, source: (u32, u32)) {
+        let (size, _) = self.layout(cell, source);
+        let axis = |center: f32, cell: f32, size: f32| {
+            let half = cell / (2.0 * size);
+            if half >= 0.5 {
+                0.5
+            } else {
+                center.clamp(half, 1.0 - half)
+            }
+        };
+        self.center = Point::new(
+            axis(self.center.x, cell.width, size.width),
+            axis(self.center.y, cell.height, size.height),
+        );
+    }
+}
+
+/// Wheel lines for a scroll event (positive zooms in).
+pub fn scroll_lines(delta: iced::mouse::ScrollDelta) -> f32 {
+    match delta {
+        iced::mouse::ScrollDelta::Lines { y, .. } => y,
+        iced::mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: Size = Size {
+        width: 200.0,
+        height: 200.0,
+    };
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    /// Normalized media point under a cell position.
+    fn media_point(view: &ZoomView, at: Point, cell: Size, source: (u32, u32)) -> Point {
+        let (size, offset) = view.layout(cell, source);
+        Point::new(
+            (at.x - offset.x) / size.width,
+            (at.y - offset.y) / size.height,
+        )
+    }
+
+    #[test]
+    fn unzoomed_media_is_fitted_and_centered() {
+        let (size, offset) = ZoomView::default().layout(Size::new(200.0, 100.0), (400, 100));
+        assert_eq!(size, Size::new(200.0, 50.0));
+        assert_eq!(offset, Point::new(0.0, 25.0));
+    }
+
+    #[test]
+    fn zoomed_media_is_offset_around_its_center() {
+        let view = ZoomView {
+            zoom: 2.0,
+            center: Point::new(0.5, 0.5),
+        };
+        let (size, offset) = view.layout(Size::new(200.0, 100.0), (400, 100));
+        assert_eq!(size, Size::new(400.0, 100.0));
+        assert_eq!(offset, Point::new(-100.0, 0.0));
+    }
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let mut view = ZoomView::default();
+        let cursor = Point::new(50.0, 70.0);
+        let before = media_point(&view, cursor, SQUARE, (200, 200));
+        view.zoom_at(3.0, cursor, SQUARE, (200, 200));
+        let after = media_point(&view, cursor, SQUARE, (200, 200));
+        assert!(view.is_zoomed());
+        assert!(close(before.x, after.x) && close(before.y, after.y));
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut view = ZoomView::default();
+        view.zoom_at(-5.0, Point::new(10.0, 10.0), SQUARE, (200, 200));
+        assert_eq!(view, ZoomView::default());
+        view.zoom_at(100.0, Point::new(100.0, 100.0), SQUARE, (200, 200));
+        assert_eq!(view.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn pan_stops_at_the_media_edge() {
+        let mut view = ZoomView {
+            zoom: 2.0,
+            center: Point::new(0.5, 0.5),
+        };
+        view.pan_by(Vector::new(1000.0, -1000.0), SQUARE, (200, 200));
+        assert!(close(view.center.x, 0.25) && close(view.center.y, 0.75));
+        let (_, offset) = view.layout(SQUARE, (200, 200));
+        assert!(close(offset.x, 0.0) && close(offset.y, -200.0));
+    }
+}